@@ -0,0 +1,36 @@
+use bevy::{core_pipeline::bloom::BloomSettings, prelude::*};
+use bevy_infinite_grid::{InfiniteGridBundle, InfiniteGridPlugin, InfiniteGridSettings};
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            InfiniteGridPlugin {
+                shadows: false,
+                ..default()
+            },
+        ))
+        .insert_resource(ClearColor(Color::BLACK))
+        .add_systems(Startup, setup_system)
+        .run();
+}
+
+fn setup_system(mut commands: Commands) {
+    // Linear colors above 1 only bloom on an HDR camera.
+    commands.spawn(InfiniteGridBundle {
+        settings: InfiniteGridSettings::neon(),
+        ..default()
+    });
+
+    commands.spawn((
+        Camera3dBundle {
+            camera: Camera {
+                hdr: true,
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 4.37, 14.77).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        BloomSettings::NATURAL,
+    ));
+}
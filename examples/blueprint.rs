@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+use bevy_infinite_grid::{InfiniteGridBundle, InfiniteGridPlugin, InfiniteGridSettings, LineStyle};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, InfiniteGridPlugin::default()))
+        .insert_resource(ClearColor(Color::rgb(0.05, 0.2, 0.45)))
+        .add_systems(Startup, setup_system)
+        .run();
+}
+
+fn setup_system(mut commands: Commands) {
+    // Faint solid minor lines under dashed major lines, like a technical drawing.
+    commands.spawn(InfiniteGridBundle {
+        settings: InfiniteGridSettings {
+            x_axis_color: Color::WHITE,
+            z_axis_color: Color::WHITE,
+            minor_line_color: Color::rgba(1., 1., 1., 0.15),
+            major_line_color: Color::rgba(1., 1., 1., 0.8),
+            major_line_style: LineStyle::Dashed {
+                dash: 0.6,
+                gap: 0.4,
+            },
+            shadow: None,
+            ..default()
+        },
+        ..default()
+    });
+
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(6., 8., 18.).looking_at(Vec3::new(0., 0., -10.), Vec3::Y),
+        ..default()
+    });
+}
@@ -0,0 +1,46 @@
+use bevy::prelude::*;
+use bevy_infinite_grid::{InfiniteGridBundle, InfiniteGridPlugin, InfiniteGridSettings};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, InfiniteGridPlugin::default()))
+        .add_systems(Startup, setup_system)
+        .run();
+}
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // An 8 by 8 board centered on the entity, the colored axes run along its corner.
+    commands.spawn(InfiniteGridBundle {
+        settings: InfiniteGridSettings {
+            grid_origin_offset: Vec2::splat(-4.),
+            shadow: None,
+            ..default()
+        },
+        ..default()
+    });
+
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(Plane3d::default().mesh().size(8., 8.)),
+        material: standard_materials.add(StandardMaterial {
+            base_color: Color::rgba(0.6, 0.5, 0.3, 0.3),
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        }),
+        transform: Transform::from_xyz(0., -0.01, 0.),
+        ..default()
+    });
+
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(0., 10., 10.).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_translation(Vec3::X * 15. + Vec3::Y * 20.)
+            .looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+}
@@ -0,0 +1,50 @@
+use bevy::prelude::*;
+use bevy_infinite_grid::{
+    GridShadowCamera, InfiniteGridBundle, InfiniteGridPlugin, InfiniteGridSettings,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, InfiniteGridPlugin::default()))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, bob_system)
+        .run();
+}
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn(InfiniteGridBundle {
+        settings: InfiniteGridSettings {
+            cull_underside: true,
+            ..default()
+        },
+        ..default()
+    });
+
+    commands.spawn((Camera3dBundle::default(), GridShadowCamera));
+
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_translation(Vec3::X * 15. + Vec3::Y * 20.)
+            .looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(Cuboid::new(1., 1., 1.)),
+        material: standard_materials.add(StandardMaterial::default()),
+        transform: Transform::from_xyz(0., 1., 0.),
+        ..default()
+    });
+}
+
+// Moves the camera through the grid, which should vanish while the camera is below it.
+fn bob_system(time: Res<Time>, mut cameras: Query<&mut Transform, With<Camera>>) {
+    let height = (time.elapsed_seconds() * 0.5).sin() * 6.;
+    for mut transform in cameras.iter_mut() {
+        *transform =
+            Transform::from_xyz(0., height, 12.).looking_at(Vec3::new(0., 1., 0.), Vec3::Y);
+    }
+}
@@ -0,0 +1,187 @@
+use bevy::{
+    core_pipeline::{core_3d::graph::Core3d, tonemapping::Tonemapping},
+    math::Vec3A,
+    prelude::*,
+    render::{
+        camera::{camera_system, CameraProjection, CameraProjectionPlugin, CameraRenderGraph},
+        primitives::Frustum,
+        view::{update_frusta, VisibilitySystems, VisibleEntities},
+    },
+    transform::TransformSystem,
+};
+use bevy_infinite_grid::{GridShadowCamera, InfiniteGridBundle, InfiniteGridPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            InfiniteGridPlugin::default(),
+            CameraProjectionPlugin::<SkewedProjection>::default(),
+        ))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, toggle_system)
+        .add_systems(
+            PostUpdate,
+            update_frusta::<SkewedProjection>
+                .after(camera_system::<SkewedProjection>)
+                .after(TransformSystem::TransformPropagate)
+                .before(VisibilitySystems::CheckVisibility),
+        )
+        .run();
+}
+
+/// Projections whose matrices have off-diagonal terms, which the grid has to unproject through
+/// without assuming a standard perspective or orthographic shape.
+#[derive(Component, Clone, Copy, Reflect)]
+#[reflect(Component, Default)]
+enum SkewedProjection {
+    /// Orthographic, with the rays slanted to the side and up for a fake 3D look on a 2D scene.
+    Oblique { aspect: f32 },
+    /// Perspective with the eye off the view's center, like a lens shift.
+    OffCenter { aspect: f32 },
+}
+
+impl Default for SkewedProjection {
+    fn default() -> Self {
+        Self::Oblique { aspect: 1. }
+    }
+}
+
+impl SkewedProjection {
+    const FAR: f32 = 1000.;
+
+    fn toggled(self) -> Self {
+        match self {
+            Self::Oblique { aspect } => Self::OffCenter { aspect },
+            Self::OffCenter { aspect } => Self::Oblique { aspect },
+        }
+    }
+}
+
+impl CameraProjection for SkewedProjection {
+    fn get_projection_matrix(&self) -> Mat4 {
+        match *self {
+            Self::Oblique { aspect } => {
+                let half_height = 10.;
+                let orthographic = Mat4::orthographic_rh(
+                    -half_height * aspect,
+                    half_height * aspect,
+                    -half_height,
+                    half_height,
+                    Self::FAR,
+                    0.,
+                );
+                // Moves points sideways and up with their depth.
+                let shear =
+                    Mat4::from_cols(Vec4::X, Vec4::Y, Vec4::new(-0.4, -0.3, 1., 0.), Vec4::W);
+                orthographic * shear
+            }
+            Self::OffCenter { aspect } => {
+                let shift = Mat4::from_translation(Vec3::new(0.5, 0.3, 0.));
+                shift * Mat4::perspective_infinite_reverse_rh(0.8, aspect, 0.1)
+            }
+        }
+    }
+
+    fn update(&mut self, width: f32, height: f32) {
+        match self {
+            Self::Oblique { aspect } | Self::OffCenter { aspect } => *aspect = width / height,
+        }
+    }
+
+    fn far(&self) -> f32 {
+        Self::FAR
+    }
+
+    fn get_frustum_corners(&self, z_near: f32, z_far: f32) -> [Vec3A; 8] {
+        let inverse = self.get_projection_matrix().inverse();
+        let projection = self.get_projection_matrix();
+        let ndc_depth = |z: f32| projection.project_point3(Vec3::new(0., 0., z)).z;
+        let mut corners = [Vec3A::ZERO; 8];
+        for (i, corner) in corners.iter_mut().enumerate() {
+            let depth = if i < 4 { z_near } else { z_far };
+            let (x, y) = match i % 4 {
+                0 => (1., -1.),
+                1 => (1., 1.),
+                2 => (-1., 1.),
+                _ => (-1., -1.),
+            };
+            *corner = inverse
+                .project_point3(Vec3::new(x, y, ndc_depth(depth)))
+                .into();
+        }
+        corners
+    }
+}
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn(InfiniteGridBundle::default());
+
+    // A `Camera3dBundle` without its `Projection`, which would fight over the projection matrix.
+    commands.spawn((
+        Camera::default(),
+        CameraRenderGraph::new(Core3d),
+        SkewedProjection::default(),
+        VisibleEntities::default(),
+        Frustum::default(),
+        Camera3d::default(),
+        Tonemapping::default(),
+        TransformBundle::from_transform(
+            Transform::from_xyz(0., 12., 14.).looking_at(Vec3::ZERO, Vec3::Y),
+        ),
+        GridShadowCamera,
+    ));
+
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_translation(Vec3::X * 15. + Vec3::Y * 20.)
+            .looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    let mesh = meshes.add(Cuboid::new(1., 1., 1.));
+    let material = standard_materials.add(StandardMaterial::default());
+    // Cubes centered on grid cells, their bases should sit on the lines with either projection.
+    for (x, z) in [(0.5, 0.5), (3.5, -2.5), (-4.5, 1.5), (-1.5, -5.5)] {
+        commands.spawn(PbrBundle {
+            mesh: mesh.clone(),
+            material: material.clone(),
+            transform: Transform::from_xyz(x, 0.5, z),
+            ..default()
+        });
+    }
+
+    commands.spawn(
+        TextBundle::from_section(
+            "Space: switch projection (oblique orthographic)",
+            TextStyle::default(),
+        )
+        .with_style(Style {
+            margin: UiRect::all(Val::Px(8.)),
+            ..default()
+        }),
+    );
+}
+
+fn toggle_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut projections: Query<&mut SkewedProjection>,
+    mut texts: Query<&mut Text>,
+) {
+    if !keys.just_pressed(KeyCode::Space) {
+        return;
+    }
+    for mut projection in projections.iter_mut() {
+        *projection = projection.toggled();
+        let name = match *projection {
+            SkewedProjection::Oblique { .. } => "oblique orthographic",
+            SkewedProjection::OffCenter { .. } => "off-center perspective",
+        };
+        for mut text in texts.iter_mut() {
+            text.sections[0].value = format!("Space: switch projection ({name})");
+        }
+    }
+}
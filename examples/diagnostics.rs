@@ -0,0 +1,116 @@
+use bevy::{
+    diagnostic::{DiagnosticPath, DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    prelude::*,
+};
+use bevy_infinite_grid::{
+    GlobalInfiniteGridSettings, GridQuality, GridShadowCamera, InfiniteGridBundle,
+    InfiniteGridDiagnosticsPlugin, InfiniteGridPlugin,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            InfiniteGridPlugin::default(),
+            InfiniteGridDiagnosticsPlugin,
+            FrameTimeDiagnosticsPlugin,
+        ))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, (quality_system, update_hud))
+        .run();
+}
+
+#[derive(Component)]
+struct Hud;
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // A few stacked grids so the counters have something to show.
+    for i in 0..3 {
+        commands.spawn(InfiniteGridBundle {
+            transform: Transform::from_xyz(0., i as f32 * -2., 0.),
+            ..default()
+        });
+    }
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 4.37, 14.77).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        GridShadowCamera,
+    ));
+
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_translation(Vec3::X * 15. + Vec3::Y * 20.)
+            .looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    commands.spawn(PbrBundle {
+        material: standard_materials.add(StandardMaterial::default()),
+        mesh: meshes.add(Cuboid::new(2., 2., 2.)),
+        transform: Transform::from_xyz(0.0, 2.0, 0.0),
+        ..default()
+    });
+
+    commands.spawn((
+        TextBundle::from_section("", TextStyle::default()).with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.),
+            left: Val::Px(10.),
+            ..default()
+        }),
+        Hud,
+    ));
+}
+
+// At `Low` the shadow passes stop and the shadow texture memory drops to zero.
+fn quality_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<GlobalInfiniteGridSettings>,
+) {
+    if keys.just_pressed(KeyCode::KeyQ) {
+        settings.quality = match settings.quality {
+            GridQuality::High => GridQuality::Medium,
+            GridQuality::Medium => GridQuality::Low,
+            GridQuality::Low => GridQuality::High,
+        };
+    }
+}
+
+fn update_hud(
+    diagnostics: Res<DiagnosticsStore>,
+    settings: Res<GlobalInfiniteGridSettings>,
+    mut hud: Query<&mut Text, With<Hud>>,
+) {
+    let value = |path: &DiagnosticPath| {
+        diagnostics
+            .get(path)
+            .and_then(|diagnostic| diagnostic.value())
+            .unwrap_or_default()
+    };
+    // Devices without timestamp queries never measure it.
+    let gpu_ms = diagnostics
+        .get(&InfiniteGridDiagnosticsPlugin::SHADOW_PASS_GPU_MS)
+        .and_then(|diagnostic| diagnostic.smoothed())
+        .map_or("n/a".to_string(), |ms| format!("{ms:.3} ms"));
+    for mut text in hud.iter_mut() {
+        text.sections[0].value = format!(
+            "Q: quality ({:?})\nfps: {:.0}\ngrids: {}\nshadow casters: {}\nshadow draws: {}\nshadow passes: {}\nshadow textures: {:.1} MiB\nshadow cpu: {:.3} ms\nshadow gpu: {}",
+            settings.quality,
+            value(&FrameTimeDiagnosticsPlugin::FPS),
+            value(&InfiniteGridDiagnosticsPlugin::GRIDS),
+            value(&InfiniteGridDiagnosticsPlugin::SHADOW_CASTERS),
+            value(&InfiniteGridDiagnosticsPlugin::SHADOW_DRAWS),
+            value(&InfiniteGridDiagnosticsPlugin::SHADOW_PASSES),
+            value(&InfiniteGridDiagnosticsPlugin::SHADOW_TEXTURE_BYTES) / (1024. * 1024.),
+            value(&InfiniteGridDiagnosticsPlugin::SHADOW_QUEUE_CPU_MS)
+                + value(&InfiniteGridDiagnosticsPlugin::SHADOW_BATCH_CPU_MS),
+            gpu_ms,
+        );
+    }
+}
@@ -0,0 +1,26 @@
+use bevy::prelude::*;
+use bevy_infinite_grid::{InfiniteGridBundle, InfiniteGridPlugin, InfiniteGridSettings};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, InfiniteGridPlugin::default()))
+        .add_systems(Startup, setup_system)
+        .run();
+}
+
+fn setup_system(mut commands: Commands) {
+    // A runway heading along Z: the lines across it stand out, the ones along it fade back.
+    commands.spawn(InfiniteGridBundle {
+        settings: InfiniteGridSettings {
+            x_lines_tint: Color::rgb_linear(3., 3., 3.),
+            z_lines_tint: Color::rgba_linear(1., 1., 1., 0.3),
+            ..default()
+        },
+        ..default()
+    });
+
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(3., 2., 12.).looking_at(Vec3::new(0., 0., -20.), Vec3::Y),
+        ..default()
+    });
+}
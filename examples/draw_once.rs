@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+use bevy_infinite_grid::{InfiniteGridGizmos, InfiniteGridPlugin, InfiniteGridSettings};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, InfiniteGridPlugin::default()))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, draw_system)
+        .run();
+}
+
+fn setup_system(mut commands: Commands) {
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(0.0, 6., 16.).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    commands.spawn(
+        TextBundle::from_section("Hold space to show the grid", TextStyle::default()).with_style(
+            Style {
+                margin: UiRect::all(Val::Px(8.)),
+                ..default()
+            },
+        ),
+    );
+}
+
+// No grid entity exists, the grid is only there on the frames it's drawn, bobbing up and down.
+fn draw_system(
+    time: Res<Time>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut grids: ResMut<InfiniteGridGizmos>,
+) {
+    if !keys.pressed(KeyCode::Space) {
+        return;
+    }
+    let height = time.elapsed_seconds().sin();
+    grids.draw_once(
+        Transform::from_xyz(0., height, 0.),
+        InfiniteGridSettings {
+            shadow: None,
+            ..default()
+        },
+    );
+}
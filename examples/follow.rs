@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+use bevy_infinite_grid::{GridFollow, InfiniteGridBundle, InfiniteGridPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, InfiniteGridPlugin::default()))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, run_system)
+        .run();
+}
+
+#[derive(Component)]
+struct Player;
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let player = commands
+        .spawn((
+            PbrBundle {
+                material: standard_materials.add(StandardMaterial::default()),
+                mesh: meshes.add(Cuboid::new(1., 1., 1.)),
+                transform: Transform::from_xyz(0.0, 0.5, 0.0),
+                ..default()
+            },
+            Player,
+        ))
+        .with_children(|player| {
+            player.spawn(Camera3dBundle {
+                transform: Transform::from_xyz(0.0, 4.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y),
+                ..default()
+            });
+        })
+        .id();
+
+    commands.spawn((
+        InfiniteGridBundle {
+            transform: Transform::from_rotation(Quat::from_rotation_y(0.3)),
+            ..default()
+        },
+        GridFollow { target: player },
+    ));
+
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_translation(Vec3::X * 15. + Vec3::Y * 20.)
+            .looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+}
+
+// The player runs away from the origin, the grid lines should still stand still under it.
+fn run_system(time: Res<Time>, mut players: Query<&mut Transform, With<Player>>) {
+    for mut transform in players.iter_mut() {
+        transform.translation.z -= 200. * time.delta_seconds();
+    }
+}
@@ -0,0 +1,67 @@
+use bevy::{core_pipeline::bloom::BloomSettings, prelude::*};
+use bevy_infinite_grid::{InfiniteGridBundle, InfiniteGridPlugin, InfiniteGridSettings};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, InfiniteGridPlugin::default()))
+        .insert_resource(ClearColor(Color::BLACK))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, toggle_system)
+        .run();
+}
+
+fn setup_system(mut commands: Commands) {
+    // Cyan lines above 1 keep glowing through bloom, the halo softens them without it too.
+    commands.spawn(InfiniteGridBundle {
+        settings: InfiniteGridSettings {
+            x_axis_color: Color::rgb_linear(0., 4., 4.),
+            z_axis_color: Color::rgb_linear(0., 4., 4.),
+            minor_line_color: Color::rgba_linear(0., 0.3, 0.4, 0.5),
+            major_line_color: Color::rgb_linear(0., 1.5, 2.),
+            glow: 12.,
+            shadow: None,
+            ..default()
+        },
+        ..default()
+    });
+
+    commands.spawn((
+        Camera3dBundle {
+            camera: Camera {
+                hdr: true,
+                ..default()
+            },
+            transform: Transform::from_xyz(4., 3., 14.)
+                .looking_at(Vec3::new(0., 0., -10.), Vec3::Y),
+            ..default()
+        },
+        BloomSettings::default(),
+    ));
+
+    commands.spawn(
+        TextBundle::from_section("Space: toggle HDR and bloom (on)", TextStyle::default())
+            .with_style(Style {
+                margin: UiRect::all(Val::Px(8.)),
+                ..default()
+            }),
+    );
+}
+
+fn toggle_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut cameras: Query<&mut Camera>,
+    mut texts: Query<&mut Text>,
+) {
+    if !keys.just_pressed(KeyCode::Space) {
+        return;
+    }
+    for mut camera in cameras.iter_mut() {
+        camera.hdr = !camera.hdr;
+        for mut text in texts.iter_mut() {
+            text.sections[0].value = format!(
+                "Space: toggle HDR and bloom ({})",
+                if camera.hdr { "on" } else { "off" }
+            );
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use bevy::{input::mouse::MouseWheel, prelude::*};
+use bevy_infinite_grid::{InfiniteGrid2dBundle, InfiniteGrid2dPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, InfiniteGrid2dPlugin))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, (pan_system, zoom_system))
+        .run();
+}
+
+fn setup_system(mut commands: Commands) {
+    // Below the sprites, which sort in front of it by their larger Z.
+    commands.spawn(InfiniteGrid2dBundle::at_z(-1.));
+
+    commands.spawn(Camera2dBundle::default());
+
+    for (x, y, color) in [
+        (100., 50., Color::ORANGE_RED),
+        (-150., 120., Color::SEA_GREEN),
+        (25., -200., Color::GOLD),
+    ] {
+        commands.spawn(SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(Vec2::splat(50.)),
+                ..default()
+            },
+            transform: Transform::from_xyz(x, y, 0.),
+            ..default()
+        });
+    }
+
+    commands.spawn(
+        TextBundle::from_section(
+            "Drag with the left mouse button to pan, scroll to zoom",
+            TextStyle::default(),
+        )
+        .with_style(Style {
+            margin: UiRect::all(Val::Px(8.)),
+            ..default()
+        }),
+    );
+}
+
+fn pan_system(
+    buttons: Res<ButtonInput<MouseButton>>,
+    mut cursor: EventReader<CursorMoved>,
+    mut cameras: Query<(&mut Transform, &OrthographicProjection)>,
+) {
+    for moved in cursor.read() {
+        let Some(delta) = moved.delta else {
+            continue;
+        };
+        if !buttons.pressed(MouseButton::Left) {
+            continue;
+        }
+        for (mut transform, projection) in cameras.iter_mut() {
+            // Screen Y points down, world Y up.
+            transform.translation += Vec3::new(-delta.x, delta.y, 0.) * projection.scale;
+        }
+    }
+}
+
+// From far out, where only the axes and major lines are left, to a few pixels per unit. The lines
+// stay as wide on screen at every zoom level.
+fn zoom_system(
+    mut wheel: EventReader<MouseWheel>,
+    mut projections: Query<&mut OrthographicProjection>,
+) {
+    let scroll: f32 = wheel.read().map(|event| event.y).sum();
+    if scroll == 0. {
+        return;
+    }
+    for mut projection in projections.iter_mut() {
+        projection.scale = (projection.scale * 1.1f32.powf(-scroll)).clamp(0.05, 200.);
+    }
+}
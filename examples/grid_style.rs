@@ -0,0 +1,24 @@
+use bevy::prelude::*;
+use bevy_infinite_grid::{GridSettingsHandle, InfiniteGridBundle, InfiniteGridPlugin};
+
+// Run with `--features serialize,bevy/file_watcher,bevy/multi-threaded` and edit
+// `assets/blueprint.gridstyle.ron` to see the grid follow the file.
+fn main() {
+    App::new()
+        .insert_resource(ClearColor(Color::rgb(0.05, 0.12, 0.3)))
+        .add_plugins((DefaultPlugins, InfiniteGridPlugin::default()))
+        .add_systems(Startup, setup_system)
+        .run();
+}
+
+fn setup_system(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        InfiniteGridBundle::default(),
+        GridSettingsHandle(asset_server.load("blueprint.gridstyle.ron")),
+    ));
+
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(0.0, 4.37, 14.77).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+}
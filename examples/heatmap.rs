@@ -0,0 +1,88 @@
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        texture::ImageSampler,
+    },
+};
+use bevy_infinite_grid::{GridHeatmap, InfiniteGridBundle, InfiniteGridPlugin};
+
+// Cells covered by the heatmap along each axis, one texel per cell.
+const CELLS: u32 = 16;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, InfiniteGridPlugin::default()))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, toggle_system)
+        .run();
+}
+
+fn setup_system(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    // A blue to red gradient along X, fading out along Z.
+    let data = (0..CELLS)
+        .flat_map(|z| {
+            (0..CELLS).flat_map(move |x| {
+                let t = x as f32 / (CELLS - 1) as f32;
+                let alpha = 1. - z as f32 / CELLS as f32;
+                Color::rgba(t, 0.2, 1. - t, alpha * 0.8).as_rgba_u8()
+            })
+        })
+        .collect();
+    let mut image = Image::new(
+        Extent3d {
+            width: CELLS,
+            height: CELLS,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    // Flat colors per cell, even if the heatmap is moved off the cell boundaries.
+    image.sampler = ImageSampler::nearest();
+
+    commands.spawn((
+        InfiniteGridBundle::default(),
+        GridHeatmap {
+            image: images.add(image),
+            origin: Vec2::splat(-(CELLS as f32) / 2.),
+            extent: Vec2::splat(CELLS as f32),
+            ..default()
+        },
+    ));
+
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(0., 14., 14.).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    commands.spawn(
+        TextBundle::from_section("Space: tint the lines too (off)", TextStyle::default())
+            .with_style(Style {
+                margin: UiRect::all(Val::Px(8.)),
+                ..default()
+            }),
+    );
+}
+
+fn toggle_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut heatmaps: Query<&mut GridHeatmap>,
+    mut texts: Query<&mut Text>,
+) {
+    if !keys.just_pressed(KeyCode::Space) {
+        return;
+    }
+    for mut heatmap in heatmaps.iter_mut() {
+        heatmap.tint_lines = !heatmap.tint_lines;
+        for mut text in texts.iter_mut() {
+            text.sections[0].value = format!(
+                "Space: tint the lines too ({})",
+                if heatmap.tint_lines { "on" } else { "off" }
+            );
+        }
+    }
+}
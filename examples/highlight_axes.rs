@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+use bevy_infinite_grid::{InfiniteGridBundle, InfiniteGridPlugin, InfiniteGridSettings};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, InfiniteGridPlugin::default()))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, toggle_system)
+        .run();
+}
+
+fn setup_system(mut commands: Commands) {
+    commands.spawn(InfiniteGridBundle {
+        settings: InfiniteGridSettings {
+            highlight_axes: false,
+            ..default()
+        },
+        ..default()
+    });
+
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(4., 6., 12.).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    commands.spawn(
+        TextBundle::from_section(
+            "Space: toggle axis highlighting (off)",
+            TextStyle::default(),
+        )
+        .with_style(Style {
+            margin: UiRect::all(Val::Px(8.)),
+            ..default()
+        }),
+    );
+}
+
+// With highlighting off the lines through the origin match the rest of the grid.
+fn toggle_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut grids: Query<&mut InfiniteGridSettings>,
+    mut texts: Query<&mut Text>,
+) {
+    if !keys.just_pressed(KeyCode::Space) {
+        return;
+    }
+    for mut settings in grids.iter_mut() {
+        settings.highlight_axes = !settings.highlight_axes;
+        for mut text in texts.iter_mut() {
+            text.sections[0].value = format!(
+                "Space: toggle axis highlighting ({})",
+                if settings.highlight_axes { "on" } else { "off" }
+            );
+        }
+    }
+}
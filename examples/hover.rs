@@ -0,0 +1,134 @@
+use bevy::prelude::*;
+use bevy_infinite_grid::{
+    GridCellChanged, GridPointerEvent, GridPointerEventKind, InfiniteGridBundle,
+    InfiniteGridPlugin, InfiniteGridSettings, NotGridHoverOccluder,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            InfiniteGridPlugin {
+                shadows: false,
+                ..default()
+            },
+        ))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, (drag_markers, move_cursor))
+        .run();
+}
+
+#[derive(Component)]
+struct Marker {
+    cell: IVec2,
+}
+
+#[derive(Component)]
+struct CellCursor;
+
+#[derive(Resource, Default)]
+struct Dragging(Option<Entity>);
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.init_resource::<Dragging>();
+
+    commands.spawn(InfiniteGridBundle {
+        settings: InfiniteGridSettings {
+            shadow: None,
+            ..default()
+        },
+        ..default()
+    });
+
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(0.0, 12.0, 12.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_translation(Vec3::X * 15. + Vec3::Y * 20.)
+            .looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Cuboid::new(1.0, 0.02, 1.0)),
+            material: standard_materials.add(Color::rgba(1.0, 1.0, 0.2, 0.5)),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        CellCursor,
+        NotGridHoverOccluder,
+    ));
+
+    let mesh = meshes.add(Sphere::new(0.35));
+    let material = standard_materials.add(Color::rgb(0.2, 0.7, 0.3));
+    for cell in [IVec2::new(-2, 1), IVec2::new(0, 0), IVec2::new(3, -2)] {
+        commands.spawn((
+            PbrBundle {
+                mesh: mesh.clone(),
+                material: material.clone(),
+                transform: Transform::from_translation(cell_center(cell)),
+                ..default()
+            },
+            Marker { cell },
+            // Markers sit on the grid, so let the cursor through to the cell underneath.
+            NotGridHoverOccluder,
+        ));
+    }
+}
+
+fn cell_center(cell: IVec2) -> Vec3 {
+    Vec3::new(cell.x as f32 + 0.5, 0.35, cell.y as f32 + 0.5)
+}
+
+fn drag_markers(
+    mut events: EventReader<GridPointerEvent>,
+    mut dragging: ResMut<Dragging>,
+    mut markers: Query<(Entity, &mut Marker, &mut Transform)>,
+) {
+    for event in events.read() {
+        match event.kind {
+            GridPointerEventKind::Down(MouseButton::Left) => {
+                dragging.0 = markers
+                    .iter()
+                    .find(|(_, marker, _)| marker.cell == event.hit.cell)
+                    .map(|(entity, ..)| entity);
+            }
+            GridPointerEventKind::Drag {
+                button: MouseButton::Left,
+                ..
+            } => {
+                if let Some(Ok((_, mut marker, mut transform))) =
+                    dragging.0.map(|entity| markers.get_mut(entity))
+                {
+                    marker.cell = event.hit.cell;
+                    transform.translation = cell_center(event.hit.cell);
+                }
+            }
+            GridPointerEventKind::Up(MouseButton::Left) => dragging.0 = None,
+            _ => {}
+        }
+    }
+}
+
+fn move_cursor(
+    mut events: EventReader<GridCellChanged>,
+    mut cursor: Query<(&mut Transform, &mut Visibility), With<CellCursor>>,
+) {
+    let (mut transform, mut visibility) = cursor.single_mut();
+    for event in events.read() {
+        match event.new {
+            Some(cell) => {
+                *visibility = Visibility::Visible;
+                transform.translation = cell_center(cell) * Vec3::new(1., 0., 1.);
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+}
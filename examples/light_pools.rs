@@ -0,0 +1,72 @@
+use bevy::prelude::*;
+use bevy_infinite_grid::{
+    CastsGridShadow, GridShadowCamera, GridShadowCasterFilter, GridShadowMode, GridShadowSettings,
+    InfiniteGridBundle, InfiniteGridPlugin, InfiniteGridSettings,
+};
+
+fn main() {
+    App::new()
+        .insert_resource(ClearColor(Color::rgb(0.02, 0.02, 0.03)))
+        .add_plugins((DefaultPlugins, InfiniteGridPlugin::default()))
+        .add_systems(Startup, setup_system)
+        .run();
+}
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // Only the lamps cast, and they light the grid up instead of darkening it.
+    commands.spawn(InfiniteGridBundle {
+        settings: InfiniteGridSettings {
+            shadow: Some(GridShadowSettings {
+                color: Color::rgba_linear(1.0, 0.6, 0.2, 0.5),
+                mode: GridShadowMode::Lighten,
+                caster_filter: GridShadowCasterFilter::Only,
+                softness: 0.5,
+                ..default()
+            }),
+            ..default()
+        },
+        ..default()
+    });
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 8., 14.).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        GridShadowCamera,
+    ));
+
+    let lamp = meshes.add(Sphere::new(1.5));
+    let lamp_material = standard_materials.add(StandardMaterial {
+        base_color: Color::rgb(1.0, 0.8, 0.5),
+        unlit: true,
+        ..default()
+    });
+    for x in [-6., 0., 6.] {
+        commands.spawn((
+            PbrBundle {
+                mesh: lamp.clone(),
+                material: lamp_material.clone(),
+                transform: Transform::from_xyz(x, 4., 0.),
+                ..default()
+            },
+            CastsGridShadow,
+        ));
+    }
+
+    // Untagged, so it casts nothing onto the grid.
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(Cuboid::new(2., 2., 2.)),
+        material: standard_materials.add(StandardMaterial::default()),
+        transform: Transform::from_xyz(3., 1., 4.),
+        ..default()
+    });
+    commands.spawn(PointLightBundle {
+        transform: Transform::from_xyz(0., 6., 6.),
+        ..default()
+    });
+}
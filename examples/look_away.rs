@@ -0,0 +1,59 @@
+use bevy::{
+    diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
+    prelude::*,
+};
+use bevy_infinite_grid::{
+    GridShadowCamera, InfiniteGridBundle, InfiniteGridDiagnosticsPlugin, InfiniteGridPlugin,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            InfiniteGridPlugin::default(),
+            InfiniteGridDiagnosticsPlugin,
+            FrameTimeDiagnosticsPlugin,
+            LogDiagnosticsPlugin::default(),
+        ))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, tilt_system)
+        .run();
+}
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn(InfiniteGridBundle::default());
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 4.37, 14.77),
+            ..default()
+        },
+        GridShadowCamera,
+    ));
+
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_translation(Vec3::X * 15. + Vec3::Y * 20.)
+            .looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(Cuboid::new(1., 1., 1.)),
+        material: standard_materials.add(StandardMaterial::default()),
+        transform: Transform::from_xyz(0., 2., 0.),
+        ..default()
+    });
+}
+
+// Pitches the camera from the grid up to the sky and back. While it only sees sky the grid gets
+// no shadow, the log shows the shadow passes dropping to 0.
+fn tilt_system(time: Res<Time>, mut cameras: Query<&mut Transform, With<GridShadowCamera>>) {
+    let pitch = (time.elapsed_seconds() * 0.5).sin() * 1.2 + 0.4;
+    for mut transform in cameras.iter_mut() {
+        transform.rotation = Quat::from_rotation_x(pitch);
+    }
+}
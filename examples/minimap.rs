@@ -0,0 +1,71 @@
+use bevy::{
+    prelude::*,
+    render::camera::{ClearColorConfig, ScalingMode, Viewport},
+};
+use bevy_infinite_grid::{
+    GridShadowCamera, InfiniteGridBundle, InfiniteGridPlugin, InfiniteGridViewOverrides,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, InfiniteGridPlugin::default()))
+        .add_systems(Startup, setup_system)
+        .run();
+}
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn(InfiniteGridBundle::default());
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 4.37, 14.77).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        GridShadowCamera,
+    ));
+
+    // A top-down minimap in the corner, seeing the same grid with bold majors only.
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0., 30., 0.).looking_at(Vec3::ZERO, Vec3::NEG_Z),
+            projection: Projection::Orthographic(OrthographicProjection {
+                scaling_mode: ScalingMode::FixedVertical(40.),
+                ..default()
+            }),
+            camera: Camera {
+                order: 1,
+                viewport: Some(Viewport {
+                    physical_position: UVec2::new(16, 16),
+                    physical_size: UVec2::new(256, 256),
+                    ..default()
+                }),
+                clear_color: ClearColorConfig::Custom(Color::rgb(0.05, 0.05, 0.08)),
+                ..default()
+            },
+            ..default()
+        },
+        InfiniteGridViewOverrides {
+            minor_line_color: Some(Color::NONE),
+            major_line_color: Some(Color::rgb_linear(0.6, 0.6, 0.6)),
+            aa_width: Some(2.),
+            ..default()
+        },
+    ));
+
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_translation(Vec3::X * 15. + Vec3::Y * 20.)
+            .looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    commands.spawn(PbrBundle {
+        material: standard_materials.add(StandardMaterial::default()),
+        mesh: meshes.add(Cuboid::new(2., 2., 2.)),
+        transform: Transform::from_xyz(0.0, 1.0, 0.0),
+        ..default()
+    });
+}
@@ -0,0 +1,41 @@
+use bevy::prelude::*;
+use bevy_infinite_grid::{InfiniteGridBundle, InfiniteGridPlugin, InfiniteGridSettings};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, InfiniteGridPlugin::default()))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, dolly_system)
+        .run();
+}
+
+fn setup_system(mut commands: Commands) {
+    // Fine lines reaching far towards the horizon, where dozens of them fall into each pixel.
+    // They settle into an even tone instead of a band of moire. Distinct major and minor colors
+    // blend smoothly into that tone too, without a ring where the majors take over.
+    commands.spawn(InfiniteGridBundle {
+        settings: InfiniteGridSettings {
+            minor_line_color: Color::rgb(0.3, 0.3, 0.6),
+            major_line_color: Color::rgb(0.9, 0.6, 0.2),
+            scale: 4.,
+            fadeout_distance: 400.,
+            minor_fadeout_ratio: 1.,
+            shadow: None,
+            ..default()
+        },
+        ..default()
+    });
+
+    // A steep, grazing view of the plane.
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(0.0, 1.5, 0.0).looking_at(Vec3::new(0., 0., -60.), Vec3::Y),
+        ..default()
+    });
+}
+
+// Slides the camera slowly over the grid, moire would crawl as it moves.
+fn dolly_system(time: Res<Time>, mut cameras: Query<&mut Transform, With<Camera>>) {
+    for mut transform in cameras.iter_mut() {
+        transform.translation.x = (time.elapsed_seconds() * 0.2).sin() * 2.;
+    }
+}
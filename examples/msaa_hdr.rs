@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+use bevy_infinite_grid::{GridShadowCamera, InfiniteGridBundle, InfiniteGridPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, InfiniteGridPlugin::default()))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, cycle_system)
+        .run();
+}
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn(InfiniteGridBundle::default());
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 4.37, 14.77).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        GridShadowCamera,
+    ));
+
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_translation(Vec3::X * 15. + Vec3::Y * 20.)
+            .looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    commands.spawn(PbrBundle {
+        material: standard_materials.add(StandardMaterial::default()),
+        mesh: meshes.add(Cuboid::new(2., 2., 2.)),
+        transform: Transform::from_xyz(0.0, 1.0, 0.0),
+        ..default()
+    });
+
+    commands.spawn(
+        TextBundle::from_section("", TextStyle::default()).with_style(Style {
+            margin: UiRect::all(Val::Px(8.)),
+            ..default()
+        }),
+    );
+}
+
+// Every few seconds, moves to the next MSAA sample count and flips HDR. The grid and its shadow
+// should stay on screen throughout. Like Bevy's own MSAA example, 2 and 8 samples need a device
+// supporting them.
+fn cycle_system(
+    time: Res<Time>,
+    mut timer: Local<Option<Timer>>,
+    mut msaa: ResMut<Msaa>,
+    mut cameras: Query<&mut Camera>,
+    mut texts: Query<&mut Text>,
+) {
+    let timer = timer.get_or_insert_with(|| Timer::from_seconds(3., TimerMode::Repeating));
+    if !timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    *msaa = match *msaa {
+        Msaa::Off => Msaa::Sample2,
+        Msaa::Sample2 => Msaa::Sample4,
+        Msaa::Sample4 => Msaa::Sample8,
+        Msaa::Sample8 => Msaa::Off,
+    };
+    for mut camera in cameras.iter_mut() {
+        camera.hdr = !camera.hdr;
+        for mut text in texts.iter_mut() {
+            text.sections[0].value = format!("MSAA: {:?}, HDR: {}", *msaa, camera.hdr);
+        }
+    }
+}
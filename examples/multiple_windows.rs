@@ -0,0 +1,86 @@
+use bevy::{prelude::*, render::camera::RenderTarget, window::WindowRef};
+use bevy_infinite_grid::{GridShadowCamera, InfiniteGridBundle, InfiniteGridPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, InfiniteGridPlugin::default()))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, orbit_system)
+        .run();
+}
+
+/// Circles the camera around the grid's origin, each window's camera at its own pace.
+#[derive(Component)]
+struct Orbit {
+    radius: f32,
+    height: f32,
+    speed: f32,
+}
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn(InfiniteGridBundle::default());
+
+    // Each shadow camera gets its own shadow texture, sized for its window.
+    commands.spawn((
+        Camera3dBundle::default(),
+        GridShadowCamera,
+        Orbit {
+            radius: 15.,
+            height: 4.4,
+            speed: 0.2,
+        },
+    ));
+
+    let second_window = commands
+        .spawn(Window {
+            title: "Second window".to_owned(),
+            resolution: (500., 800.).into(),
+            ..default()
+        })
+        .id();
+
+    commands.spawn((
+        Camera3dBundle {
+            camera: Camera {
+                target: RenderTarget::Window(WindowRef::Entity(second_window)),
+                ..default()
+            },
+            ..default()
+        },
+        GridShadowCamera,
+        Orbit {
+            radius: 11.,
+            height: 6.,
+            speed: -0.35,
+        },
+    ));
+
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_translation(Vec3::X * 15. + Vec3::Y * 20.)
+            .looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    commands.spawn(PbrBundle {
+        material: standard_materials.add(StandardMaterial::default()),
+        mesh: meshes.add(Cuboid::new(2., 2., 2.)),
+        transform: Transform::from_xyz(0.0, 2.0, 0.0),
+        ..default()
+    });
+}
+
+fn orbit_system(time: Res<Time>, mut cameras: Query<(&mut Transform, &Orbit)>) {
+    for (mut transform, orbit) in cameras.iter_mut() {
+        let angle = time.elapsed_seconds() * orbit.speed;
+        *transform = Transform::from_xyz(
+            angle.sin() * orbit.radius,
+            orbit.height,
+            angle.cos() * orbit.radius,
+        )
+        .looking_at(Vec3::ZERO, Vec3::Y);
+    }
+}
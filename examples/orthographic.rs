@@ -0,0 +1,83 @@
+use bevy::{prelude::*, render::camera::ScalingMode};
+use bevy_infinite_grid::{GridShadowCamera, InfiniteGridBundle, InfiniteGridPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, InfiniteGridPlugin::default()))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, toggle_system)
+        .run();
+}
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn(InfiniteGridBundle::default());
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(10., 10., 10.).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        GridShadowCamera,
+    ));
+
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_translation(Vec3::X * 15. + Vec3::Y * 20.)
+            .looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    let mesh = meshes.add(Cuboid::new(1., 1., 1.));
+    let material = standard_materials.add(StandardMaterial::default());
+    for (x, z) in [(-3., 0.), (3., 1.), (0., -3.), (1., 3.)] {
+        commands.spawn(PbrBundle {
+            mesh: mesh.clone(),
+            material: material.clone(),
+            transform: Transform::from_xyz(x, 1., z),
+            ..default()
+        });
+    }
+
+    commands.spawn(
+        TextBundle::from_section(
+            "Space: switch projection (perspective)",
+            TextStyle::default(),
+        )
+        .with_style(Style {
+            margin: UiRect::all(Val::Px(8.)),
+            ..default()
+        }),
+    );
+}
+
+// The grid, its fadeout and the shadows should look alike in both projections.
+fn toggle_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut cameras: Query<&mut Projection, With<Camera>>,
+    mut texts: Query<&mut Text>,
+) {
+    if !keys.just_pressed(KeyCode::Space) {
+        return;
+    }
+    for mut projection in cameras.iter_mut() {
+        let name = match *projection {
+            Projection::Perspective(_) => {
+                *projection = Projection::Orthographic(OrthographicProjection {
+                    scaling_mode: ScalingMode::FixedVertical(16.),
+                    ..default()
+                });
+                "orthographic"
+            }
+            Projection::Orthographic(_) => {
+                *projection = Projection::Perspective(default());
+                "perspective"
+            }
+        };
+        for mut text in texts.iter_mut() {
+            text.sections[0].value = format!("Space: switch projection ({name})");
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use bevy::{
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        render_resource::{
+            Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+        },
+    },
+};
+use bevy_infinite_grid::{InfiniteGridBundle, InfiniteGridPlugin, InfiniteGridSettings};
+
+const WIDTH: u32 = 320;
+const HEIGHT: u32 = 180;
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins.set(ImagePlugin::default_nearest()),
+            InfiniteGridPlugin::default(),
+        ))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, pan_system)
+        .run();
+}
+
+#[derive(Component)]
+struct Pan;
+
+fn setup_system(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let size = Extent3d {
+        width: WIDTH,
+        height: HEIGHT,
+        depth_or_array_layers: 1,
+    };
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: None,
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Bgra8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..default()
+    };
+    image.resize(size);
+    let image = images.add(image);
+
+    commands.spawn(InfiniteGridBundle {
+        settings: InfiniteGridSettings {
+            pixel_snap: true,
+            shadow: None,
+            ..default()
+        },
+        ..default()
+    });
+
+    // Renders the grid at 320x180...
+    commands.spawn((
+        Camera3dBundle {
+            camera: Camera {
+                target: RenderTarget::Image(image.clone()),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 4., 12.).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        Pan,
+    ));
+
+    // ...and shows it scaled up four times, without filtering.
+    commands.spawn(SpriteBundle {
+        texture: image,
+        transform: Transform::from_scale(Vec3::splat(4.)),
+        ..default()
+    });
+    commands.spawn(Camera2dBundle {
+        camera: Camera {
+            order: 1,
+            ..default()
+        },
+        ..default()
+    });
+}
+
+// Slides the camera slowly along the grid, lines should step from pixel to pixel without
+// shimmering.
+fn pan_system(time: Res<Time>, mut cameras: Query<&mut Transform, With<Pan>>) {
+    for mut transform in cameras.iter_mut() {
+        transform.translation.x = (time.elapsed_seconds() * 0.3).sin() * 5.;
+    }
+}
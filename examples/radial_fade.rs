@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+use bevy_infinite_grid::{
+    FadeMode, GridShadowCamera, InfiniteGridBundle, InfiniteGridPlugin, InfiniteGridSettings,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, InfiniteGridPlugin::default()))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, player_system)
+        .run();
+}
+
+#[derive(Component)]
+struct Player;
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn(InfiniteGridBundle {
+        settings: InfiniteGridSettings {
+            fadeout_distance: 8.,
+            fade_mode: FadeMode::RadialFromPoint { center: Vec2::ZERO },
+            ..default()
+        },
+        ..default()
+    });
+
+    // A steep 2.5D view, where the camera fadeout would leave the grid barely faded.
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0., 20., 12.).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        GridShadowCamera,
+    ));
+
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_translation(Vec3::X * 15. + Vec3::Y * 20.)
+            .looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Cuboid::new(1., 1., 1.)),
+            material: standard_materials.add(StandardMaterial::default()),
+            transform: Transform::from_xyz(0., 0.5, 0.),
+            ..default()
+        },
+        Player,
+    ));
+}
+
+// Walks the player around, the visible patch of grid follows it.
+fn player_system(
+    time: Res<Time>,
+    mut players: Query<&mut Transform, With<Player>>,
+    mut grids: Query<&mut InfiniteGridSettings>,
+) {
+    let angle = time.elapsed_seconds() * 0.5;
+    let position = Vec2::new(angle.cos() * 6., angle.sin() * 4.);
+    for mut transform in players.iter_mut() {
+        transform.translation = position.extend(0.5).xzy();
+    }
+    for mut settings in grids.iter_mut() {
+        settings.fade_mode = FadeMode::RadialFromPoint { center: position };
+    }
+}
@@ -0,0 +1,133 @@
+use bevy::{
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        render_resource::{
+            Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+        },
+    },
+};
+use bevy_infinite_grid::{
+    GridShadowCamera, InfiniteGridBundle, InfiniteGridPlugin, InfiniteGridSettings,
+    ReflectionCaptureCamera,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, InfiniteGridPlugin::default()))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, toggle_system)
+        .run();
+}
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let size = Extent3d {
+        width: 512,
+        height: 512,
+        depth_or_array_layers: 1,
+    };
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: None,
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Bgra8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..default()
+    };
+    image.resize(size);
+    let image = images.add(image);
+
+    commands.spawn(InfiniteGridBundle::default());
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0., 4., 10.).looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
+            ..default()
+        },
+        GridShadowCamera,
+    ));
+
+    // Looks at the scene from behind the mirror. A real mirror camera would follow the main
+    // camera's reflection, the point here is only what it draws.
+    commands.spawn((
+        Camera3dBundle {
+            camera: Camera {
+                target: RenderTarget::Image(image.clone()),
+                order: -1,
+                clear_color: Color::rgb(0.2, 0.2, 0.25).into(),
+                ..default()
+            },
+            transform: Transform::from_xyz(0., 2., -8.).looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
+            ..default()
+        },
+        ReflectionCaptureCamera,
+    ));
+
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(Rectangle::new(6., 4.)),
+        material: standard_materials.add(StandardMaterial {
+            base_color_texture: Some(image),
+            unlit: true,
+            ..default()
+        }),
+        transform: Transform::from_xyz(0., 2.5, -4.),
+        ..default()
+    });
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(Cuboid::new(1., 1., 1.)),
+        material: standard_materials.add(StandardMaterial::default()),
+        transform: Transform::from_xyz(0., 0.5, 0.),
+        ..default()
+    });
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_translation(Vec3::X * 15. + Vec3::Y * 20.)
+            .looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    commands.spawn(
+        TextBundle::from_section(
+            "Space: toggle the grid in the mirror (hidden)",
+            TextStyle::default(),
+        )
+        .with_style(Style {
+            margin: UiRect::all(Val::Px(8.)),
+            ..default()
+        }),
+    );
+}
+
+fn toggle_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut grids: Query<&mut InfiniteGridSettings>,
+    mut texts: Query<&mut Text>,
+) {
+    if !keys.just_pressed(KeyCode::Space) {
+        return;
+    }
+    for mut settings in grids.iter_mut() {
+        settings.exclude_from_reflections = !settings.exclude_from_reflections;
+        for mut text in texts.iter_mut() {
+            text.sections[0].value = format!(
+                "Space: toggle the grid in the mirror ({})",
+                if settings.exclude_from_reflections {
+                    "hidden"
+                } else {
+                    "shown"
+                }
+            );
+        }
+    }
+}
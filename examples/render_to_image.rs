@@ -0,0 +1,113 @@
+use bevy::{
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        render_resource::{
+            Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+        },
+        view::RenderLayers,
+    },
+};
+use bevy_infinite_grid::{GridShadowCamera, InfiniteGridBundle, InfiniteGridPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, InfiniteGridPlugin::default()))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, rotate_system)
+        .run();
+}
+
+#[derive(Component)]
+struct Screen;
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let size = Extent3d {
+        width: 512,
+        height: 512,
+        depth_or_array_layers: 1,
+    };
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: None,
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Bgra8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..default()
+    };
+    image.resize(size);
+    let image = images.add(image);
+
+    // The offscreen scene lives on its own layer, so only the image camera sees it. The grid's
+    // layers also pick its shadow casters.
+    let offscreen = RenderLayers::layer(1);
+    commands.spawn((InfiniteGridBundle::default(), offscreen));
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Cuboid::new(2., 2., 2.)),
+            material: standard_materials.add(StandardMaterial::default()),
+            transform: Transform::from_xyz(0., 1., 0.),
+            ..default()
+        },
+        offscreen,
+    ));
+    commands.spawn((
+        Camera3dBundle {
+            camera: Camera {
+                target: RenderTarget::Image(image.clone()),
+                // Before the main camera, which shows the image.
+                order: -1,
+                clear_color: Color::rgb(0.2, 0.2, 0.25).into(),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 6., 10.).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        GridShadowCamera,
+        offscreen,
+    ));
+    commands.spawn((
+        DirectionalLightBundle {
+            transform: Transform::from_translation(Vec3::X * 15. + Vec3::Y * 20.)
+                .looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        offscreen,
+    ));
+
+    // The main scene shows the image on a quad.
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Rectangle::new(4., 4.)),
+            material: standard_materials.add(StandardMaterial {
+                base_color_texture: Some(image),
+                unlit: true,
+                ..default()
+            }),
+            ..default()
+        },
+        Screen,
+    ));
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(0.0, 0., 8.).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+}
+
+fn rotate_system(time: Res<Time>, mut screens: Query<&mut Transform, With<Screen>>) {
+    for mut transform in screens.iter_mut() {
+        transform.rotation = Quat::from_rotation_y((time.elapsed_seconds() * 0.5).sin() * 0.6);
+    }
+}
@@ -0,0 +1,91 @@
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+    },
+};
+use bevy_infinite_grid::{
+    DebugGridShadowTexture, GridShadowCamera, InfiniteGridBundle, InfiniteGridPlugin,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, InfiniteGridPlugin::default()))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, spin_system)
+        .run();
+}
+
+#[derive(Component)]
+struct Spin;
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: 256,
+            height: 256,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0; 4],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    image.texture_descriptor.usage |= TextureUsages::RENDER_ATTACHMENT;
+    let image = images.add(image);
+
+    commands.spawn((
+        InfiniteGridBundle::default(),
+        DebugGridShadowTexture {
+            image: image.clone(),
+            camera: None,
+        },
+    ));
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 4.37, 14.77).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        GridShadowCamera,
+    ));
+
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_translation(Vec3::X * 15. + Vec3::Y * 20.)
+            .looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    commands.spawn((
+        PbrBundle {
+            material: standard_materials.add(StandardMaterial::default()),
+            mesh: meshes.add(Cuboid::new(1., 1., 4.)),
+            transform: Transform::from_xyz(0., 2., 0.),
+            ..default()
+        },
+        Spin,
+    ));
+
+    // The shadow texture, in the top left corner.
+    commands.spawn(ImageBundle {
+        image: UiImage::new(image),
+        style: Style {
+            width: Val::Px(256.),
+            height: Val::Px(256.),
+            ..default()
+        },
+        ..default()
+    });
+}
+
+fn spin_system(time: Res<Time>, mut query: Query<&mut Transform, With<Spin>>) {
+    for mut transform in query.iter_mut() {
+        transform.rotate_y(time.delta_seconds());
+    }
+}
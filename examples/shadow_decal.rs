@@ -0,0 +1,108 @@
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+    },
+};
+use bevy_infinite_grid::{
+    GridShadowCamera, GridShadowDecal, GridShadowDecalExtension, GridShadowDecalMaterial,
+    GridShadowDecalPlugin, InfiniteGridBundle, InfiniteGridPlugin, NotGridShadowCaster,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            InfiniteGridPlugin::default(),
+            GridShadowDecalPlugin,
+        ))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, spin_system)
+        .run();
+}
+
+#[derive(Component)]
+struct Spin;
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+    mut decal_materials: ResMut<Assets<GridShadowDecalMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    // Coverage is a plain value, not a color, so the image isn't sRGB.
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: 512,
+            height: 512,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0; 4],
+        TextureFormat::Rgba8Unorm,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    image.texture_descriptor.usage |= TextureUsages::RENDER_ATTACHMENT;
+    let decal = GridShadowDecal {
+        image: images.add(image),
+        ..default()
+    };
+
+    let grid_transform = GlobalTransform::default();
+    commands.spawn((InfiniteGridBundle::default(), decal.clone()));
+
+    // A floor over the decal's 20x20 area, a little above the grid. It is unlit, so the only
+    // shadow on it is the grid's.
+    commands.spawn((
+        MaterialMeshBundle {
+            mesh: meshes.add(Plane3d::default().mesh().size(20., 20.)),
+            material: decal_materials.add(GridShadowDecalMaterial {
+                base: StandardMaterial {
+                    base_color: Color::rgb(0.8, 0.7, 0.5),
+                    unlit: true,
+                    ..default()
+                },
+                extension: GridShadowDecalExtension::new(&decal, &grid_transform, 0.6),
+            }),
+            transform: Transform::from_xyz(0., 0.01, 0.),
+            ..default()
+        },
+        NotGridShadowCaster,
+    ));
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 10., 20.).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        GridShadowCamera,
+    ));
+
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_translation(Vec3::X * 15. + Vec3::Y * 20.)
+            .looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    let mesh = meshes.add(Cuboid::new(1., 1., 4.));
+    let material = standard_materials.add(StandardMaterial::default());
+    for x in [-5., 5.] {
+        commands.spawn((
+            PbrBundle {
+                mesh: mesh.clone(),
+                material: material.clone(),
+                transform: Transform::from_xyz(x, 2., 0.),
+                ..default()
+            },
+            Spin,
+        ));
+    }
+}
+
+fn spin_system(time: Res<Time>, mut query: Query<&mut Transform, With<Spin>>) {
+    for mut transform in query.iter_mut() {
+        transform.rotate_y(time.delta_seconds() * 0.5);
+    }
+}
@@ -0,0 +1,108 @@
+use bevy::prelude::*;
+use bevy_infinite_grid::{
+    GridShadowCamera, GridShadowOcclusion, GridShadowOcclusionPlugin, InfiniteGridBundle,
+    InfiniteGridPlugin, InfiniteGridSettings,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            InfiniteGridPlugin::default(),
+            GridShadowOcclusionPlugin,
+        ))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, (spawn_cube_system, occlusion_text_system))
+        .run();
+}
+
+#[derive(Component)]
+struct Floor;
+
+#[derive(Resource)]
+struct CubeAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+    spawned: u32,
+}
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // A short fadeout keeps the shadow texture, and so the measured area, to the floor in view.
+    commands.spawn((
+        InfiniteGridBundle {
+            settings: InfiniteGridSettings {
+                fadeout_distance: 20.,
+                ..default()
+            },
+            ..default()
+        },
+        Floor,
+    ));
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0., 18., 8.).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        GridShadowCamera,
+    ));
+
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_translation(Vec3::X * 5. + Vec3::Y * 20.)
+            .looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    commands.insert_resource(CubeAssets {
+        mesh: meshes.add(Cuboid::new(2., 2., 2.)),
+        material: standard_materials.add(StandardMaterial::default()),
+        spawned: 0,
+    });
+
+    commands.spawn(
+        TextBundle::from_section("Shadowed: -", TextStyle::default()).with_style(Style {
+            margin: UiRect::all(Val::Px(8.)),
+            ..default()
+        }),
+    );
+}
+
+// Drops a cube twice a second, spiralling out from the center until the floor is covered.
+fn spawn_cube_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut cubes: ResMut<CubeAssets>,
+    mut timer: Local<Option<Timer>>,
+) {
+    let timer = timer.get_or_insert_with(|| Timer::from_seconds(0.5, TimerMode::Repeating));
+    if !timer.tick(time.delta()).just_finished() || cubes.spawned >= 200 {
+        return;
+    }
+    let index = cubes.spawned as f32;
+    let (sin, cos) = (index * 2.4).sin_cos();
+    let radius = index.sqrt() * 1.6;
+    commands.spawn(PbrBundle {
+        mesh: cubes.mesh.clone(),
+        material: cubes.material.clone(),
+        transform: Transform::from_xyz(cos * radius, 1.5, sin * radius),
+        ..default()
+    });
+    cubes.spawned += 1;
+}
+
+fn occlusion_text_system(
+    occlusion: Res<GridShadowOcclusion>,
+    floors: Query<Entity, With<Floor>>,
+    mut texts: Query<&mut Text>,
+) {
+    let Some(fraction) = floors.iter().find_map(|floor| occlusion.fraction(floor)) else {
+        return;
+    };
+    for mut text in texts.iter_mut() {
+        text.sections[0].value = format!("Shadowed: {:.1}%", fraction * 100.);
+    }
+}
@@ -0,0 +1,161 @@
+use bevy::{diagnostic::DiagnosticsStore, prelude::*};
+use bevy_infinite_grid::{
+    GlobalInfiniteGridSettings, GridShadowCamera, InfiniteGridBundle,
+    InfiniteGridDiagnosticsPlugin, InfiniteGridPlugin,
+};
+
+/// The menu's choices of shadow texture size.
+const SIZES: [u32; 3] = [1024, 4096, 8192];
+
+const IDLE: Color = Color::rgb(0.2, 0.2, 0.2);
+const SELECTED: Color = Color::rgb(0.2, 0.45, 0.8);
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            InfiniteGridPlugin::default(),
+            InfiniteGridDiagnosticsPlugin,
+        ))
+        .add_systems(Startup, setup_system)
+        .add_systems(
+            Update,
+            (resolution_system, menu_system, text_system).chain(),
+        )
+        .run();
+}
+
+#[derive(Component)]
+struct SizeButton(u32);
+
+#[derive(Component)]
+struct ResolutionText;
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+    mut settings: ResMut<GlobalInfiniteGridSettings>,
+) {
+    // Start low, so the shadow edges visibly sharpen on the way up.
+    settings.render_settings.max_texture_size = 256;
+
+    commands.spawn(InfiniteGridBundle::default());
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 4.37, 14.77).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        GridShadowCamera,
+    ));
+
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_translation(Vec3::X * 15. + Vec3::Y * 20.)
+            .looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    commands.spawn(PbrBundle {
+        material: standard_materials.add(StandardMaterial::default()),
+        mesh: meshes.add(Torus::new(1., 2.)),
+        transform: Transform::from_xyz(0.0, 2.0, 0.0),
+        ..default()
+    });
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(8.),
+                margin: UiRect::all(Val::Px(8.)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|menu| {
+            menu.spawn((
+                TextBundle::from_section("", TextStyle::default()),
+                ResolutionText,
+            ));
+            menu.spawn(NodeBundle {
+                style: Style {
+                    column_gap: Val::Px(8.),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|row| {
+                for size in SIZES {
+                    row.spawn((
+                        ButtonBundle {
+                            style: Style {
+                                padding: UiRect::axes(Val::Px(12.), Val::Px(6.)),
+                                ..default()
+                            },
+                            background_color: IDLE.into(),
+                            ..default()
+                        },
+                        SizeButton(size),
+                    ))
+                    .with_children(|button| {
+                        button.spawn(TextBundle::from_section(
+                            size.to_string(),
+                            TextStyle::default(),
+                        ));
+                    });
+                }
+            });
+        });
+}
+
+// Render settings are synced to the render world whenever they change, and the shadow textures
+// are resized on the next frame.
+fn resolution_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<GlobalInfiniteGridSettings>,
+) {
+    let size = settings.render_settings.max_texture_size;
+    if keys.just_pressed(KeyCode::ArrowUp) {
+        settings.render_settings.max_texture_size = (size * 2).min(16384);
+    } else if keys.just_pressed(KeyCode::ArrowDown) {
+        settings.render_settings.max_texture_size = (size / 2).max(64);
+    }
+}
+
+// The buttons jump straight to a preset size, and highlight the one in use.
+fn menu_system(
+    mut buttons: Query<(&Interaction, &SizeButton, &mut BackgroundColor)>,
+    mut settings: ResMut<GlobalInfiniteGridSettings>,
+) {
+    for (interaction, size, _) in buttons.iter() {
+        if *interaction == Interaction::Pressed
+            && settings.render_settings.max_texture_size != size.0
+        {
+            settings.render_settings.max_texture_size = size.0;
+        }
+    }
+    for (_, size, mut color) in buttons.iter_mut() {
+        let selected = settings.render_settings.max_texture_size == size.0;
+        *color = if selected { SELECTED } else { IDLE }.into();
+    }
+}
+
+// Shows the size and the shadow texture memory, which follows it.
+fn text_system(
+    settings: Res<GlobalInfiniteGridSettings>,
+    diagnostics: Res<DiagnosticsStore>,
+    mut texts: Query<&mut Text, With<ResolutionText>>,
+) {
+    let bytes = diagnostics
+        .get(&InfiniteGridDiagnosticsPlugin::SHADOW_TEXTURE_BYTES)
+        .and_then(|diagnostic| diagnostic.value())
+        .unwrap_or_default();
+    for mut text in texts.iter_mut() {
+        text.sections[0].value = format!(
+            "Up/Down: shadow max_texture_size ({})\nShadow textures: {:.1} MiB",
+            settings.render_settings.max_texture_size,
+            bytes / (1024. * 1024.)
+        );
+    }
+}
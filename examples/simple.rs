@@ -8,7 +8,11 @@ fn main() -> color_eyre::eyre::Result<()> {
     color_eyre::install()?;
 
     App::new()
-        .add_plugins((DefaultPlugins, CameraControllerPlugin, InfiniteGridPlugin))
+        .add_plugins((
+            DefaultPlugins,
+            CameraControllerPlugin,
+            InfiniteGridPlugin::default(),
+        ))
         .add_systems(Startup, setup_system)
         .run();
 
@@ -22,7 +26,7 @@ fn setup_system(
 ) {
     commands.spawn(InfiniteGridBundle {
         settings: InfiniteGridSettings {
-            shadow_color: None,
+            shadow: None,
             ..default()
         },
         ..default()
@@ -0,0 +1,53 @@
+use bevy::prelude::*;
+use bevy_infinite_grid::{GridShadowCamera, GridSpin, InfiniteGridBundle, InfiniteGridPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, InfiniteGridPlugin::default()))
+        .add_systems(Startup, setup_system)
+        .run();
+}
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mesh = meshes.add(Cuboid::new(1., 1., 1.));
+    let material = standard_materials.add(StandardMaterial::default());
+
+    // The tilted axis takes the grid through every orientation, the cubes ride along and their
+    // shadows should follow without popping.
+    commands
+        .spawn((
+            InfiniteGridBundle::default(),
+            GridSpin {
+                axis: Vec3::new(1., 0., 1.),
+                radians_per_sec: 0.3,
+            },
+        ))
+        .with_children(|grid| {
+            for (x, z) in [(-3., 0.), (3., 1.), (0., -3.)] {
+                grid.spawn(PbrBundle {
+                    mesh: mesh.clone(),
+                    material: material.clone(),
+                    transform: Transform::from_xyz(x, 1.5, z),
+                    ..default()
+                });
+            }
+        });
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 6., 16.).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        GridShadowCamera,
+    ));
+
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_translation(Vec3::X * 15. + Vec3::Y * 20.)
+            .looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+}
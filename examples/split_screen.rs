@@ -0,0 +1,90 @@
+use bevy::{
+    prelude::*,
+    render::camera::{ClearColorConfig, Viewport},
+    window::{PrimaryWindow, WindowResized},
+};
+use bevy_infinite_grid::{GridShadowCamera, InfiniteGridBundle, InfiniteGridPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, InfiniteGridPlugin::default()))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, set_viewports_system)
+        .run();
+}
+
+// Which half of the window a camera draws to.
+#[derive(Component)]
+struct Player(u32);
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn(InfiniteGridBundle::default());
+
+    // Mirrored views of the same scene, so both halves should look alike.
+    for player in 0..2 {
+        let x = if player == 0 { -8. } else { 8. };
+        commands.spawn((
+            Camera3dBundle {
+                transform: Transform::from_xyz(x, 6., 12.).looking_at(Vec3::ZERO, Vec3::Y),
+                camera: Camera {
+                    order: player as isize,
+                    // The second camera must not clear the first one's half.
+                    clear_color: if player == 0 {
+                        ClearColorConfig::Default
+                    } else {
+                        ClearColorConfig::None
+                    },
+                    ..default()
+                },
+                ..default()
+            },
+            GridShadowCamera,
+            Player(player),
+        ));
+    }
+
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_translation(Vec3::Y * 20.).looking_at(Vec3::ZERO, Vec3::Z),
+        ..default()
+    });
+
+    let mesh = meshes.add(Cuboid::new(1., 1., 1.));
+    let material = standard_materials.add(StandardMaterial::default());
+    for x in [-3., 0., 3.] {
+        commands.spawn(PbrBundle {
+            mesh: mesh.clone(),
+            material: material.clone(),
+            transform: Transform::from_xyz(x, 1., 0.),
+            ..default()
+        });
+    }
+}
+
+fn set_viewports_system(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut resized: EventReader<WindowResized>,
+    mut cameras: Query<(&Player, &mut Camera)>,
+    mut initialized: Local<bool>,
+) {
+    // The first frame sets the viewports up, resizes update them.
+    if resized.read().count() == 0 && *initialized {
+        return;
+    }
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    *initialized = true;
+    let size = UVec2::new(window.physical_width(), window.physical_height());
+    let half = UVec2::new(size.x / 2, size.y);
+    for (player, mut camera) in cameras.iter_mut() {
+        camera.viewport = Some(Viewport {
+            physical_position: UVec2::new(player.0 * half.x, 0),
+            physical_size: half.max(UVec2::ONE),
+            ..default()
+        });
+    }
+}
@@ -0,0 +1,108 @@
+use bevy::{
+    prelude::*,
+    render::camera::{ClearColorConfig, Viewport},
+    window::{PrimaryWindow, WindowResized},
+};
+use bevy_infinite_grid::{GridShadowCamera, InfiniteGridBundle, InfiniteGridPlugin};
+
+/// Distance between the eyes, in meters like the rest of the scene.
+const IPD: f32 = 0.064;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, InfiniteGridPlugin::default()))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, (head_system, set_viewports_system))
+        .run();
+}
+
+// Which half of the window an eye draws to, 0 for the left.
+#[derive(Component)]
+struct Eye(u32);
+
+#[derive(Component)]
+struct Head;
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn(InfiniteGridBundle::default());
+
+    // One camera per eye, like an XR runtime without multiview. Each eye is its own view, with
+    // its own grid uniforms and shadow, so both halves should show the same grid from slightly
+    // apart.
+    commands
+        .spawn((SpatialBundle::default(), Head))
+        .with_children(|head| {
+            for eye in 0..2 {
+                let x = if eye == 0 { -IPD / 2. } else { IPD / 2. };
+                head.spawn((
+                    Camera3dBundle {
+                        transform: Transform::from_xyz(x, 0., 0.),
+                        camera: Camera {
+                            order: eye as isize,
+                            // The right eye must not clear the left one's half.
+                            clear_color: if eye == 0 {
+                                ClearColorConfig::Default
+                            } else {
+                                ClearColorConfig::None
+                            },
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    GridShadowCamera,
+                    Eye(eye),
+                ));
+            }
+        });
+
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_translation(Vec3::X * 15. + Vec3::Y * 20.)
+            .looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(Cuboid::new(0.5, 0.5, 0.5)),
+        material: standard_materials.add(StandardMaterial::default()),
+        transform: Transform::from_xyz(0., 0.25, 0.),
+        ..default()
+    });
+}
+
+// A standing viewer slowly walking around the cube.
+fn head_system(time: Res<Time>, mut heads: Query<&mut Transform, With<Head>>) {
+    let angle = time.elapsed_seconds() * 0.2;
+    for mut transform in heads.iter_mut() {
+        *transform = Transform::from_xyz(angle.sin() * 3., 1.7, angle.cos() * 3.)
+            .looking_at(Vec3::ZERO, Vec3::Y);
+    }
+}
+
+fn set_viewports_system(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut resized: EventReader<WindowResized>,
+    mut cameras: Query<(&Eye, &mut Camera)>,
+    mut initialized: Local<bool>,
+) {
+    // The first frame sets the viewports up, resizes update them.
+    if resized.read().count() == 0 && *initialized {
+        return;
+    }
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    *initialized = true;
+    let size = UVec2::new(window.physical_width(), window.physical_height());
+    let half = UVec2::new(size.x / 2, size.y);
+    for (eye, mut camera) in cameras.iter_mut() {
+        camera.viewport = Some(Viewport {
+            physical_position: UVec2::new(eye.0 * half.x, 0),
+            physical_size: half.max(UVec2::ONE),
+            ..default()
+        });
+    }
+}
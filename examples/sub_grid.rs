@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+use bevy_infinite_grid::{InfiniteGridBundle, InfiniteGridPlugin, InfiniteGridSettings, SubGrid};
+
+fn main() {
+    App::new()
+        .insert_resource(ClearColor(Color::rgb(0.05, 0.12, 0.3)))
+        .add_plugins((DefaultPlugins, InfiniteGridPlugin::default()))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, zoom_system)
+        .run();
+}
+
+fn setup_system(mut commands: Commands) {
+    commands.spawn(InfiniteGridBundle {
+        settings: InfiniteGridSettings {
+            sub_grid: Some(SubGrid {
+                color: Color::rgba(0.45, 0.6, 0.85, 0.25),
+                ..default()
+            }),
+            ..InfiniteGridSettings::blueprint()
+        },
+        ..default()
+    });
+
+    commands.spawn(Camera3dBundle::default());
+}
+
+// Swings the camera down towards the grid and back, the sub-grid only shows up close.
+fn zoom_system(time: Res<Time>, mut cameras: Query<&mut Transform, With<Camera>>) {
+    let height = 0.5 + 10. * (0.5 + 0.5 * (time.elapsed_seconds() * 0.4).cos());
+    for mut transform in cameras.iter_mut() {
+        *transform = Transform::from_xyz(0.0, height, height * 2.).looking_at(Vec3::ZERO, Vec3::Y);
+    }
+}
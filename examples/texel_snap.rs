@@ -0,0 +1,92 @@
+use bevy::prelude::*;
+use bevy_infinite_grid::{
+    GridShadowCamera, GridShadowSettings, InfiniteGridBundle, InfiniteGridPlugin,
+    InfiniteGridSettings,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, InfiniteGridPlugin::default()))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, (orbit_system, toggle_system))
+        .run();
+}
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // A low resolution makes the crawling shadow edges easy to see.
+    commands.spawn(InfiniteGridBundle {
+        settings: InfiniteGridSettings {
+            shadow: Some(GridShadowSettings {
+                resolution: Some(512),
+                pcf_size: 1,
+                texel_snap: true,
+                ..default()
+            }),
+            ..default()
+        },
+        ..default()
+    });
+
+    commands.spawn((Camera3dBundle::default(), GridShadowCamera));
+
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_translation(Vec3::X * 15. + Vec3::Y * 20.)
+            .looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    let mesh = meshes.add(Cuboid::new(1., 1., 1.));
+    let material = standard_materials.add(StandardMaterial::default());
+    for i in 0..8 {
+        let angle = i as f32 * std::f32::consts::TAU / 8.;
+        commands.spawn(PbrBundle {
+            mesh: mesh.clone(),
+            material: material.clone(),
+            transform: Transform::from_xyz(angle.cos() * 4., 1., angle.sin() * 4.)
+                .with_rotation(Quat::from_rotation_y(angle)),
+            ..default()
+        });
+    }
+
+    commands.spawn(
+        TextBundle::from_section("Space: toggle texel snapping (on)", TextStyle::default())
+            .with_style(Style {
+                margin: UiRect::all(Val::Px(8.)),
+                ..default()
+            }),
+    );
+}
+
+// Circles the camera slowly, with snapping off the shadow edges crawl.
+fn orbit_system(time: Res<Time>, mut cameras: Query<&mut Transform, With<Camera>>) {
+    let angle = time.elapsed_seconds() * 0.05;
+    for mut transform in cameras.iter_mut() {
+        *transform = Transform::from_xyz(angle.cos() * 14., 6., angle.sin() * 14.)
+            .looking_at(Vec3::ZERO, Vec3::Y);
+    }
+}
+
+fn toggle_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut grids: Query<&mut InfiniteGridSettings>,
+    mut texts: Query<&mut Text>,
+) {
+    if !keys.just_pressed(KeyCode::Space) {
+        return;
+    }
+    for mut settings in grids.iter_mut() {
+        if let Some(shadow) = &mut settings.shadow {
+            shadow.texel_snap = !shadow.texel_snap;
+            for mut text in texts.iter_mut() {
+                text.sections[0].value = format!(
+                    "Space: toggle texel snapping ({})",
+                    if shadow.texel_snap { "on" } else { "off" }
+                );
+            }
+        }
+    }
+}
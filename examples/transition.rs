@@ -0,0 +1,54 @@
+use bevy::prelude::*;
+use bevy_infinite_grid::{
+    GridSettingsTransition, InfiniteGridBundle, InfiniteGridPlugin, InfiniteGridSettings,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, InfiniteGridPlugin::default()))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, swap_system)
+        .run();
+}
+
+fn setup_system(mut commands: Commands) {
+    commands.spawn((
+        InfiniteGridBundle {
+            settings: InfiniteGridSettings::dark_editor(),
+            ..default()
+        },
+        GridSettingsTransition::new(
+            InfiniteGridSettings::dark_editor(),
+            InfiniteGridSettings::blueprint(),
+            3.,
+        ),
+    ));
+
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(0.0, 4.37, 14.77).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+}
+
+// Blends back the other way whenever a transition finishes.
+fn swap_system(
+    mut commands: Commands,
+    mut removed: RemovedComponents<GridSettingsTransition>,
+    grids: Query<&InfiniteGridSettings>,
+    mut back: Local<bool>,
+) {
+    for entity in removed.read() {
+        let Ok(settings) = grids.get(entity) else {
+            continue;
+        };
+        *back = !*back;
+        let to = if *back {
+            InfiniteGridSettings::dark_editor()
+        } else {
+            InfiniteGridSettings::blueprint()
+        };
+        commands
+            .entity(entity)
+            .insert(GridSettingsTransition::new(*settings, to, 3.));
+    }
+}
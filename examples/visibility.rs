@@ -0,0 +1,103 @@
+use bevy::{
+    diagnostic::{DiagnosticPath, DiagnosticsStore},
+    prelude::*,
+};
+use bevy_infinite_grid::{
+    GridShadowCamera, InfiniteGridBundle, InfiniteGridDiagnosticsPlugin, InfiniteGridPlugin,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            InfiniteGridPlugin::default(),
+            InfiniteGridDiagnosticsPlugin,
+        ))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, (toggle_system, update_hud))
+        .run();
+}
+
+#[derive(Component)]
+struct GridParent;
+
+fn setup_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // The grid inherits its parent's visibility, like any mesh would.
+    commands
+        .spawn((SpatialBundle::default(), GridParent))
+        .with_children(|parent| {
+            parent.spawn(InfiniteGridBundle::default());
+        });
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0., 6., 14.).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        GridShadowCamera,
+    ));
+
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_translation(Vec3::X * 15. + Vec3::Y * 20.)
+            .looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    let mesh = meshes.add(Cuboid::new(1., 1., 1.));
+    let material = standard_materials.add(StandardMaterial::default());
+    for x in [-3., 0., 3.] {
+        commands.spawn(PbrBundle {
+            mesh: mesh.clone(),
+            material: material.clone(),
+            transform: Transform::from_xyz(x, 1.5, 0.),
+            ..default()
+        });
+    }
+
+    commands.spawn(
+        TextBundle::from_section("", TextStyle::default()).with_style(Style {
+            margin: UiRect::all(Val::Px(8.)),
+            ..default()
+        }),
+    );
+}
+
+// Hides and shows the grid's parent every second.
+fn toggle_system(
+    time: Res<Time>,
+    mut timer: Local<Option<Timer>>,
+    mut parents: Query<&mut Visibility, With<GridParent>>,
+) {
+    let timer = timer.get_or_insert_with(|| Timer::from_seconds(1., TimerMode::Repeating));
+    if !timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    for mut visibility in parents.iter_mut() {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        };
+    }
+}
+
+// While the grid is hidden nothing is drawn for it, not even its shadow.
+fn update_hud(diagnostics: Res<DiagnosticsStore>, mut texts: Query<&mut Text>) {
+    let value = |path: &DiagnosticPath| {
+        diagnostics
+            .get(path)
+            .and_then(|diagnostic| diagnostic.value())
+            .unwrap_or_default()
+    };
+    for mut text in texts.iter_mut() {
+        text.sections[0].value = format!(
+            "grids: {}\nshadow passes: {}\nshadow textures: {:.1} MiB",
+            value(&InfiniteGridDiagnosticsPlugin::GRIDS),
+            value(&InfiniteGridDiagnosticsPlugin::SHADOW_PASSES),
+            value(&InfiniteGridDiagnosticsPlugin::SHADOW_TEXTURE_BYTES) / (1024. * 1024.),
+        );
+    }
+}
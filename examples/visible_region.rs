@@ -0,0 +1,82 @@
+use bevy::{
+    prelude::*,
+    render::camera::{ClearColorConfig, ScalingMode, Viewport},
+};
+use bevy_infinite_grid::{
+    GridFrustumIntersect, InfiniteGridBundle, InfiniteGridPlugin, InfiniteGridSettings,
+};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, InfiniteGridPlugin::default()))
+        .add_systems(Startup, setup_system)
+        .add_systems(Update, (orbit_system, draw_region_system))
+        .run();
+}
+
+#[derive(Component)]
+struct MainCamera;
+
+fn setup_system(mut commands: Commands) {
+    commands.spawn(InfiniteGridBundle {
+        settings: InfiniteGridSettings {
+            fadeout_distance: 40.,
+            ..default()
+        },
+        ..default()
+    });
+
+    commands.spawn((Camera3dBundle::default(), MainCamera));
+
+    // The minimap has a higher order, so the grid's intersect follows the main camera.
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(0., 100., 0.).looking_at(Vec3::ZERO, Vec3::NEG_Z),
+        projection: Projection::Orthographic(OrthographicProjection {
+            scaling_mode: ScalingMode::FixedVertical(100.),
+            ..default()
+        }),
+        camera: Camera {
+            order: 1,
+            viewport: Some(Viewport {
+                physical_position: UVec2::new(16, 16),
+                physical_size: UVec2::new(256, 256),
+                ..default()
+            }),
+            clear_color: ClearColorConfig::Custom(Color::rgb(0.05, 0.05, 0.08)),
+            ..default()
+        },
+        ..default()
+    });
+}
+
+fn orbit_system(time: Res<Time>, mut cameras: Query<&mut Transform, With<MainCamera>>) {
+    let angle = time.elapsed_seconds() * 0.2;
+    for mut transform in cameras.iter_mut() {
+        *transform = Transform::from_xyz(angle.cos() * 10., 4., angle.sin() * 10.)
+            .looking_at(Vec3::ZERO, Vec3::Y);
+    }
+}
+
+// The outline of the view on the grid in yellow, the rectangle around it in cyan.
+fn draw_region_system(mut gizmos: Gizmos, grids: Query<&GridFrustumIntersect>) {
+    for intersect in grids.iter().filter(|intersect| !intersect.is_empty()) {
+        let points = intersect.points();
+        if let Some(&first) = points.first() {
+            gizmos.linestrip(points.iter().copied().chain([first]), Color::YELLOW);
+        }
+
+        let right = intersect.up_dir.cross(Vec3::Y).normalize_or_zero() * intersect.width / 2.;
+        let up = intersect.up_dir * intersect.height / 2.;
+        let center = intersect.center;
+        gizmos.linestrip(
+            [
+                center - right - up,
+                center + right - up,
+                center + right + up,
+                center - right + up,
+                center - right - up,
+            ],
+            Color::CYAN,
+        );
+    }
+}
@@ -0,0 +1,199 @@
+use std::sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    Arc,
+};
+#[cfg(feature = "shadows")]
+use std::time::Duration;
+
+use bevy::diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic};
+use bevy::prelude::*;
+
+/// Adds grid render cost diagnostics to an App, read back through `DiagnosticsStore`.
+///
+/// The counts come from the render world, so they trail the main world by a frame.
+#[derive(Default)]
+pub struct InfiniteGridDiagnosticsPlugin;
+
+impl Plugin for InfiniteGridDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_diagnostic(Diagnostic::new(Self::GRIDS))
+            .register_diagnostic(Diagnostic::new(Self::SHADOW_CASTERS))
+            .register_diagnostic(Diagnostic::new(Self::SHADOW_TEXTURE_BYTES).with_suffix(" B"))
+            .register_diagnostic(Diagnostic::new(Self::SHADOW_BIND_GROUPS))
+            .register_diagnostic(Diagnostic::new(Self::SHADOW_PASSES))
+            .register_diagnostic(Diagnostic::new(Self::SHADOW_DRAWS))
+            .register_diagnostic(Diagnostic::new(Self::SHADOW_CASTERS_PER_GRID))
+            .register_diagnostic(Diagnostic::new(Self::SHADOW_QUEUE_CPU_MS).with_suffix(" ms"))
+            .register_diagnostic(Diagnostic::new(Self::SHADOW_BATCH_CPU_MS).with_suffix(" ms"))
+            .register_diagnostic(Diagnostic::new(Self::SHADOW_PASS_GPU_MS).with_suffix(" ms"))
+            .add_systems(Update, Self::diagnostic_system);
+    }
+}
+
+impl InfiniteGridDiagnosticsPlugin {
+    /// Grids queued for drawing, summed over all views.
+    pub const GRIDS: DiagnosticPath = DiagnosticPath::const_new("infinite_grid/grids");
+    /// Meshes queued into grid shadow passes, summed over all shadow views.
+    pub const SHADOW_CASTERS: DiagnosticPath =
+        DiagnosticPath::const_new("infinite_grid/shadow_casters");
+    /// Size of the shadow textures requested this frame.
+    pub const SHADOW_TEXTURE_BYTES: DiagnosticPath =
+        DiagnosticPath::const_new("infinite_grid/shadow_texture_bytes");
+    /// Grid shadow bind groups kept for reuse. Like the textures, they are dropped along with
+    /// the grids that used them.
+    pub const SHADOW_BIND_GROUPS: DiagnosticPath =
+        DiagnosticPath::const_new("infinite_grid/shadow_bind_groups");
+    /// Grid shadow render passes. Grids that can share a shadow share its pass.
+    pub const SHADOW_PASSES: DiagnosticPath =
+        DiagnosticPath::const_new("infinite_grid/shadow_passes");
+    /// Draw calls in the grid shadow passes. Casters sharing a mesh and material are drawn
+    /// instanced, so there are fewer draws than casters.
+    pub const SHADOW_DRAWS: DiagnosticPath =
+        DiagnosticPath::const_new("infinite_grid/shadow_draws");
+    /// Most meshes queued into a single grid shadow pass.
+    pub const SHADOW_CASTERS_PER_GRID: DiagnosticPath =
+        DiagnosticPath::const_new("infinite_grid/shadow_casters_per_grid");
+    /// CPU time spent queueing shadow casters.
+    pub const SHADOW_QUEUE_CPU_MS: DiagnosticPath =
+        DiagnosticPath::const_new("infinite_grid/shadow_queue_cpu_ms");
+    /// CPU time spent batching the queued shadow casters.
+    pub const SHADOW_BATCH_CPU_MS: DiagnosticPath =
+        DiagnosticPath::const_new("infinite_grid/shadow_batch_cpu_ms");
+    /// GPU time of the grid shadow render passes. Only measured on devices with
+    /// `WgpuFeatures::TIMESTAMP_QUERY`, and a few frames behind the other diagnostics.
+    pub const SHADOW_PASS_GPU_MS: DiagnosticPath =
+        DiagnosticPath::const_new("infinite_grid/shadow_pass_gpu_ms");
+
+    fn diagnostic_system(mut diagnostics: Diagnostics, stats: Option<Res<GridRenderStats>>) {
+        let Some(stats) = stats else {
+            return;
+        };
+        // GPU timestamps cost a readback, so they're only taken once someone reads them.
+        stats.0.gpu_timing_wanted.store(true, Ordering::Relaxed);
+        diagnostics.add_measurement(&Self::GRIDS, || {
+            stats.0.grids.load(Ordering::Relaxed) as f64
+        });
+        diagnostics.add_measurement(&Self::SHADOW_CASTERS, || {
+            stats.0.shadow_casters.load(Ordering::Relaxed) as f64
+        });
+        diagnostics.add_measurement(&Self::SHADOW_TEXTURE_BYTES, || {
+            stats.0.shadow_texture_bytes.load(Ordering::Relaxed) as f64
+        });
+        diagnostics.add_measurement(&Self::SHADOW_BIND_GROUPS, || {
+            stats.0.shadow_bind_groups.load(Ordering::Relaxed) as f64
+        });
+        diagnostics.add_measurement(&Self::SHADOW_PASSES, || {
+            stats.0.shadow_passes.load(Ordering::Relaxed) as f64
+        });
+        diagnostics.add_measurement(&Self::SHADOW_DRAWS, || {
+            stats.0.shadow_draws.load(Ordering::Relaxed) as f64
+        });
+        diagnostics.add_measurement(&Self::SHADOW_CASTERS_PER_GRID, || {
+            stats.0.shadow_casters_per_grid.load(Ordering::Relaxed) as f64
+        });
+        let millis = |nanos: &AtomicU64| nanos.load(Ordering::Relaxed) as f64 / 1e6;
+        diagnostics.add_measurement(&Self::SHADOW_QUEUE_CPU_MS, || {
+            millis(&stats.0.shadow_queue_cpu_ns)
+        });
+        diagnostics.add_measurement(&Self::SHADOW_BATCH_CPU_MS, || {
+            millis(&stats.0.shadow_batch_cpu_ns)
+        });
+        if stats.0.shadow_pass_gpu_timed.load(Ordering::Relaxed) {
+            diagnostics.add_measurement(&Self::SHADOW_PASS_GPU_MS, || {
+                millis(&stats.0.shadow_pass_gpu_ns)
+            });
+        }
+    }
+}
+
+/// Counters written by the render systems, shared between the main and render worlds.
+#[derive(Resource, Clone, Default)]
+pub(crate) struct GridRenderStats(Arc<GridRenderCounters>);
+
+#[derive(Default)]
+struct GridRenderCounters {
+    grids: AtomicU64,
+    shadow_casters: AtomicU64,
+    shadow_texture_bytes: AtomicU64,
+    shadow_bind_groups: AtomicU64,
+    shadow_passes: AtomicU64,
+    shadow_draws: AtomicU64,
+    shadow_casters_per_grid: AtomicU64,
+    shadow_queue_cpu_ns: AtomicU64,
+    shadow_batch_cpu_ns: AtomicU64,
+    shadow_pass_gpu_ns: AtomicU64,
+    /// Whether `shadow_pass_gpu_ns` holds a measurement.
+    shadow_pass_gpu_timed: AtomicBool,
+    /// Set by the diagnostics plugin.
+    gpu_timing_wanted: AtomicBool,
+}
+
+impl GridRenderStats {
+    pub(crate) fn set_grids(&self, count: u64) {
+        self.0.grids.store(count, Ordering::Relaxed);
+    }
+
+    /// Counts grids queued after `set_grids`, in the 2D pass.
+    pub(crate) fn add_grids(&self, count: u64) {
+        self.0.grids.fetch_add(count, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "shadows")]
+    pub(crate) fn set_shadow_casters(&self, count: u64) {
+        self.0.shadow_casters.store(count, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "shadows")]
+    pub(crate) fn set_shadow_texture_bytes(&self, bytes: u64) {
+        self.0.shadow_texture_bytes.store(bytes, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "shadows")]
+    pub(crate) fn set_shadow_bind_groups(&self, count: u64) {
+        self.0.shadow_bind_groups.store(count, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "shadows")]
+    pub(crate) fn set_shadow_passes(&self, count: u64) {
+        self.0.shadow_passes.store(count, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "shadows")]
+    pub(crate) fn set_shadow_draws(&self, count: u64) {
+        self.0.shadow_draws.store(count, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "shadows")]
+    pub(crate) fn set_shadow_casters_per_grid(&self, count: u64) {
+        self.0
+            .shadow_casters_per_grid
+            .store(count, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "shadows")]
+    pub(crate) fn set_shadow_queue_cpu_time(&self, time: Duration) {
+        self.0
+            .shadow_queue_cpu_ns
+            .store(time.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "shadows")]
+    pub(crate) fn set_shadow_batch_cpu_time(&self, time: Duration) {
+        self.0
+            .shadow_batch_cpu_ns
+            .store(time.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "shadows")]
+    pub(crate) fn set_shadow_pass_gpu_time(&self, time: Duration) {
+        self.0
+            .shadow_pass_gpu_ns
+            .store(time.as_nanos() as u64, Ordering::Relaxed);
+        self.0.shadow_pass_gpu_timed.store(true, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "shadows")]
+    pub(crate) fn gpu_timing_wanted(&self) -> bool {
+        self.0.gpu_timing_wanted.load(Ordering::Relaxed)
+    }
+}
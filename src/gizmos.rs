@@ -0,0 +1,41 @@
+use bevy::prelude::*;
+
+use crate::InfiniteGridSettings;
+
+/// Immediate mode grids, drawn for one frame without spawning an entity, like Bevy's `Gizmos`.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_infinite_grid::{InfiniteGridGizmos, InfiniteGridSettings};
+/// fn debug_system(mut grids: ResMut<InfiniteGridGizmos>) {
+///     grids.draw_once(Transform::from_xyz(0., 2., 0.), InfiniteGridSettings::default());
+/// }
+/// ```
+///
+/// These grids are seen by every camera, and get no hover or pointer events. They never get
+/// shadows, `shadow` is ignored.
+#[derive(Resource, Default)]
+pub struct InfiniteGridGizmos {
+    pub(crate) grids: Vec<(GlobalTransform, InfiniteGridSettings)>,
+}
+
+impl InfiniteGridGizmos {
+    /// Draws a grid this frame. Call it every frame to keep it on screen.
+    pub fn draw_once(
+        &mut self,
+        transform: impl Into<GlobalTransform>,
+        settings: InfiniteGridSettings,
+    ) {
+        self.grids.push((transform.into(), settings));
+    }
+}
+
+pub(crate) fn build(app: &mut App) {
+    app.init_resource::<InfiniteGridGizmos>()
+        .add_systems(First, clear_gizmo_grids);
+}
+
+// Grids drawn during the last frame have been extracted by now.
+fn clear_gizmo_grids(mut grids: ResMut<InfiniteGridGizmos>) {
+    grids.grids.clear();
+}
@@ -0,0 +1,86 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::{
+    prelude::*,
+    render::view::{NoFrustumCulling, VisibleEntities},
+};
+
+use crate::{InfiniteGrid, InfiniteGridPlugin, InfiniteGridSettings};
+
+/// Draws grids for `Camera2d`s, in the 2D transparent pass and sorted among sprites by Z.
+///
+/// Adds [`InfiniteGridPlugin`] without shadows unless it's already added, so add that first to
+/// draw grids for 3D cameras too. Grid shadows are never drawn in 2D.
+#[derive(Default)]
+pub struct InfiniteGrid2dPlugin;
+
+impl Plugin for InfiniteGrid2dPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<InfiniteGridPlugin>() {
+            app.add_plugins(InfiniteGridPlugin {
+                shadows: false,
+                ..default()
+            });
+        }
+    }
+
+    fn finish(&self, app: &mut App) {
+        crate::render::register_core_2d(app);
+    }
+}
+
+/// A grid on the XY plane for 2D cameras, at the Z of its transform's translation.
+///
+/// `scale` is lines per world unit, so with the default 2D camera a scale of `0.02` puts minor
+/// lines 50 pixels apart (see [`InfiniteGridSettingsBuilder::spacing`]); line and antialiasing
+/// widths stay in pixels at any zoom. Lines closer than a few pixels fade out as the camera
+/// zooms out, and 2D cameras see no distance fadeout. `x_axis_color` colors the horizontal X
+/// axis and `z_axis_color` the vertical Y axis.
+///
+/// The grid's local Z axis points down the screen, so grid space positions, e.g. of hover hits
+/// and heatmaps, have Y flipped.
+///
+/// [`InfiniteGridSettingsBuilder::spacing`]: crate::InfiniteGridSettingsBuilder::spacing
+#[derive(Bundle)]
+pub struct InfiniteGrid2dBundle {
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+    pub settings: InfiniteGridSettings,
+    pub grid: InfiniteGrid,
+    pub visibility: Visibility,
+    pub view_visibility: ViewVisibility,
+    pub inherited_visibility: InheritedVisibility,
+    /// Always empty in 2D, where nothing casts grid shadows.
+    pub shadow_casters: VisibleEntities,
+    pub no_frustum_culling: NoFrustumCulling,
+}
+
+impl InfiniteGrid2dBundle {
+    /// A grid at `z`, e.g. below the sprites of a level.
+    pub fn at_z(z: f32) -> Self {
+        let mut bundle = Self::default();
+        bundle.transform.translation.z = z;
+        bundle
+    }
+}
+
+impl Default for InfiniteGrid2dBundle {
+    fn default() -> Self {
+        Self {
+            // Turns the grid's XZ plane into the XY plane, its normal towards 2D cameras.
+            transform: Transform::from_rotation(Quat::from_rotation_x(FRAC_PI_2)),
+            global_transform: default(),
+            settings: InfiniteGridSettings {
+                shadow: None,
+                scale: 0.02,
+                ..default()
+            },
+            grid: default(),
+            visibility: default(),
+            view_visibility: default(),
+            inherited_visibility: default(),
+            shadow_casters: default(),
+            no_frustum_culling: NoFrustumCulling,
+        }
+    }
+}
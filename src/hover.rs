@@ -0,0 +1,331 @@
+use bevy::input::InputSystem;
+use bevy::math::Vec3Swizzles;
+use bevy::prelude::*;
+use bevy::render::camera::NormalizedRenderTarget;
+use bevy::render::primitives::Aabb;
+use bevy::utils::HashMap;
+use bevy::window::PrimaryWindow;
+
+use crate::{InfiniteGrid, InfiniteGridSettings};
+
+pub(crate) fn build(app: &mut App) {
+    app.init_resource::<InfiniteGridHover>()
+        .register_type::<InfiniteGridHover>()
+        .register_type::<GridHit>()
+        .register_type::<Option<GridHit>>()
+        .register_type::<NotGridHoverOccluder>()
+        .add_event::<GridPointerEvent>()
+        .add_event::<GridCellChanged>()
+        .add_systems(
+            PreUpdate,
+            (
+                update_grid_hover,
+                (send_grid_pointer_events, send_grid_cell_changed_events),
+            )
+                .chain()
+                .after(InputSystem),
+        );
+}
+
+/// Where the cursor ray meets a grid plane.
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+pub struct GridHit {
+    pub grid: Entity,
+    pub camera: Entity,
+    /// World space hit position on the plane.
+    pub position: Vec3,
+    /// Hit position in grid space (the plane's local X/Z, in the entity's scaled units).
+    pub local: Vec2,
+    /// Cell under the hit, counted from [`InfiniteGridSettings::grid_origin_offset`].
+    pub cell: IVec2,
+    /// World space center of `cell`.
+    pub cell_center: Vec3,
+    /// Distance from the camera along the cursor ray.
+    pub depth: f32,
+}
+
+/// The grid currently under the cursor, if any.
+///
+/// This is a standalone hover helper, not a backend for `bevy_mod_picking` or `bevy_picking`:
+/// its hits and [`GridPointerEvent`]s don't go through their pointer pipeline, and aren't
+/// ordered against their hits on other entities.
+#[derive(Resource, Default, Debug, Reflect)]
+#[reflect(Resource, Default)]
+pub struct InfiniteGridHover {
+    pub hit: Option<GridHit>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GridPointerEventKind {
+    Over,
+    Out,
+    Move,
+    Down(MouseButton),
+    Up(MouseButton),
+    Click(MouseButton),
+    Drag { button: MouseButton, delta: Vec3 },
+}
+
+/// Cursor events on the grid under [`InfiniteGridHover`], sent in `PreUpdate`.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct GridPointerEvent {
+    pub grid: Entity,
+    pub kind: GridPointerEventKind,
+    pub hit: GridHit,
+}
+
+/// Sent when the hovered cell of a grid changes, including the cursor entering or leaving it.
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GridCellChanged {
+    pub grid: Entity,
+    pub old: Option<IVec2>,
+    pub new: Option<IVec2>,
+}
+
+/// Meshes with this component don't block the cursor from reaching grids behind them.
+///
+/// Other meshes block it by their `Aabb`, not their triangles. The hover is lost over the empty
+/// corners of a mesh's bounds, e.g. around a sphere, and kept behind meshes without an `Aabb`.
+#[derive(Component, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct NotGridHoverOccluder;
+
+fn grid_hit(
+    ray: Ray3d,
+    camera: Entity,
+    grid: Entity,
+    transform: &GlobalTransform,
+    settings: &InfiniteGridSettings,
+) -> Option<GridHit> {
+    let origin = transform.translation();
+    let depth = ray.intersect_plane(origin, Plane3d::new(transform.up()))?;
+    let position = ray.get_point(depth);
+
+    let (scale, rotation, _) = transform.to_scale_rotation_translation();
+    let scale = scale.xz();
+    let local = (rotation.inverse() * (position - origin)).xz() / scale;
+    // Cells count from the offset origin, like the drawn lines.
+    let offset = settings.grid_origin_offset;
+    let cell = ((local - offset) * settings.scale).floor();
+    let cell_center = origin
+        + rotation
+            * (((cell + 0.5) / settings.scale + offset) * scale)
+                .extend(0.)
+                .xzy();
+
+    Some(GridHit {
+        grid,
+        camera,
+        position,
+        local,
+        cell: cell.as_ivec2(),
+        cell_center,
+        depth,
+    })
+}
+
+fn ray_aabb_distance(ray: Ray3d, transform: &GlobalTransform, aabb: &Aabb) -> Option<f32> {
+    // Work in the mesh's local space, where the aabb is axis aligned. The direction isn't
+    // renormalized so the returned parameter stays a world space distance.
+    let inverse = transform.affine().inverse();
+    let origin = inverse.transform_point3(ray.origin);
+    let direction = inverse.transform_vector3(*ray.direction);
+
+    let t1 = (Vec3::from(aabb.min()) - origin) / direction;
+    let t2 = (Vec3::from(aabb.max()) - origin) / direction;
+    let near = t1.min(t2).max_element();
+    let far = t1.max(t2).min_element();
+
+    (near <= far && far >= 0.).then_some(near.max(0.))
+}
+
+fn cursor_ray(
+    windows: &Query<(Entity, &Window)>,
+    primary_window: &Query<Entity, With<PrimaryWindow>>,
+    cameras: &Query<(Entity, &Camera, &GlobalTransform)>,
+) -> Option<(Entity, Ray3d)> {
+    let primary_window = primary_window.get_single().ok();
+    cameras
+        .iter()
+        .filter(|(_, camera, _)| camera.is_active)
+        .filter_map(|(entity, camera, transform)| {
+            let Some(NormalizedRenderTarget::Window(window)) =
+                camera.target.normalize(primary_window)
+            else {
+                return None;
+            };
+            let (_, window) = windows.get(window.entity()).ok()?;
+            let cursor = window.cursor_position()?;
+            let viewport = camera.logical_viewport_rect()?;
+            if !viewport.contains(cursor) {
+                return None;
+            }
+            let ray = camera.viewport_to_world(transform, cursor - viewport.min)?;
+            Some((camera.order, entity, ray))
+        })
+        .max_by_key(|(order, ..)| *order)
+        .map(|(_, entity, ray)| (entity, ray))
+}
+
+#[allow(clippy::type_complexity)]
+fn update_grid_hover(
+    mut hover: ResMut<InfiniteGridHover>,
+    windows: Query<(Entity, &Window)>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    cameras: Query<(Entity, &Camera, &GlobalTransform)>,
+    grids: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &InfiniteGridSettings,
+            &InheritedVisibility,
+        ),
+        With<InfiniteGrid>,
+    >,
+    occluders: Query<
+        (&GlobalTransform, &Aabb, &InheritedVisibility),
+        (
+            With<Handle<Mesh>>,
+            Without<InfiniteGrid>,
+            Without<NotGridHoverOccluder>,
+        ),
+    >,
+) {
+    hover.hit = cursor_ray(&windows, &primary_window, &cameras).and_then(|(camera, ray)| {
+        let hit = grids
+            .iter()
+            .filter(|(.., visibility)| visibility.get())
+            .filter_map(|(entity, transform, settings, _)| {
+                grid_hit(ray, camera, entity, transform, settings)
+            })
+            .min_by(|a, b| a.depth.total_cmp(&b.depth))?;
+
+        let occluded = occluders
+            .iter()
+            .filter(|(.., visibility)| visibility.get())
+            .filter_map(|(transform, aabb, _)| ray_aabb_distance(ray, transform, aabb))
+            .any(|distance| distance < hit.depth);
+
+        (!occluded).then_some(hit)
+    });
+}
+
+#[derive(Default)]
+struct GridPointerState {
+    previous: Option<GridHit>,
+    pressed: Vec<(MouseButton, GridHit)>,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn send_grid_pointer_events(
+    mut state: Local<GridPointerState>,
+    hover: Res<InfiniteGridHover>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    windows: Query<(Entity, &Window)>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    cameras: Query<(Entity, &Camera, &GlobalTransform)>,
+    grids: Query<(&GlobalTransform, &InfiniteGridSettings)>,
+    mut events: EventWriter<GridPointerEvent>,
+) {
+    let current = hover.hit;
+    let previous = state.previous;
+    let mut send = |grid, kind, hit| {
+        events.send(GridPointerEvent { grid, kind, hit });
+    };
+
+    match (previous, current) {
+        (Some(previous), Some(current)) if previous.grid == current.grid => {
+            if previous.position != current.position {
+                send(current.grid, GridPointerEventKind::Move, current);
+            }
+        }
+        (previous, current) => {
+            if let Some(previous) = previous {
+                send(previous.grid, GridPointerEventKind::Out, previous);
+            }
+            if let Some(current) = current {
+                send(current.grid, GridPointerEventKind::Over, current);
+            }
+        }
+    }
+
+    if let Some(current) = current {
+        for &button in buttons.get_just_pressed() {
+            send(current.grid, GridPointerEventKind::Down(button), current);
+            state.pressed.push((button, current));
+        }
+    }
+
+    // Dragging keeps following the grid plane even when the cursor moves over an occluder
+    // or onto another grid, so the drag target doesn't get lost.
+    let ray = cursor_ray(&windows, &primary_window, &cameras);
+    for (button, start) in state.pressed.iter_mut() {
+        let Some(hit) = ray.and_then(|(camera, ray)| {
+            let (transform, settings) = grids.get(start.grid).ok()?;
+            grid_hit(ray, camera, start.grid, transform, settings)
+        }) else {
+            continue;
+        };
+        if hit.position != start.position {
+            send(
+                hit.grid,
+                GridPointerEventKind::Drag {
+                    button: *button,
+                    delta: hit.position - start.position,
+                },
+                hit,
+            );
+            *start = hit;
+        }
+    }
+
+    state.pressed.retain(|&(button, start)| {
+        if !buttons.just_released(button) {
+            return true;
+        }
+        // The grid that saw the press always gets the release, so drags can't get stuck.
+        match current {
+            Some(current) if current.grid == start.grid => {
+                send(start.grid, GridPointerEventKind::Up(button), current);
+                send(start.grid, GridPointerEventKind::Click(button), current);
+            }
+            _ => send(start.grid, GridPointerEventKind::Up(button), start),
+        }
+        false
+    });
+
+    state.previous = current;
+}
+
+fn send_grid_cell_changed_events(
+    mut hovered_cells: Local<HashMap<Entity, IVec2>>,
+    hover: Res<InfiniteGridHover>,
+    grids: Query<(), With<InfiniteGrid>>,
+    mut events: EventWriter<GridCellChanged>,
+) {
+    let current = hover.hit.map(|hit| (hit.grid, hit.cell));
+
+    hovered_cells.retain(|&grid, &mut old| {
+        let new = current
+            .filter(|&(hit_grid, _)| hit_grid == grid)
+            .map(|(_, cell)| cell);
+        if new != Some(old) && grids.contains(grid) {
+            events.send(GridCellChanged {
+                grid,
+                old: Some(old),
+                new,
+            });
+        }
+        new.is_some()
+    });
+
+    if let Some((grid, cell)) = current {
+        if hovered_cells.insert(grid, cell).is_none() {
+            events.send(GridCellChanged {
+                grid,
+                old: None,
+                new: Some(cell),
+            });
+        }
+    }
+}
@@ -1,76 +1,669 @@
+mod diagnostics;
+mod gizmos;
+mod grid_2d;
+#[cfg(feature = "hover")]
+mod hover;
+mod mesh;
 mod render;
+mod settings;
+#[cfg(feature = "shadows")]
+mod shadow_decal;
+#[cfg(feature = "shadows")]
+mod shadow_occlusion;
+#[cfg(feature = "serialize")]
+mod style;
+mod validation;
 
-pub use render::RenderSettings;
+pub use diagnostics::InfiniteGridDiagnosticsPlugin;
+pub use gizmos::InfiniteGridGizmos;
+pub use grid_2d::{InfiniteGrid2dBundle, InfiniteGrid2dPlugin};
+#[cfg(feature = "hover")]
+pub use hover::{
+    GridCellChanged, GridHit, GridPointerEvent, GridPointerEventKind, InfiniteGridHover,
+    NotGridHoverOccluder,
+};
+pub use render::InfiniteGridPipeline;
+#[cfg(feature = "shadows")]
+pub use render::{
+    GridShadowBindGroup, GridShadowUniformOffset, GridShadowView, RenderSettings, ShadowFilter,
+    ViewGridShadows,
+};
+pub use settings::{
+    GridSettingsTransition, InfiniteGridSettingsBuilder, InfiniteGridSettingsError,
+};
+#[cfg(feature = "shadows")]
+pub use shadow_decal::{
+    GridShadowDecal, GridShadowDecalExtension, GridShadowDecalMaterial, GridShadowDecalPlugin,
+};
+#[cfg(feature = "shadows")]
+pub use shadow_occlusion::{GridShadowOcclusion, GridShadowOcclusionPlugin};
+#[cfg(feature = "serialize")]
+pub use style::{
+    GridSettingsAsset, GridSettingsHandle, GridStyleAsset, GridStyleAssetLoader,
+    GridStyleAssetLoaderError,
+};
 
-use bevy::math::{Vec3Swizzles, Vec4Swizzles};
+use bevy::math::{Vec3A, Vec3Swizzles};
 use bevy::prelude::*;
+use bevy::render::batching::NoAutomaticBatching;
 use bevy::render::primitives::Aabb;
-use bevy::render::view::{VisibilitySystems, VisibleEntities};
+use bevy::render::view::{RenderLayers, VisibilitySystems, VisibleEntities};
+use bevy::transform::TransformSystem;
 use bevy::{pbr::NotShadowCaster, render::view::NoFrustumCulling};
 
-pub struct InfiniteGridPlugin;
+pub struct InfiniteGridPlugin {
+    /// Grid shadows. Without them (or without the `shadows` feature) the shadow pipeline,
+    /// render graph node and caster tracking are never set up, and `shadow` is ignored.
+    pub shadows: bool,
+    /// Cursor hover and pointer events on grids, see `InfiniteGridHover`. Needs the `hover`
+    /// feature.
+    pub cursor_hover: bool,
+}
+
+impl Default for InfiniteGridPlugin {
+    fn default() -> Self {
+        Self {
+            shadows: true,
+            cursor_hover: true,
+        }
+    }
+}
 
 impl Plugin for InfiniteGridPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<GlobalInfiniteGridSettings>();
+        app.init_resource::<GlobalInfiniteGridSettings>()
+            .register_type::<GlobalInfiniteGridSettings>()
+            .register_type::<InfiniteGrid>()
+            .register_type::<InfiniteGridSettings>()
+            .register_type::<InfiniteGridViewOverrides>()
+            .register_type::<GridShadowSettings>()
+            .register_type::<PcfKernel>()
+            .register_type::<FadeMode>()
+            .register_type::<LineStyle>()
+            .register_type::<SubGrid>()
+            .register_type::<GridFrustumIntersect>()
+            .register_type::<GridShadowCamera>()
+            .register_type::<GridQuality>()
+            .register_type::<NoInfiniteGrid>()
+            .register_type::<ReflectionCaptureCamera>()
+            .register_type::<GridShadowUpdateMode>()
+            .register_type::<GridShadowProjection>()
+            .register_type::<GridShadowFormat>()
+            .register_type::<GridShadowUpdate>()
+            .register_type::<NotGridShadowCaster>()
+            .register_type::<GridShadowCasterOnly>()
+            .register_type::<CastsGridShadow>()
+            .register_type::<GridShadowCaster>()
+            .register_type::<GridShadowCasterFilter>()
+            .register_type::<GridShadowMode>()
+            .register_type::<GridShadowOpacity>()
+            .register_type::<DebugGridShadowTexture>()
+            .register_type::<GridHeatmap>()
+            .register_type::<GridFollow>()
+            .register_type::<GridSpin>()
+            .register_type::<FadeoutDistanceOverride>()
+            .register_type::<GridSettingsTransition>()
+            // Reflection based editors like `bevy-inspector-egui` need the optional fields too.
+            .register_type::<Option<GridShadowSettings>>()
+            .register_type::<Option<SubGrid>>()
+            .register_type::<Option<u32>>()
+            .register_type::<Option<Entity>>()
+            .register_type::<Option<Color>>()
+            .register_type::<Option<f32>>()
+            .add_systems(
+                PostUpdate,
+                (
+                    follow_target_system.before(TransformSystem::TransformPropagate),
+                    spin_system.before(TransformSystem::TransformPropagate),
+                    track_frustum_intersect_system
+                        .after(TransformSystem::TransformPropagate)
+                        .after(VisibilitySystems::VisibilityPropagate),
+                    clear_removed_grids,
+                    hide_grid_only_casters_from_lights,
+                    unbatch_translucent_casters,
+                    settings::grid_settings_transition_system,
+                ),
+            );
+
+        #[cfg(feature = "shadows")]
+        app.register_type::<RenderSettings>()
+            .register_type::<ShadowFilter>();
+
+        #[cfg(feature = "hover")]
+        if self.cursor_hover {
+            hover::build(app);
+        }
+
+        #[cfg(feature = "serialize")]
+        style::build(app);
+
+        gizmos::build(app);
+        validation::build(app);
     }
 
     fn finish(&self, app: &mut App) {
-        render::render_app_builder(app);
-        app.add_systems(
-            PostUpdate,
-            (
-                track_frustum_intersect_system,
-                track_caster_visibility.after(VisibilitySystems::CheckVisibility),
-            ),
-        );
+        let shadows = self.shadows && cfg!(feature = "shadows");
+        render::render_app_builder(app, shadows);
+        if shadows {
+            app.add_systems(
+                PostUpdate,
+                track_caster_visibility
+                    .after(VisibilitySystems::CheckVisibility)
+                    .run_if(grid_shadows_enabled),
+            );
+        } else {
+            app.add_systems(PostUpdate, warn_shadows_disabled);
+        }
     }
 }
 
-#[derive(Resource, Default)]
+#[derive(Resource, Default, Reflect)]
+#[reflect(Resource, Default)]
 pub struct GlobalInfiniteGridSettings {
+    /// Shadow rendering settings, synced to the render world whenever they change.
+    #[cfg(feature = "shadows")]
     pub render_settings: RenderSettings,
+    /// Trades grid shadow quality for speed, e.g. from a graphics settings menu. Can be changed
+    /// at any time.
+    pub quality: GridQuality,
+}
+
+/// How much grid shadows may cost, see [`GlobalInfiniteGridSettings::quality`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Default)]
+pub enum GridQuality {
+    /// No grid shadows. Shadow textures are freed and the shadow passes and caster tracking
+    /// stop, as if no grid had `shadow` settings.
+    Low,
+    /// Grid shadows at most [`GridQuality::MEDIUM_MAX_TEXTURE_SIZE`] texels on a side, per grid
+    /// `resolution` included, and without shadow MSAA.
+    Medium,
+    /// Grid shadows as configured.
+    #[default]
+    High,
 }
 
-#[derive(Component, Default)]
+impl GridQuality {
+    pub const MEDIUM_MAX_TEXTURE_SIZE: u32 = 2048;
+
+    pub(crate) fn shadows(self) -> bool {
+        self != Self::Low
+    }
+}
+
+fn grid_shadows_enabled(settings: Res<GlobalInfiniteGridSettings>) -> bool {
+    settings.quality.shadows()
+}
+
+impl GlobalInfiniteGridSettings {
+    fn shadow_area_padding(&self) -> f32 {
+        #[cfg(feature = "shadows")]
+        return self.render_settings.shadow_area_padding.max(1.);
+        #[cfg(not(feature = "shadows"))]
+        1.
+    }
+}
+
+#[derive(Component, Default, Reflect)]
+#[reflect(Component, Default)]
 pub struct InfiniteGrid;
 
-#[derive(Component, Copy, Clone)]
+#[derive(Component, Copy, Clone, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct InfiniteGridSettings {
+    /// Line colors are drawn in linear space and aren't clamped, so on an HDR camera values
+    /// above 1 (e.g. `Color::rgb_linear(0., 4., 4.)`) glow through bloom.
     pub x_axis_color: Color,
     pub z_axis_color: Color,
-    pub shadow_color: Option<Color>,
+    /// Shadows cast onto this grid. `None` skips all shadow work for the grid.
+    pub shadow: Option<GridShadowSettings>,
     pub minor_line_color: Color,
     pub major_line_color: Color,
     pub fadeout_distance: f32,
+    /// Fraction of `fadeout_distance` at which minor lines have faded out, leaving only majors.
+    pub minor_fadeout_ratio: f32,
     pub dot_fadeout_strength: f32,
     pub scale: f32,
+    /// Width in pixels of the antialiased band at line edges. Wider bands look smoother on
+    /// high-DPI displays, narrower ones sharper. Values under half a pixel are treated as half.
+    pub aa_width: f32,
+    /// Moves the drawn plane along its normal, e.g. slightly below a floor mesh to avoid
+    /// z-fighting. Cells, hover hits and shadows stay at the entity's transform.
+    pub plane_offset: f32,
+    /// Finer lines drawn under the grid, like the faint tenths on blueprint paper.
+    pub sub_grid: Option<SubGrid>,
+    /// Draws lines one pixel wide and without antialiasing, on the pixel each line crosses, so
+    /// they stay crisp and stable on small, integer scaled render targets. `aa_width` is unused.
+    pub pixel_snap: bool,
+    /// Hides the grid from cameras below it, on the side its normal points away from.
+    pub cull_underside: bool,
+    /// What `fadeout_distance` and the minor and sub-grid fadeouts are measured from.
+    pub fade_mode: FadeMode,
+    /// Multiplies the color and alpha of the minor and major lines running along the grid's X
+    /// axis, e.g. to make the lines across a runway bolder than those along it. White keeps the
+    /// line colors, values above 1 brighten them.
+    pub x_lines_tint: Color,
+    /// Like `x_lines_tint`, for the lines running along the grid's Z axis.
+    pub z_lines_tint: Color,
+    /// Width in pixels of a soft halo around the lines and axes, in their colors and added to
+    /// what's behind the grid. Brightest with bloom on an HDR camera. 0 draws no halo.
+    pub glow: f32,
+    /// Draws the lines through the origin in `x_axis_color` and `z_axis_color`. Without it they
+    /// look like any other line.
+    pub highlight_axes: bool,
+    /// Hides the grid from [`ReflectionCaptureCamera`]s, so it isn't baked into reflections and
+    /// environment probes. Turn it off to reflect the grid on purpose.
+    pub exclude_from_reflections: bool,
+    /// Where the axes cross and the cells start, in grid space, e.g. a game board's corner while
+    /// the entity sits at its center. Picked cells count from here too. Fades, heatmaps and the
+    /// entity's transform are unaffected.
+    pub grid_origin_offset: Vec2,
+    /// Solid or dashed minor lines.
+    pub minor_line_style: LineStyle,
+    /// Solid or dashed major lines, e.g. dashed majors over solid minors for a blueprint look.
+    pub major_line_style: LineStyle,
 }
 
 impl Default for InfiniteGridSettings {
     fn default() -> Self {
         Self {
-            x_axis_color: Color::rgb(1.0, 0.2, 0.2),
-            z_axis_color: Color::rgb(0.2, 0.2, 1.0),
-            shadow_color: Some(Color::rgba(0.2, 0.2, 0.2, 0.7)),
-            minor_line_color: Color::rgb(0.1, 0.1, 0.1),
-            major_line_color: Color::rgb(0.25, 0.25, 0.25),
+            x_axis_color: Color::rgb_linear(1.0, 0.2, 0.2),
+            z_axis_color: Color::rgb_linear(0.2, 0.2, 1.0),
+            shadow: cfg!(feature = "shadows").then(GridShadowSettings::default),
+            minor_line_color: Color::rgb_linear(0.1, 0.1, 0.1),
+            major_line_color: Color::rgb_linear(0.25, 0.25, 0.25),
             fadeout_distance: 100.,
+            minor_fadeout_ratio: 1.,
             dot_fadeout_strength: 0.25,
             scale: 1.,
+            aa_width: 1.,
+            plane_offset: 0.,
+            sub_grid: None,
+            pixel_snap: false,
+            cull_underside: false,
+            fade_mode: FadeMode::default(),
+            x_lines_tint: Color::WHITE,
+            z_lines_tint: Color::WHITE,
+            glow: 0.,
+            highlight_axes: true,
+            exclude_from_reflections: true,
+            grid_origin_offset: Vec2::ZERO,
+            minor_line_style: LineStyle::Solid,
+            major_line_style: LineStyle::Solid,
         }
     }
 }
 
-#[derive(Component, Default, Clone, Copy, Debug)]
+/// Changes how a camera sees every grid, e.g. bolder lines and no minor lines on a minimap.
+/// Fields left `None` keep the grid's own setting.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_infinite_grid::InfiniteGridViewOverrides;
+/// # fn setup(mut commands: Commands) {
+/// commands.spawn((
+///     Camera3dBundle::default(),
+///     InfiniteGridViewOverrides {
+///         minor_line_color: Some(Color::NONE),
+///         aa_width: Some(3.),
+///         ..default()
+///     },
+/// ));
+/// # }
+/// ```
+///
+/// Applied on top of `InfiniteGridSettings` on the camera, if any. Shadows keep the grid's own
+/// fadeout distance.
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct InfiniteGridViewOverrides {
+    pub x_axis_color: Option<Color>,
+    pub z_axis_color: Option<Color>,
+    pub minor_line_color: Option<Color>,
+    pub major_line_color: Option<Color>,
+    pub fadeout_distance: Option<f32>,
+    pub minor_fadeout_ratio: Option<f32>,
+    pub dot_fadeout_strength: Option<f32>,
+    pub aa_width: Option<f32>,
+}
+
+impl InfiniteGridViewOverrides {
+    /// `settings` with the overridden fields replaced.
+    pub fn apply(&self, settings: &InfiniteGridSettings) -> InfiniteGridSettings {
+        InfiniteGridSettings {
+            x_axis_color: self.x_axis_color.unwrap_or(settings.x_axis_color),
+            z_axis_color: self.z_axis_color.unwrap_or(settings.z_axis_color),
+            minor_line_color: self.minor_line_color.unwrap_or(settings.minor_line_color),
+            major_line_color: self.major_line_color.unwrap_or(settings.major_line_color),
+            fadeout_distance: self.fadeout_distance.unwrap_or(settings.fadeout_distance),
+            minor_fadeout_ratio: self
+                .minor_fadeout_ratio
+                .unwrap_or(settings.minor_fadeout_ratio),
+            dot_fadeout_strength: self
+                .dot_fadeout_strength
+                .unwrap_or(settings.dot_fadeout_strength),
+            aa_width: self.aa_width.unwrap_or(settings.aa_width),
+            ..*settings
+        }
+    }
+}
+
+/// A second set of lines between the minor lines of a grid.
+#[derive(Copy, Clone, PartialEq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct SubGrid {
+    pub color: Color,
+    /// Sub-grid lines per minor line, 10 draws tenths.
+    pub scale_ratio: f32,
+    /// Fraction of `fadeout_distance` at which the sub-grid has faded out.
+    pub fadeout_ratio: f32,
+}
+
+impl Default for SubGrid {
+    fn default() -> Self {
+        Self {
+            color: Color::rgba_linear(0.1, 0.1, 0.1, 0.3),
+            scale_ratio: 10.,
+            fadeout_ratio: 0.2,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct GridShadowSettings {
+    /// What shadowed areas are tinted with, or with [`GridShadowMode::Lighten`] the light added.
+    pub color: Color,
+    pub mode: GridShadowMode,
+    /// Scales how dark the shadow gets, 0 removes it.
+    pub intensity: f32,
+    /// Fraction of the shadow area, from its edges inward, over which the shadow fades in.
+    /// Zero keeps a hard edge at the end of the shadow texture.
+    pub falloff: f32,
+    /// Fades a caster's shadow with its height above the grid, coverage is scaled by
+    /// `exp(-height_falloff * height)`. Zero keeps floating objects as dark as resting ones.
+    pub height_falloff: f32,
+    /// World space radius over which the shadow texture is filtered (PCF). Zero samples it once.
+    pub softness: f32,
+    /// Radius added to `softness` per unit of height of the closest caster, so shadows blur as
+    /// their casters rise off the grid. Non-zero values store caster heights in the shadow
+    /// texture, which then uses `Rg16Float` whatever the `format`.
+    pub height_softness: f32,
+    /// Largest filter radius `height_softness` can reach, which is also how far around each
+    /// point casters are looked for.
+    pub max_softness: f32,
+    /// Taps per side of the filter kernel: 1, 3 or 5. 1 turns filtering off.
+    pub pcf_size: u32,
+    pub pcf_kernel: PcfKernel,
+    /// Longest side of this grid's shadow texture, overriding `RenderSettings::max_texture_size`.
+    pub resolution: Option<u32>,
+    /// Casters whose bounds land further than this (plus `softness`) outside the shadowed area
+    /// are skipped.
+    pub cull_margin: f32,
+    pub update_mode: GridShadowUpdateMode,
+    /// Distance along the grid's normal from the grid to the shadow projection. Casters above it
+    /// are left out of the shadow.
+    pub projection_height: f32,
+    /// Clipping range of the shadow projection, measured down from `projection_height`. Casters
+    /// outside it are left out of the shadow, so `far` should reach past the grid for casters
+    /// that sink below it.
+    pub near: f32,
+    pub far: f32,
+    pub projection: GridShadowProjection,
+    pub format: GridShadowFormat,
+    /// Lines the shadow texture up with the grid's axes and moves it in whole texels, so shadow
+    /// edges stay put while the camera moves instead of crawling. Costs up to a fifth of the
+    /// resolution, and the texture is resized in steps.
+    pub texel_snap: bool,
+    /// 2 adds a second shadow texture covering only the grid within `cascade_distance` of the
+    /// camera, so nearby shadows stay sharp when the grid reaches far towards the horizon. 1 uses
+    /// one texture for everything.
+    pub cascades: u32,
+    /// How far from the camera the near cascade reaches, like `fadeout_distance` does for the
+    /// whole grid. The cascades blend over a narrow band at its edge.
+    pub cascade_distance: f32,
+    /// Which meshes cast this grid's shadow, by [`CastsGridShadow`] tag.
+    pub caster_filter: GridShadowCasterFilter,
+}
+
+impl Default for GridShadowSettings {
+    fn default() -> Self {
+        Self {
+            color: Color::rgba_linear(0.2, 0.2, 0.2, 0.7),
+            mode: GridShadowMode::default(),
+            intensity: 1.,
+            falloff: 0.,
+            height_falloff: 0.,
+            softness: 0.,
+            height_softness: 0.,
+            max_softness: 2.,
+            pcf_size: 3,
+            pcf_kernel: PcfKernel::default(),
+            resolution: None,
+            cull_margin: 1.,
+            update_mode: GridShadowUpdateMode::default(),
+            projection_height: 500.,
+            near: 0.,
+            far: 1000.,
+            projection: GridShadowProjection::default(),
+            format: GridShadowFormat::default(),
+            texel_snap: false,
+            cascades: 1,
+            cascade_distance: 20.,
+            caster_filter: GridShadowCasterFilter::default(),
+        }
+    }
+}
+
+/// How a grid's shadow is drawn over it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum GridShadowMode {
+    /// Shadowed areas are covered with the shadow color.
+    #[default]
+    Darken,
+    /// The shadow color, scaled by its alpha, is added to shadowed areas and whatever is seen
+    /// through the grid, e.g. for pools of light under lamps.
+    Lighten,
+}
+
+/// Which meshes cast a grid's shadow. [`NotGridShadowCaster`] meshes never do.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum GridShadowCasterFilter {
+    #[default]
+    All,
+    /// Only meshes tagged with [`CastsGridShadow`], for scenes where few meshes matter.
+    Only,
+    /// Every mesh but those tagged with [`CastsGridShadow`], to leave them out of this grid's
+    /// shadow only.
+    Except,
+}
+
+/// What a grid's fadeout distances are measured from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum FadeMode {
+    /// Depth from the camera, fading towards the horizon. Seen from above, the grid barely fades.
+    #[default]
+    Camera,
+    /// Distance across the grid from `center`, whatever the camera angle. A round patch of grid
+    /// for top-down and 2.5D views, e.g. under the player. `center` is in world units along the
+    /// grid's local X and Z axes, unaffected by `scale`.
+    RadialFromPoint { center: Vec2 },
+}
+
+/// How a grid's lines are drawn, see [`InfiniteGridSettings::major_line_style`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum LineStyle {
+    #[default]
+    Solid,
+    /// Dashes `dash` long with `gap` between them, both in grid units along the line. Where a
+    /// dash and its gap shrink to a few pixels, e.g. towards the horizon, the dashes blend
+    /// smoothly into a fainter solid line instead of aliasing. A `dash` or `gap` of 0 draws
+    /// solid lines.
+    Dashed { dash: f32, gap: f32 },
+}
+
+/// Texture format of a grid's shadow texture.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum GridShadowFormat {
+    /// 256 shadow levels, which can band under soft or height faded shadows.
+    #[default]
+    R8Unorm,
+    /// Half floats, smooth gradients at twice the memory.
+    R16Float,
+    /// Like `R16Float` with a spare channel, at four times the memory of `R8Unorm`.
+    Rg16Float,
+}
+
+/// Direction along which casters are projected onto the grid.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum GridShadowProjection {
+    /// Straight down the grid's normal.
+    #[default]
+    PlaneNormal,
+    /// Along a `DirectionalLight` entity, so grid shadows line up with its own shadows. Falls
+    /// back to `PlaneNormal` while the entity doesn't exist.
+    FollowLight(Entity),
+    /// Along a world space direction, pointing from the light towards the grid.
+    Direction(Vec3),
+}
+
+impl GridShadowProjection {
+    /// Light closer to grazing than about 76° from the normal is steepened to it, so shadows
+    /// don't stretch out to infinity.
+    const MAX_SLOPE: f32 = 4.;
+
+    /// How far across the grid a point's shadow lands per unit of height above the grid.
+    pub(crate) fn shear(
+        &self,
+        normal: Vec3,
+        light_direction: impl Fn(Entity) -> Option<Vec3>,
+    ) -> Vec3 {
+        let direction = match *self {
+            Self::PlaneNormal => return Vec3::ZERO,
+            Self::FollowLight(light) => match light_direction(light) {
+                Some(direction) => direction,
+                None => return Vec3::ZERO,
+            },
+            Self::Direction(direction) => direction,
+        };
+        let tangent = direction.reject_from_normalized(normal);
+        // Light from below the grid gets the steepest slope instead of flipping.
+        let slope = tangent.length() / (-direction.dot(normal)).max(f32::EPSILON);
+        tangent.normalize_or_zero() * slope.min(Self::MAX_SLOPE)
+    }
+}
+
+/// When a grid's shadow texture is rendered. Between renders the grid keeps sampling the last one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum GridShadowUpdateMode {
+    #[default]
+    EveryFrame,
+    /// When a caster moves, appears or disappears, the shadowed area moves, or the grid changes.
+    OnChange,
+    /// Only on [`GridShadowUpdate::force`], or when the shadow texture has to be recreated,
+    /// e.g. after a resize.
+    Manual,
+}
+
+/// Counts changes to a grid's shadow for [`GridShadowUpdateMode`]. The plugin inserts it on grids
+/// with shadows.
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct GridShadowUpdate {
+    pub(crate) changes: u32,
+    pub(crate) requests: u32,
+}
+
+impl GridShadowUpdate {
+    /// Renders the grid's shadow next frame, whatever its update mode.
+    pub fn force(&mut self) {
+        self.requests = self.requests.wrapping_add(1);
+    }
+}
+
+/// Where the taps of the grid shadow filter land.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum PcfKernel {
+    /// A regular grid of taps.
+    #[default]
+    Box,
+    /// Taps spread over a disk, trading the box's banding for noise.
+    Poisson,
+}
+
+/// The part of a grid a camera sees, up to the fadeout distance, e.g. to show the view's extent
+/// on a minimap.
+///
+/// The plugin keeps it on every grid some active `Camera3d` draws, following the lowest order
+/// [`GridShadowCamera`], or the lowest order camera without one. Updated in `PostUpdate` after
+/// transform propagation, and [empty](Self::is_empty) while no camera draws the grid.
+#[derive(Component, Default, Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Component, Default)]
 pub struct GridFrustumIntersect {
-    pub points: [Vec3; 4],
+    /// See [`points`](Self::points), only the first `point_count` are set.
+    points: [Vec3; Self::MAX_POINTS],
+    point_count: usize,
+    /// The smallest rectangle around the points, its center on the plane, its `height` along
+    /// `up_dir` pointing away from the camera and its `width` across.
     pub center: Vec3,
     pub up_dir: Vec3,
     pub width: f32,
     pub height: f32,
 }
 
+/// Replaces the grid's `fadeout_distance`, so grids of different sizes can share one
+/// [`InfiniteGridSettings`] value.
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+pub struct FadeoutDistanceOverride(pub f32);
+
+impl FadeoutDistanceOverride {
+    pub(crate) fn apply(fadeout: Option<&Self>, settings: &InfiniteGridSettings) -> f32 {
+        fadeout.map_or(settings.fadeout_distance, |fadeout| fadeout.0)
+    }
+}
+
+/// Everything needed for a visible grid. The default is a grid on the XZ plane through the origin.
+///
+/// `frustum_intersect` is filled in by the plugin while a camera draws the grid.
+/// Add `RenderLayers` to draw the grid only for cameras on those layers; only meshes sharing a
+/// layer with the grid cast shadows on it.
+///
+/// The transform's X and Z scale stretch the cells along the grid's axes, e.g. a scale of
+/// `Vec3::new(2., 1., 3.)` draws 2 by 3 cells. Grid space positions, like hover hits and
+/// heatmap extents, are in these scaled units. The Y scale is ignored, shadows and fadeout
+/// distances are unaffected. Neither X nor Z scale may be zero.
 #[derive(Bundle, Default)]
 pub struct InfiniteGridBundle {
     pub transform: Transform,
@@ -85,6 +678,27 @@ pub struct InfiniteGridBundle {
     pub no_frustum_culling: NoFrustumCulling,
 }
 
+/// Whether `projection` is orthographic, the same test as Bevy's shaders.
+pub(crate) fn is_orthographic(projection: &Mat4) -> bool {
+    projection.w_axis.w == 1.
+}
+
+// Sutherland-Hodgman, keeping the part of a convex outline where `distance` isn't negative.
+fn clip_outline(outline: &[Vec3], distance: impl Fn(Vec3) -> f32) -> Vec<Vec3> {
+    let mut clipped = Vec::with_capacity(outline.len() + 1);
+    for (i, &point) in outline.iter().enumerate() {
+        let next = outline[(i + 1) % outline.len()];
+        let (point_distance, next_distance) = (distance(point), distance(next));
+        if point_distance >= 0. {
+            clipped.push(point);
+        }
+        if (point_distance >= 0.) != (next_distance >= 0.) {
+            clipped.push(point.lerp(next, point_distance / (point_distance - next_distance)));
+        }
+    }
+    clipped
+}
+
 pub fn calculate_distant_from(
     cam: &GlobalTransform,
     grid: &GlobalTransform,
@@ -98,7 +712,8 @@ pub fn calculate_distant_from(
     let inverse_rot = grid_rot.inverse();
 
     let gs_cam_pos = (inverse_rot * (cam_pos - grid.translation())).xz();
-    let gs_cam_dir = (inverse_rot * cam_dir).xz().normalize();
+    // Zero when the camera faces the grid head on, which a spinning grid passes through.
+    let gs_cam_dir = (inverse_rot * cam_dir).xz().normalize_or_zero();
 
     let h = (cam_pos - grid.translation()).dot(grid.up()).abs();
     let s = 1. / view_distance;
@@ -123,131 +738,898 @@ pub fn calculate_distant_from(
     grid.translation() + pos_in_3d_gs
 }
 
-#[derive(Component)]
+/// Cameras with this component draw no grids, e.g. a camera rendering thumbnails. They render no
+/// grid shadows either, even with [`GridShadowCamera`].
+///
+/// To hide only some grids from a camera, give the grids and the camera `RenderLayers` instead.
+#[derive(Component, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct NoInfiniteGrid;
+
+/// Marks cameras that capture reflections or environment probes, e.g. a mirror camera rendering
+/// a shiny floor's reflection. They don't draw grids with
+/// [`InfiniteGridSettings::exclude_from_reflections`], and render no grid shadows.
+#[derive(Component, Clone, Copy, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct ReflectionCaptureCamera;
+
+/// Whether a camera draws a grid, going by [`NoInfiniteGrid`] and `RenderLayers`.
+pub(crate) fn camera_sees_grid(
+    camera_layers: Option<&RenderLayers>,
+    no_grid: bool,
+    grid_layers: Option<&RenderLayers>,
+) -> bool {
+    !no_grid
+        && camera_layers
+            .copied()
+            .unwrap_or_default()
+            .intersects(&grid_layers.copied().unwrap_or_default())
+}
+
+/// Cameras with this component render grid shadows.
+///
+/// Every shadow camera gets its own shadow textures, so several windows can each show shadows.
+#[derive(Component, Default, Reflect)]
+#[reflect(Component, Default)]
 pub struct GridShadowCamera;
 
-fn track_frustum_intersect_system(
+/// Meshes with this component cast no grid shadow. Unlike `NotShadowCaster`, they still cast
+/// light shadows, which suits helpers like gizmos, cursors and preview ghosts.
+///
+/// Takes precedence over [`GridShadowCasterOnly`] when an entity has both.
+#[derive(Component, Clone, Copy, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct NotGridShadowCaster;
+
+#[deprecated(note = "renamed to NotGridShadowCaster")]
+pub type NoGridShadow = NotGridShadowCaster;
+
+/// Meshes with this component cast a grid shadow but no light shadow.
+///
+/// The plugin inserts `NotShadowCaster` alongside it, and removes it again along with this or
+/// once [`NotGridShadowCaster`] is added. A `NotShadowCaster` of your own is left alone.
+#[derive(Component, Clone, Copy, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct GridShadowCasterOnly;
+
+/// Tags a mesh for [`GridShadowCasterFilter::Only`] and [`GridShadowCasterFilter::Except`].
+///
+/// Only the tagged entity is affected. Add [`propagate_casts_grid_shadow`] to tag the meshes of
+/// whole scenes through their root.
+#[derive(Component, Clone, Copy, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct CastsGridShadow;
+
+/// Meshes with this component cast their grid shadow through the material-free pipeline, which
+/// binds only the view and the mesh. Meant for meshes drawn by a custom pipeline rather than a
+/// `Material`, like instanced foliage; their `StandardMaterial`, if any, is ignored, so masked
+/// ones cast a solid shadow.
+#[derive(Component, Clone, Copy, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct GridShadowCaster;
+
+/// Opt-in system tagging every descendant of a [`CastsGridShadow`] entity, including children
+/// spawned later like those of a loading scene. Removing the tag from the root leaves the
+/// descendants tagged.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_infinite_grid::propagate_casts_grid_shadow;
+/// # let mut app = App::new();
+/// app.add_systems(PostUpdate, propagate_casts_grid_shadow);
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn propagate_casts_grid_shadow(
     mut commands: Commands,
-    mut grids: Query<
+    roots: Query<
+        Entity,
         (
-            Entity,
-            &GlobalTransform,
-            &InfiniteGridSettings,
-            Option<&mut GridFrustumIntersect>,
+            With<CastsGridShadow>,
+            Or<(Added<CastsGridShadow>, Changed<Children>)>,
         ),
-        With<InfiniteGrid>,
     >,
-    camera: Query<(&GlobalTransform, &Camera), With<GridShadowCamera>>,
+    children: Query<&Children>,
+    tagged: Query<(), With<CastsGridShadow>>,
 ) {
-    if camera.is_empty() {
-        return;
+    for root in roots.iter() {
+        for descendant in children.iter_descendants(root) {
+            if !tagged.contains(descendant) {
+                commands.entity(descendant).insert(CastsGridShadow);
+            }
+        }
     }
+}
 
-    let (cam_pos, cam) = camera.single();
+/// Marks a `NotShadowCaster` inserted for [`GridShadowCasterOnly`], so only that one is removed
+/// again.
+#[derive(Component)]
+struct GridOnlyNotShadowCaster;
 
-    let view = cam_pos.compute_matrix();
-    let inverse_view = view.inverse();
-    let reverse_proj = cam.projection_matrix().inverse();
+/// Which meshes cast a grid shadow, before the grid's own [`GridShadowCasterFilter`].
+type GridShadowCasters = (
+    Without<NotGridShadowCaster>,
+    Or<(Without<NotShadowCaster>, With<GridShadowCasterOnly>)>,
+);
 
-    for (entity, grid, grid_params, intersects) in grids.iter_mut() {
-        let mut slot = None;
-        let intersects = match (grid_params.shadow_color.is_none(), intersects) {
-            (true, None) => {
-                continue;
-            }
-            (true, Some(_)) => {
-                commands.entity(entity).remove::<GridFrustumIntersect>();
-                continue;
-            }
-            (false, None) => {
-                slot = Some(GridFrustumIntersect::default());
-                slot.as_mut().unwrap()
-            }
-            (false, Some(val)) => val.into_inner(),
-        };
-        let distant_point = calculate_distant_from(cam_pos, grid, grid_params.fadeout_distance);
-        let projected = cam.projection_matrix() * inverse_view * distant_point.extend(1.);
-        let coords = projected.xyz() / projected.w;
+#[allow(clippy::type_complexity)]
+fn hide_grid_only_casters_from_lights(
+    mut commands: Commands,
+    casters: Query<
+        Entity,
+        (
+            With<GridShadowCasterOnly>,
+            Without<NotGridShadowCaster>,
+            Without<NotShadowCaster>,
+        ),
+    >,
+    overridden: Query<Entity, (With<GridOnlyNotShadowCaster>, With<NotGridShadowCaster>)>,
+    inserted: Query<(), With<GridOnlyNotShadowCaster>>,
+    mut removed: RemovedComponents<GridShadowCasterOnly>,
+) {
+    for entity in casters.iter() {
+        commands
+            .entity(entity)
+            .insert((NotShadowCaster, GridOnlyNotShadowCaster));
+    }
+    // `NotGridShadowCaster` wins, making the mesh a plain light shadow caster again.
+    let restored = removed
+        .read()
+        .filter(|&entity| inserted.contains(entity))
+        .chain(overridden.iter());
+    for entity in restored {
+        if let Some(mut entity) = commands.get_entity(entity) {
+            entity.remove::<(NotShadowCaster, GridOnlyNotShadowCaster)>();
+        }
+    }
+}
 
-        let horizon_sign = (cam_pos.translation() - grid.translation())
-            .dot(grid.up())
-            .signum();
+/// Scales how dark a mesh's grid shadow is, so preview ghosts can cast a faint one. Clamped to
+/// `0..=1`, meshes without it cast a full shadow.
+///
+/// The plugin inserts `NoAutomaticBatching` alongside it, so each such mesh is drawn on its own.
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component, Default)]
+pub struct GridShadowOpacity(pub f32);
 
-        let horizon = if (-1.0..1.0).contains(&coords.y) {
-            coords.y
-        } else {
-            horizon_sign
-        };
-        // let horizon = horizon_sign;
+impl Default for GridShadowOpacity {
+    fn default() -> Self {
+        Self(1.)
+    }
+}
 
-        let seeds = [
-            Vec2::new(1., horizon),
-            Vec2::new(1., -horizon_sign),
-            Vec2::new(-1., -horizon_sign),
-            Vec2::new(-1., horizon),
-        ];
+// Opacity is bound per draw, and a batched draw only binds it for its first mesh.
+fn unbatch_translucent_casters(
+    mut commands: Commands,
+    casters: Query<Entity, (Added<GridShadowOpacity>, Without<NoAutomaticBatching>)>,
+) {
+    for entity in casters.iter() {
+        commands.entity(entity).insert(NoAutomaticBatching);
+    }
+}
+
+/// Draws a grid's shadow texture into `image` every frame, to look at while tuning shadow
+/// settings. Coverage shows as white, stretched over the whole image.
+///
+/// The image needs `TextureUsages::RENDER_ATTACHMENT`, and `TEXTURE_BINDING` to be displayed.
+/// `camera` picks whose shadow to show, any shadow camera's when `None`.
+#[derive(Component, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct DebugGridShadowTexture {
+    pub image: Handle<Image>,
+    pub camera: Option<Entity>,
+}
+
+/// Tints the grid's cells by `image`, e.g. to show data on the grid like a heatmap.
+///
+/// The image is stretched over the `extent` grid space units from `origin`, its first row along
+/// `origin` and its first column along the grid's X axis. Each cell takes the color, and the
+/// alpha times `opacity`, at its center, so a texel per cell gives a flat color per cell. With
+/// `tint_lines` the lines are tinted too, by the color under them. Cells off the image are left
+/// as they are.
+///
+/// The image is sampled with its own sampler, and needs `TextureUsages::TEXTURE_BINDING`.
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component, Default)]
+pub struct GridHeatmap {
+    pub image: Handle<Image>,
+    pub origin: Vec2,
+    pub extent: Vec2,
+    pub opacity: f32,
+    pub tint_lines: bool,
+}
+
+impl Default for GridHeatmap {
+    fn default() -> Self {
+        Self {
+            image: Handle::default(),
+            origin: Vec2::ZERO,
+            extent: Vec2::ONE,
+            opacity: 1.,
+            tint_lines: false,
+        }
+    }
+}
+
+/// Recenters the grid under `target` every frame, in steps of the major line spacing so the
+/// lines don't appear to move. The grid keeps its rotation and its height along its normal.
+///
+/// Keeps the grid near the target in shader space, so precision holds far from the world origin.
+/// The axis lines follow along. The grid entity must not have a parent.
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+pub struct GridFollow {
+    pub target: Entity,
+}
+
+/// Rotates the grid around `axis`, in the grid's parent space, by `radians_per_sec` every second,
+/// e.g. for a loading screen. Shadows follow the rotation.
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+pub struct GridSpin {
+    pub axis: Vec3,
+    pub radians_per_sec: f32,
+}
+
+impl GridFrustumIntersect {
+    /// Most points an outline can have: the plane cuts up to six faces of the view frustum, and
+    /// an orthographic view's fadeout up to four more.
+    pub const MAX_POINTS: usize = 10;
+
+    pub(crate) fn new(
+        cam_pos: &GlobalTransform,
+        projection: Mat4,
+        grid: &GlobalTransform,
+        fadeout_distance: f32,
+    ) -> Self {
+        let view = cam_pos.compute_matrix();
+        let inverse_view = view.inverse();
+        // Everything goes through the inverse view projection, so custom projections work too,
+        // e.g. oblique or off-center ones with off-diagonal terms.
+        let inverse_view_projection = view * projection.inverse();
+        let unproject = |ndc: Vec3| inverse_view_projection.project_point3(ndc);
+        let view_space = |point: Vec3| inverse_view.transform_point3(point);
 
         let plane_normal = grid.up();
         let plane_origin = grid.translation();
+        let height = |point: Vec3| (point - plane_origin).dot(plane_normal);
 
-        let points = seeds.map(|sp| {
-            let val = view * reverse_proj * sp.extend(1.).extend(1.);
-            let near_point = val.xyz() / val.w;
-            let val = view * reverse_proj * sp.extend(0.001).extend(1.);
-            let far_point = val.xyz() / val.w;
+        let corners = [
+            Vec2::new(-1., -1.),
+            Vec2::new(1., -1.),
+            Vec2::new(1., 1.),
+            Vec2::new(-1., 1.),
+        ];
+        let near = corners.map(|corner| unproject(corner.extend(1.)));
+        let orthographic = is_orthographic(&projection);
+        let central_ray = (unproject(Vec3::new(0., 0., 0.5)) - unproject(Vec3::Z)).normalize();
+        // Looking down on the plane lifts the fadeout, see the shader.
+        let (far, fade_limit) = if orthographic {
+            // Parallel rays fade with the distance from the view's axis, down to zero at the
+            // fadeout distance over one minus the cosine to the plane.
+            let lift = 1. - central_ray.dot(plane_normal).abs();
+            let far = corners.map(|corner| unproject(corner.extend(0.)));
+            (far, (lift > 1e-4).then_some(fadeout_distance / lift))
+        } else {
+            // Rays from the eye fade with the view depth, down to zero at the fadeout distance
+            // plus the camera's height over the plane. The far corners stop there, as an infinite
+            // projection has no far plane to stop at.
+            let max_depth = fadeout_distance + height(cam_pos.translation()).abs();
+            let depth = |point: Vec3| -view_space(point).z;
+            let mut far = near;
+            for (far, corner) in far.iter_mut().zip(corners) {
+                let through = unproject(corner.extend(0.5));
+                let (near_depth, through_depth) = (depth(*far), depth(through));
+                *far = far.lerp(
+                    through,
+                    (max_depth - near_depth) / (through_depth - near_depth),
+                );
+            }
+            (far, Some(max_depth))
+        };
 
-            let ray_origin = near_point;
-            let ray_direction = (far_point - near_point).normalize();
+        // The plane crosses the edges of the frustum at the corners of its outline.
+        let mut outline: Vec<Vec3> = (0..4)
+            .flat_map(|i| {
+                let next = (i + 1) % 4;
+                [
+                    (near[i], near[next]),
+                    (far[i], far[next]),
+                    (near[i], far[i]),
+                ]
+            })
+            .filter_map(|(a, b)| {
+                let (height_a, height_b) = (height(a), height(b));
+                ((height_a <= 0.) != (height_b <= 0.))
+                    .then(|| a.lerp(b, height_a / (height_a - height_b)))
+            })
+            .collect();
+        let centroid = outline.iter().sum::<Vec3>() / outline.len().max(1) as f32;
+        let (right, forward) = (grid.right(), grid.forward());
+        let angle = |point: &Vec3| {
+            let offset = *point - centroid;
+            offset.dot(forward).atan2(offset.dot(right))
+        };
+        outline.sort_by(|a, b| angle(a).total_cmp(&angle(b)));
+        match (orthographic, fade_limit) {
+            (false, Some(max_depth)) => {
+                outline = clip_outline(&outline, |point| max_depth + view_space(point).z);
+            }
+            (true, Some(max_distance)) => {
+                for axis in [Vec3::X, Vec3::NEG_X, Vec3::Y, Vec3::NEG_Y] {
+                    outline =
+                        clip_outline(&outline, |point| max_distance - view_space(point).dot(axis));
+                }
+            }
+            _ => {}
+        }
+        outline.truncate(Self::MAX_POINTS);
 
-            let denominator = ray_direction.dot(plane_normal);
-            let point_to_point = plane_origin - ray_origin;
-            let t = plane_normal.dot(point_to_point) / denominator;
+        // Away from the camera along the plane, or towards the top of the view looking straight
+        // down.
+        let far_dir = central_ray
+            .reject_from(plane_normal)
+            .try_normalize()
+            .unwrap_or_else(|| cam_pos.up().reject_from(plane_normal).normalize_or_zero());
+        let mut points = [Vec3::ZERO; Self::MAX_POINTS];
+        points[..outline.len()].copy_from_slice(&outline);
+        let mut intersect = Self {
+            points,
+            point_count: outline.len(),
+            center: if outline.is_empty() {
+                plane_origin + (cam_pos.translation() - plane_origin).reject_from(plane_normal)
+            } else {
+                centroid
+            },
+            up_dir: far_dir,
+            width: 0.,
+            height: 0.,
+        };
 
-            ray_direction * t + ray_origin
-        });
+        // The smallest rectangle around a convex polygon has a side along one of its edges, so
+        // try each edge and keep the one wasting the least shadow texture.
+        let mut best_area = f32::INFINITY;
+        for i in 0..outline.len() {
+            let edge = (outline[(i + 1) % outline.len()] - outline[i])
+                .reject_from(plane_normal)
+                .normalize_or_zero();
+            if edge == Vec3::ZERO {
+                continue;
+            }
+            // Of the rectangle's axes, the one closest to pointing away from the camera is up.
+            let side = plane_normal.cross(edge);
+            let up_dir = [edge, -edge, side, -side]
+                .into_iter()
+                .max_by(|a, b| a.dot(far_dir).total_cmp(&b.dot(far_dir)))
+                .unwrap();
+            let right = plane_normal.cross(up_dir);
+            let (min, max) = outline
+                .iter()
+                .fold((Vec2::MAX, Vec2::MIN), |(min, max), point| {
+                    let point = Vec2::new(point.dot(right), point.dot(up_dir));
+                    (min.min(point), max.max(point))
+                });
+            let size = max - min;
+            if size.x * size.y < best_area {
+                best_area = size.x * size.y;
+                let mid = (min + max) / 2.;
+                intersect.center =
+                    right * mid.x + up_dir * mid.y + plane_normal * plane_origin.dot(plane_normal);
+                intersect.up_dir = up_dir;
+                intersect.width = size.x;
+                intersect.height = size.y;
+            }
+        }
+        intersect
+    }
 
-        intersects.points = points;
-        intersects.center = points.iter().sum::<Vec3>() / 4.;
-        intersects.up_dir = ((points[0] + points[3]) - (points[1] + points[2])).normalize();
+    /// Outline of the part of the plane in view, up to where the grid fades out, in order around
+    /// it. Empty when the view doesn't see the plane.
+    pub fn points(&self) -> &[Vec3] {
+        &self.points[..self.point_count]
+    }
 
-        intersects.height = (points[0] - points[1]).dot(intersects.up_dir);
-        let w1 = points[0].distance_squared(points[3]);
-        let w2 = points[1].distance_squared(points[2]);
-        intersects.width = w1.max(w2).sqrt();
+    /// Whether the area has no extent, or no finite one, e.g. while the view looks away from the
+    /// plane. Such grids get no shadow that frame.
+    pub fn is_empty(&self) -> bool {
+        !(self.width > 0.
+            && self.height > 0.
+            && self.width.is_finite()
+            && self.height.is_finite()
+            && self.center.is_finite()
+            && self.up_dir.is_finite())
+    }
+
+    /// Scales the area around its center by `padding`.
+    pub(crate) fn padded(self, padding: f32) -> Self {
+        Self {
+            width: self.width * padding,
+            height: self.height * padding,
+            ..self
+        }
+    }
 
-        if let Some(intersect) = slot {
-            commands.entity(entity).insert(intersect);
+    /// Whether a caster's bounds, projected onto the grid along its shadow projection, come
+    /// within `margin` of the shadowed area.
+    pub(crate) fn overlaps(
+        &self,
+        normal: Vec3,
+        shear: Vec3,
+        transform: &GlobalTransform,
+        aabb: &Aabb,
+        margin: f32,
+    ) -> bool {
+        let (normal, shear) = (Vec3A::from(normal), Vec3A::from(shear));
+        // Moves a point (or box axis) to where its shadow lands, keeping its height.
+        let project = |v: Vec3A| v + shear * v.dot(normal);
+        let affine = transform.affine();
+        let offset = project(affine.transform_point3a(aabb.center) - Vec3A::from(self.center));
+        let half = aabb.half_extents;
+        let axes = [
+            project(affine.matrix3.x_axis * half.x),
+            project(affine.matrix3.y_axis * half.y),
+            project(affine.matrix3.z_axis * half.z),
+        ];
+        let overlaps_along = |axis: Vec3A, size: f32| {
+            let extent: f32 = axes.iter().map(|v| v.dot(axis).abs()).sum();
+            offset.dot(axis).abs() <= size / 2. + extent + margin
+        };
+        overlaps_along(normal.cross(self.up_dir.into()), self.width)
+            && overlaps_along(self.up_dir.into(), self.height)
+    }
+}
+
+// The render world computes its own intersect per shadow camera, this one is for the main world.
+#[allow(clippy::type_complexity)]
+fn track_frustum_intersect_system(
+    mut commands: Commands,
+    mut grids: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &InfiniteGridSettings,
+            Option<&FadeoutDistanceOverride>,
+            Option<&RenderLayers>,
+            Option<&mut GridFrustumIntersect>,
+            &InheritedVisibility,
+        ),
+        With<InfiniteGrid>,
+    >,
+    cameras: Query<
+        (
+            &GlobalTransform,
+            &Camera,
+            Option<&RenderLayers>,
+            Has<NoInfiniteGrid>,
+            Has<GridShadowCamera>,
+            Has<ReflectionCaptureCamera>,
+        ),
+        With<Camera3d>,
+    >,
+) {
+    let mut cameras: Vec<_> = cameras
+        .iter()
+        .filter(|(_, cam, ..)| cam.is_active)
+        .collect();
+    cameras.sort_by_key(|(_, cam, _, _, shadow_camera, _)| (!shadow_camera, cam.order));
+
+    for (entity, grid, grid_params, fadeout, grid_layers, intersects, visibility) in
+        grids.iter_mut()
+    {
+        // Hidden grids aren't drawn by any camera.
+        let camera = cameras.iter().filter(|_| visibility.get()).find(
+            |(_, _, layers, no_grid, _, reflection)| {
+                camera_sees_grid(*layers, *no_grid, grid_layers)
+                    && !(*reflection && grid_params.exclude_from_reflections)
+            },
+        );
+        let Some((cam_pos, cam, ..)) = camera else {
+            if let Some(mut val) = intersects.filter(|val| !val.is_empty()) {
+                *val = GridFrustumIntersect::default();
+            }
+            continue;
+        };
+        let intersect = GridFrustumIntersect::new(
+            cam_pos,
+            cam.projection_matrix(),
+            grid,
+            FadeoutDistanceOverride::apply(fadeout, grid_params),
+        );
+        match intersects {
+            Some(mut val) => *val = intersect,
+            None => {
+                commands.entity(entity).insert(intersect);
+            }
+        }
+    }
+}
+
+/// Drops the state tracked for grids whose [`InfiniteGridSettings`] were removed, so nothing
+/// stale is left when they are added back. Despawned grids take it with them, and the render
+/// world, rebuilt every frame, forgets both.
+fn clear_removed_grids(
+    mut removed: RemovedComponents<InfiniteGridSettings>,
+    mut grids: Query<
+        (&mut VisibleEntities, Option<&mut GridFrustumIntersect>),
+        Without<InfiniteGridSettings>,
+    >,
+) {
+    for entity in removed.read() {
+        let Ok((mut visibles, intersect)) = grids.get_mut(entity) else {
+            continue;
+        };
+        visibles.entities.clear();
+        if let Some(mut intersect) = intersect {
+            *intersect = GridFrustumIntersect::default();
         }
     }
 }
 
 #[allow(clippy::type_complexity)]
 fn track_caster_visibility(
-    mut grids: Query<(
-        &mut VisibleEntities,
-        &GlobalTransform,
-        &GridFrustumIntersect,
-    )>,
+    mut commands: Commands,
+    mut grids: Query<
+        (
+            Entity,
+            &mut VisibleEntities,
+            Ref<GlobalTransform>,
+            Ref<InfiniteGridSettings>,
+            Option<&FadeoutDistanceOverride>,
+            Option<&RenderLayers>,
+            Option<&mut GridShadowUpdate>,
+            &InheritedVisibility,
+        ),
+        With<InfiniteGrid>,
+    >,
     mut meshes: Query<
         (
             Entity,
             &Visibility,
             &mut ViewVisibility,
-            Option<(&GlobalTransform, &Aabb)>,
+            Option<&RenderLayers>,
+            Ref<GlobalTransform>,
+            Option<&Aabb>,
+            Option<Ref<GridShadowOpacity>>,
+            Has<CastsGridShadow>,
+        ),
+        (With<Handle<Mesh>>, GridShadowCasters),
+    >,
+    cameras: Query<
+        (
+            &GlobalTransform,
+            &Camera,
+            Option<&RenderLayers>,
+            Has<NoInfiniteGrid>,
         ),
-        (With<Handle<Mesh>>, Without<NotShadowCaster>),
+        (With<GridShadowCamera>, Without<ReflectionCaptureCamera>),
     >,
+    lights: Query<Ref<GlobalTransform>, With<DirectionalLight>>,
+    global_settings: Res<GlobalInfiniteGridSettings>,
 ) {
-    for (mut visibles, _grid_transform, _grid) in grids.iter_mut() {
-        visibles.entities.clear();
-        for (entity, visibility, mut view_visibility, _intersect_testable) in meshes.iter_mut() {
+    // Casters in the padding around the shadowed area land in the shadow texture too.
+    let padding = global_settings.shadow_area_padding();
+    for (grid_entity, mut visibles, grid, settings, fadeout, grid_layers, update, visibility) in
+        grids.iter_mut()
+    {
+        let previous = std::mem::take(&mut visibles.entities);
+        // Hidden grids render no shadow, so they have no casters either.
+        let Some(shadow) = settings.shadow.filter(|_| visibility.get()) else {
+            continue;
+        };
+        // Every shadow camera drawing the grid gets its own shadow view, a caster only needs
+        // to land in one.
+        let intersects: Vec<_> = cameras
+            .iter()
+            .filter(|(_, camera, layers, no_grid)| {
+                camera.is_active && camera_sees_grid(*layers, *no_grid, grid_layers)
+            })
+            .map(|(camera_transform, camera, ..)| {
+                GridFrustumIntersect::new(
+                    camera_transform,
+                    camera.projection_matrix(),
+                    &grid,
+                    FadeoutDistanceOverride::apply(fadeout, &settings),
+                )
+                .padded(padding)
+            })
+            // Views that don't see the plane render no shadow to cast into.
+            .filter(|intersect| !intersect.is_empty())
+            .collect();
+        let margin = shadow.cull_margin + shadow.softness.max(shadow.max_softness);
+        let shear = shadow.projection.shear(grid.up(), |light| {
+            lights.get(light).ok().map(|light| light.forward())
+        });
+        let mut changed = grid.is_changed() || settings.is_changed();
+        if let GridShadowProjection::FollowLight(light) = shadow.projection {
+            changed |= lights.get(light).is_ok_and(|light| light.is_changed());
+        }
+        for (entity, visibility, mut view_visibility, layers, transform, aabb, opacity, tagged) in
+            meshes.iter_mut()
+        {
             if let Visibility::Hidden = visibility {
                 continue;
             }
+            // Tagging, untagging and switching filters change the visible list, so shadows that
+            // update on change follow.
+            match (shadow.caster_filter, tagged) {
+                (GridShadowCasterFilter::Only, false) | (GridShadowCasterFilter::Except, true) => {
+                    continue
+                }
+                _ => {}
+            }
+            // Same rule as cameras: no `RenderLayers` means layer 0.
+            if !grid_layers
+                .copied()
+                .unwrap_or_default()
+                .intersects(&layers.copied().unwrap_or_default())
+            {
+                continue;
+            }
 
-            // TODO: add a check here for if the projection of the aabb onto the plane has any overlap with the grid frustum intersect
+            // Meshes without bounds can't be culled.
+            if let Some(aabb) = aabb {
+                if !intersects
+                    .iter()
+                    .any(|intersect| intersect.overlaps(grid.up(), shear, &transform, aabb, margin))
+                {
+                    continue;
+                }
+            }
+            changed |=
+                transform.is_changed() || opacity.is_some_and(|opacity| opacity.is_changed());
             view_visibility.set();
             visibles.entities.push(entity);
         }
+        changed |= visibles.entities != previous;
+
+        match update {
+            Some(mut update) => {
+                if changed {
+                    update.changes = update.changes.wrapping_add(1);
+                }
+            }
+            None => {
+                commands
+                    .entity(grid_entity)
+                    .insert(GridShadowUpdate::default());
+            }
+        }
+    }
+}
+
+fn follow_target_system(
+    mut grids: Query<(&mut Transform, &GridFollow, &InfiniteGridSettings)>,
+    targets: Query<&GlobalTransform>,
+) {
+    for (mut transform, follow, settings) in grids.iter_mut() {
+        let Ok(target) = targets.get(follow.target) else {
+            continue;
+        };
+        // Snap in grid space, so rotated and stretched grids move along their own lines.
+        let spacing = transform.scale * 10. / settings.scale;
+        let inverse_rot = transform.rotation.inverse();
+        let target_gs = inverse_rot * target.translation();
+        let grid_gs = inverse_rot * transform.translation;
+        let snapped = Vec3::new(
+            (target_gs.x / spacing.x).round() * spacing.x,
+            grid_gs.y,
+            (target_gs.z / spacing.z).round() * spacing.z,
+        );
+        let translation = transform.rotation * snapped;
+        // Only write on change, so Changed<Transform> filters stay quiet.
+        if transform.translation != translation {
+            transform.translation = translation;
+        }
+    }
+}
+
+fn spin_system(time: Res<Time>, mut grids: Query<(&mut Transform, &GridSpin)>) {
+    for (mut transform, spin) in grids.iter_mut() {
+        let Some(axis) = spin.axis.try_normalize() else {
+            continue;
+        };
+        transform.rotate_axis(axis, spin.radians_per_sec * time.delta_seconds());
+        // Keeps rounding errors from piling up over long spins.
+        transform.rotation = transform.rotation.normalize();
+    }
+}
+
+fn warn_shadows_disabled(
+    mut warned: Local<bool>,
+    grids: Query<&InfiniteGridSettings, Changed<InfiniteGridSettings>>,
+) {
+    if !*warned && grids.iter().any(|settings| settings.shadow.is_some()) {
+        warn!("a grid has shadow settings, but grid shadows are disabled");
+        *warned = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn caster_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_systems(Update, hide_grid_only_casters_from_lights);
+        app
+    }
+
+    fn casts_grid_shadow(app: &mut App, entity: Entity) -> bool {
+        app.world
+            .query_filtered::<Entity, (With<Handle<Mesh>>, GridShadowCasters)>()
+            .iter(&app.world)
+            .any(|caster| caster == entity)
+    }
+
+    #[test]
+    // Only the main world, `tests/headless.rs` renders the bundle where there is an adapter.
+    fn default_bundle_smoke() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            TransformPlugin,
+            HierarchyPlugin,
+            bevy::render::view::VisibilityPlugin,
+            InfiniteGridPlugin {
+                shadows: false,
+                cursor_hover: false,
+            },
+        ))
+        .init_asset::<Shader>()
+        .init_asset::<Mesh>()
+        // Normally from `RenderPlugin`, which needs a GPU.
+        .init_resource::<bevy::render::deterministic::DeterministicRenderingConfig>();
+        app.finish();
+        app.cleanup();
+
+        app.world.spawn(InfiniteGridBundle::default());
+        app.world.spawn((
+            Camera3dBundle {
+                transform: Transform::from_xyz(0., 4., 10.).looking_at(Vec3::ZERO, Vec3::Y),
+                ..default()
+            },
+            GridShadowCamera,
+        ));
+        for _ in 0..3 {
+            app.update();
+        }
+    }
+
+    #[test]
+    fn caster_marker_precedence() {
+        let mut app = caster_app();
+        let mesh = Handle::<Mesh>::default();
+        let plain = app.world.spawn(mesh.clone()).id();
+        let grid_only = app.world.spawn((mesh.clone(), GridShadowCasterOnly)).id();
+        let not_grid = app.world.spawn((mesh.clone(), NotGridShadowCaster)).id();
+        let both = app
+            .world
+            .spawn((mesh.clone(), GridShadowCasterOnly, NotGridShadowCaster))
+            .id();
+        let not_any = app.world.spawn((mesh.clone(), NotShadowCaster)).id();
+        let user_grid_only = app
+            .world
+            .spawn((mesh, GridShadowCasterOnly, NotShadowCaster))
+            .id();
+        app.update();
+
+        let light_caster =
+            |app: &App, entity| !app.world.entity(entity).contains::<NotShadowCaster>();
+        for (entity, light, grid) in [
+            (plain, true, true),
+            (grid_only, false, true),
+            (not_grid, true, false),
+            (both, true, false),
+            (not_any, false, false),
+            (user_grid_only, false, true),
+        ] {
+            assert_eq!(light_caster(&app, entity), light, "{entity:?} light shadow");
+            assert_eq!(
+                casts_grid_shadow(&mut app, entity),
+                grid,
+                "{entity:?} grid shadow"
+            );
+        }
+    }
+
+    #[test]
+    fn grid_only_caster_restores_light_shadow() {
+        let mut app = caster_app();
+        let mesh = Handle::<Mesh>::default();
+        let removed = app.world.spawn((mesh.clone(), GridShadowCasterOnly)).id();
+        let overridden = app.world.spawn((mesh.clone(), GridShadowCasterOnly)).id();
+        let user = app
+            .world
+            .spawn((mesh, GridShadowCasterOnly, NotShadowCaster))
+            .id();
+        app.update();
+        assert!(app.world.entity(removed).contains::<NotShadowCaster>());
+
+        app.world
+            .entity_mut(removed)
+            .remove::<GridShadowCasterOnly>();
+        app.world.entity_mut(overridden).insert(NotGridShadowCaster);
+        app.world.entity_mut(user).remove::<GridShadowCasterOnly>();
+        app.update();
+
+        assert!(!app.world.entity(removed).contains::<NotShadowCaster>());
+        assert!(!app.world.entity(overridden).contains::<NotShadowCaster>());
+        // Not the crate's to remove.
+        assert!(app.world.entity(user).contains::<NotShadowCaster>());
+    }
+
+    // Compares an outline to the expected corners, in any order.
+    fn assert_outline(intersect: &GridFrustumIntersect, expected: &[Vec3]) {
+        assert_eq!(
+            intersect.points().len(),
+            expected.len(),
+            "{:?}",
+            intersect.points()
+        );
+        for corner in expected {
+            assert!(
+                intersect
+                    .points()
+                    .iter()
+                    .any(|point| point.distance(*corner) < 1e-3),
+                "{corner} not in {:?}",
+                intersect.points()
+            );
+        }
+    }
+
+    // Both cameras look straight down on the XZ plane from 10 units up, with the top of the view
+    // towards -Z, so view X is world X and view Y is world -Z.
+    fn looking_down() -> GlobalTransform {
+        Transform::from_xyz(0., 10., 0.)
+            .looking_at(Vec3::ZERO, Vec3::NEG_Z)
+            .into()
+    }
+
+    #[test]
+    fn oblique_intersect() {
+        // A 2 by 2 parallel view, sheared so that view X moves by half the view depth: at the
+        // plane, 10 units deep, the view shifts 5 units along X.
+        let shear = Mat4::from_cols(Vec4::X, Vec4::Y, Vec4::new(0.5, 0., 1., 0.), Vec4::W);
+        let projection = Mat4::orthographic_rh(-1., 1., -1., 1., 100., 0.) * shear;
+        let intersect =
+            GridFrustumIntersect::new(&looking_down(), projection, &GlobalTransform::IDENTITY, 1e4);
+        assert_outline(
+            &intersect,
+            &[
+                Vec3::new(4., 0., -1.),
+                Vec3::new(6., 0., -1.),
+                Vec3::new(6., 0., 1.),
+                Vec3::new(4., 0., 1.),
+            ],
+        );
+        assert!(intersect.center.distance(Vec3::new(5., 0., 0.)) < 1e-3);
+        assert!((intersect.width - 2.).abs() < 1e-3 && (intersect.height - 2.).abs() < 1e-3);
+    }
+
+    #[test]
+    fn off_center_intersect() {
+        // A 90 degree square view, shifted by half its width: NDC X from -1 to 1 covers what the
+        // centered view has from -1.5 to 0.5, 15 units left to 5 right at 10 units deep.
+        let shift = Mat4::from_cols(Vec4::X, Vec4::Y, Vec4::Z, Vec4::new(0.5, 0., 0., 1.));
+        let projection =
+            shift * Mat4::perspective_infinite_reverse_rh(std::f32::consts::FRAC_PI_2, 1., 0.1);
+        let intersect = GridFrustumIntersect::new(
+            &looking_down(),
+            projection,
+            &GlobalTransform::IDENTITY,
+            100.,
+        );
+        assert_outline(
+            &intersect,
+            &[
+                Vec3::new(-15., 0., -10.),
+                Vec3::new(5., 0., -10.),
+                Vec3::new(5., 0., 10.),
+                Vec3::new(-15., 0., 10.),
+            ],
+        );
+        assert!(intersect.center.distance(Vec3::new(-5., 0., 0.)) < 1e-3);
     }
 }
@@ -0,0 +1,162 @@
+use bevy::prelude::*;
+use bevy::render::mesh::PrimitiveTopology;
+use bevy::render::render_asset::RenderAssetUsages;
+
+use crate::InfiniteGridSettings;
+
+impl InfiniteGridSettings {
+    /// Builds the grid lines inside `bounds` (grid space X/Z) as a line list mesh.
+    ///
+    /// Every line is split into `subdivisions` segments (at least one), which helps when baking
+    /// lighting into the vertices. The mesh has positions, up facing normals and vertex colors
+    /// matching the line and axis colors, tinted like the shader does. Lines are always solid and
+    /// never fade: dashed [`LineStyle`](crate::LineStyle)s, the sub-grid, fadeouts and glow are
+    /// left out.
+    pub fn to_mesh(&self, bounds: Rect, subdivisions: u32) -> Mesh {
+        let subdivisions = subdivisions.max(1);
+        let mut positions = Vec::new();
+        let mut colors = Vec::new();
+
+        // Lines are laid out from the offset origin, like the shader does.
+        let origin = self.grid_origin_offset;
+        // Tints multiply the minor and major lines' color and alpha, not the axes'.
+        let tinted = |color: Color, tint: Color| {
+            let mut color =
+                Vec4::from(color.as_linear_rgba_f32()) * Vec4::from(tint.as_linear_rgba_f32());
+            color.w = color.w.min(1.);
+            color.to_array()
+        };
+        let mut add_lines =
+            |min: f32, max: f32, along: (f32, f32), axis: (f32, Color, Color), flip| {
+                let (origin, axis_color, tint) = axis;
+                let first = ((min - origin) * self.scale).ceil() as i32;
+                let last = ((max - origin) * self.scale).floor() as i32;
+                for i in first..=last {
+                    // Without highlighting, the axes are major lines like any other.
+                    let color = if i == 0 && self.highlight_axes {
+                        axis_color.as_linear_rgba_f32()
+                    } else if i % 10 == 0 {
+                        tinted(self.major_line_color, tint)
+                    } else {
+                        tinted(self.minor_line_color, tint)
+                    };
+                    let offset = origin + i as f32 / self.scale;
+                    let (start, end) = along;
+                    for segment in 0..subdivisions {
+                        for step in [segment, segment + 1] {
+                            let t = start + (end - start) * step as f32 / subdivisions as f32;
+                            positions.push(match flip {
+                                false => [offset, 0., t],
+                                true => [t, 0., offset],
+                            });
+                            colors.push(color);
+                        }
+                    }
+                }
+            };
+
+        // Lines of constant x run along z, the one through the origin is the z axis.
+        add_lines(
+            bounds.min.x,
+            bounds.max.x,
+            (bounds.min.y, bounds.max.y),
+            (origin.x, self.z_axis_color, self.z_lines_tint),
+            false,
+        );
+        add_lines(
+            bounds.min.y,
+            bounds.max.y,
+            (bounds.min.x, bounds.max.x),
+            (origin.y, self.x_axis_color, self.x_lines_tint),
+            true,
+        );
+
+        let normals = vec![[0., 1., 0.]; positions.len()];
+        Mesh::new(PrimitiveTopology::LineList, RenderAssetUsages::default())
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::render::mesh::VertexAttributeValues;
+
+    use super::*;
+
+    fn positions(mesh: &Mesh) -> &[[f32; 3]] {
+        match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(positions)) => positions,
+            _ => panic!("no positions"),
+        }
+    }
+
+    #[test]
+    fn ten_by_ten_grid() {
+        let mesh = InfiniteGridSettings::default().to_mesh(Rect::new(-5., -5., 5., 5.), 1);
+        // 11 lines each way, from -5 to 5, of 2 vertices.
+        assert_eq!(mesh.count_vertices(), 2 * 11 * 2);
+    }
+
+    #[test]
+    fn subdivided_offset_grid() {
+        let settings = InfiniteGridSettings {
+            grid_origin_offset: Vec2::new(0.5, 0.25),
+            ..default()
+        };
+        let mesh = settings.to_mesh(Rect::new(-5., -5., 5., 5.), 3);
+        // Offset off the bounds, 10 lines each way remain, of 3 segments of 2 vertices.
+        assert_eq!(mesh.count_vertices(), 2 * 10 * 3 * 2);
+        let positions = positions(&mesh);
+        // Lines of constant x come first, from -4.5 with segments along z.
+        assert_eq!(positions[0], [-4.5, 0., -5.]);
+        assert_eq!(positions[1][0], -4.5);
+        assert!((positions[1][2] - (-5. + 10. / 3.)).abs() < 1e-5);
+        let along_x = positions.len() / 2;
+        assert_eq!(positions[along_x], [-5., 0., -4.75]);
+    }
+
+    fn colors(mesh: &Mesh) -> &[[f32; 4]] {
+        match mesh.attribute(Mesh::ATTRIBUTE_COLOR) {
+            Some(VertexAttributeValues::Float32x4(colors)) => colors,
+            _ => panic!("no colors"),
+        }
+    }
+
+    #[test]
+    fn plain_axes() {
+        let settings = InfiniteGridSettings {
+            highlight_axes: false,
+            ..default()
+        };
+        let mesh = settings.to_mesh(Rect::new(-5., -5., 5., 5.), 1);
+        let axis_colors =
+            [settings.x_axis_color, settings.z_axis_color].map(|color| color.as_linear_rgba_f32());
+        assert!(!colors(&mesh)
+            .iter()
+            .any(|color| axis_colors.contains(color)));
+        let major = settings.major_line_color.as_linear_rgba_f32();
+        assert_eq!(
+            colors(&mesh)
+                .iter()
+                .filter(|&&color| color == major)
+                .count(),
+            2 * 2
+        );
+    }
+
+    #[test]
+    fn tinted_lines() {
+        let settings = InfiniteGridSettings {
+            minor_line_color: Color::rgba_linear(0.5, 0.5, 0.5, 0.5),
+            x_lines_tint: Color::rgba_linear(2., 1., 0., 0.5),
+            ..default()
+        };
+        let mesh = settings.to_mesh(Rect::new(-5., -5., 5., 5.), 1);
+        let colors = colors(&mesh);
+        // The first line along z is untinted, the first along x takes the x tint.
+        assert_eq!(colors[0], [0.5, 0.5, 0.5, 0.5]);
+        assert_eq!(colors[colors.len() / 2], [1., 0.5, 0., 0.25]);
+    }
+}
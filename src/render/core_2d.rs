@@ -0,0 +1,107 @@
+use bevy::{
+    core_pipeline::core_2d::Transparent2d,
+    pbr::MeshPipelineKey,
+    prelude::*,
+    render::{
+        render_phase::{AddRenderCommand, DrawFunctions, RenderPhase, SetItemPipeline},
+        render_resource::{PipelineCache, SpecializedRenderPipelines},
+        view::{ExtractedView, VisibleEntities},
+        Render, RenderApp, RenderSet,
+    },
+    utils::FloatOrd,
+};
+
+use crate::{diagnostics::GridRenderStats, GridHeatmap, NoInfiniteGrid, ReflectionCaptureCamera};
+
+use super::{
+    heatmap::SetGridHeatmapBindGroup, plane_check, queue_infinite_grids, reflection_draws_grid,
+    ExtractedInfiniteGrid, FinishDrawInfiniteGrid, GizmoGrid, GridPipelineKey,
+    InfiniteGridPipeline, SetGridViewBindGroup, SetInfiniteGridBindGroup,
+};
+
+// 2D views never get grid shadows.
+type DrawInfiniteGrid2d = (
+    SetItemPipeline,
+    SetGridViewBindGroup<0>,
+    SetInfiniteGridBindGroup<1>,
+    SetGridHeatmapBindGroup,
+    FinishDrawInfiniteGrid,
+);
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn queue_infinite_grids_2d(
+    pipeline_cache: Res<PipelineCache>,
+    transparent_draw_functions: Res<DrawFunctions<Transparent2d>>,
+    pipeline: Res<InfiniteGridPipeline>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<InfiniteGridPipeline>>,
+    infinite_grids: Query<(&ExtractedInfiniteGrid, Has<GridHeatmap>)>,
+    gizmo_grids: Query<Entity, With<GizmoGrid>>,
+    mut views: Query<
+        (
+            &VisibleEntities,
+            &mut RenderPhase<Transparent2d>,
+            &ExtractedView,
+            Has<ReflectionCaptureCamera>,
+        ),
+        Without<NoInfiniteGrid>,
+    >,
+    msaa: Res<Msaa>,
+    stats: Res<GridRenderStats>,
+) {
+    let mut queued = 0;
+    let draw_function_id = transparent_draw_functions
+        .read()
+        .get_id::<DrawInfiniteGrid2d>()
+        .unwrap();
+
+    for (entities, mut phase, view, reflection) in views.iter_mut() {
+        for entity in entities.entities.iter().copied().chain(&gizmo_grids) {
+            let Some((grid, heatmap)) = infinite_grids.get(entity).ok().filter(|(grid, _)| {
+                reflection_draws_grid(reflection, grid)
+                    && plane_check(&grid.transform, view.transform.translation())
+            }) else {
+                continue;
+            };
+            let key = GridPipelineKey {
+                mesh_key: MeshPipelineKey::from_hdr(view.hdr),
+                has_shadows: false,
+                shadow_pcf: None,
+                shadow_height: false,
+                shadow_cascade: false,
+                shadow_lighten: false,
+                heatmap,
+                pixel_snap: grid.grid.pixel_snap,
+                cull_underside: grid.grid.cull_underside,
+                glow: grid.grid.glow > 0.,
+                core_2d: true,
+                sample_count: msaa.samples(),
+            };
+            // Sorted among sprites by Z, like them.
+            phase.items.push(Transparent2d {
+                sort_key: FloatOrd(grid.transform.translation().z),
+                entity,
+                pipeline: pipelines.specialize(&pipeline_cache, &pipeline, key),
+                draw_function: draw_function_id,
+                batch_range: 0..1,
+                dynamic_offset: None,
+            });
+            queued += 1;
+        }
+    }
+    stats.add_grids(queued);
+}
+
+/// Draws grids in the `Core2d` graph's transparent pass, see `InfiniteGrid2dPlugin`.
+pub(crate) fn register_core_2d(app: &mut App) {
+    let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+        return;
+    };
+    render_app
+        .add_render_command::<Transparent2d, DrawInfiniteGrid2d>()
+        .add_systems(
+            Render,
+            queue_infinite_grids_2d
+                .in_set(RenderSet::Queue)
+                .after(queue_infinite_grids),
+        );
+}
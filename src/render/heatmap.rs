@@ -0,0 +1,172 @@
+use bevy::{
+    ecs::{
+        query::ROQueryItem,
+        system::{lifetimeless::Read, SystemParamItem},
+    },
+    prelude::*,
+    render::{
+        render_asset::RenderAssets,
+        render_phase::{PhaseItem, RenderCommand, RenderCommandResult},
+        render_resource::{
+            BindGroup, BindGroupEntries, BindGroupLayout, BindGroupLayoutEntry, BindingType,
+            BufferBindingType, BufferSize, DynamicUniformBuffer, SamplerBindingType, ShaderStages,
+            ShaderType, TextureSampleType, TextureViewDimension,
+        },
+        renderer::{RenderDevice, RenderQueue},
+        texture::FallbackImageZero,
+        Extract,
+    },
+};
+
+use crate::{GridHeatmap, InfiniteGridSettings};
+
+use super::InfiniteGridPipeline;
+#[cfg(feature = "shadows")]
+use super::ViewGridShadows;
+
+#[derive(ShaderType)]
+struct GridHeatmapUniform {
+    origin: Vec2,
+    extent: Vec2,
+    opacity: f32,
+    // 1 to tint the lines too
+    tint_lines: u32,
+}
+
+#[derive(Resource, Default)]
+pub(super) struct GridHeatmapUniforms {
+    uniforms: DynamicUniformBuffer<GridHeatmapUniform>,
+}
+
+#[derive(Component)]
+pub(super) struct GridHeatmapBindGroup {
+    bind_group: BindGroup,
+    offset: u32,
+}
+
+pub(super) fn create_heatmap_layout(render_device: &RenderDevice) -> BindGroupLayout {
+    render_device.create_bind_group_layout(
+        "grid-heatmap-bind-group-layout",
+        &[
+            BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::FRAGMENT,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: true,
+                    min_binding_size: BufferSize::new(GridHeatmapUniform::min_size().into()),
+                },
+                count: None,
+            },
+            BindGroupLayoutEntry {
+                binding: 1,
+                visibility: ShaderStages::FRAGMENT,
+                ty: BindingType::Texture {
+                    sample_type: TextureSampleType::Float { filterable: true },
+                    view_dimension: TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            BindGroupLayoutEntry {
+                binding: 2,
+                visibility: ShaderStages::FRAGMENT,
+                ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                count: None,
+            },
+        ],
+    )
+}
+
+#[allow(clippy::type_complexity)]
+pub(super) fn extract_grid_heatmaps(
+    mut commands: Commands,
+    grids: Extract<Query<(Entity, &GridHeatmap, &ViewVisibility), With<InfiniteGridSettings>>>,
+) {
+    let extracted: Vec<_> = grids
+        .iter()
+        .filter(|(.., visibility)| visibility.get())
+        .map(|(entity, heatmap, _)| (entity, heatmap.clone()))
+        .collect();
+    commands.insert_or_spawn_batch(extracted);
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(super) fn prepare_grid_heatmaps(
+    mut commands: Commands,
+    grids: Query<(Entity, &GridHeatmap)>,
+    mut uniforms: ResMut<GridHeatmapUniforms>,
+    pipeline: Res<InfiniteGridPipeline>,
+    images: Res<RenderAssets<Image>>,
+    fallback_image: Res<FallbackImageZero>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+) {
+    uniforms.uniforms.clear();
+    let offsets: Vec<_> = grids
+        .iter()
+        .map(|(entity, heatmap)| {
+            let offset = uniforms.uniforms.push(&GridHeatmapUniform {
+                origin: heatmap.origin,
+                extent: heatmap.extent,
+                opacity: heatmap.opacity,
+                tint_lines: heatmap.tint_lines as u32,
+            });
+            (entity, heatmap, offset)
+        })
+        .collect();
+    uniforms
+        .uniforms
+        .write_buffer(&render_device, &render_queue);
+
+    let Some(binding) = uniforms.uniforms.binding() else {
+        return;
+    };
+    for (entity, heatmap, offset) in offsets {
+        // Until the image is loaded the grid is drawn untinted, the fallback being transparent.
+        let image = images.get(&heatmap.image).unwrap_or(&fallback_image);
+        let bind_group = render_device.create_bind_group(
+            "grid-heatmap-bind-group",
+            &pipeline.heatmap_layout,
+            &BindGroupEntries::sequential((binding.clone(), &image.texture_view, &image.sampler)),
+        );
+        commands
+            .entity(entity)
+            .insert(GridHeatmapBindGroup { bind_group, offset });
+    }
+}
+
+#[cfg(feature = "shadows")]
+type ViewShadowsQuery = Option<Read<ViewGridShadows>>;
+#[cfg(not(feature = "shadows"))]
+type ViewShadowsQuery = ();
+
+/// Binds the heatmap after the grid's shadow, if it has one in this view.
+pub(super) struct SetGridHeatmapBindGroup;
+
+impl<P: PhaseItem> RenderCommand<P> for SetGridHeatmapBindGroup {
+    type Param = ();
+    type ViewQuery = ViewShadowsQuery;
+    type ItemQuery = Read<GridHeatmapBindGroup>;
+
+    #[inline]
+    fn render<'w>(
+        item: &P,
+        view_shadows: ROQueryItem<'w, Self::ViewQuery>,
+        heatmap: ROQueryItem<'w, Option<Self::ItemQuery>>,
+        _param: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut bevy::render::render_phase::TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        #[cfg(feature = "shadows")]
+        let index = match view_shadows {
+            Some(view_shadows) if view_shadows.shadows.contains_key(&item.entity()) => 3,
+            _ => 2,
+        };
+        #[cfg(not(feature = "shadows"))]
+        let (index, _) = (2, (item, view_shadows));
+        if let Some(heatmap) = heatmap {
+            pass.set_bind_group(index, &heatmap.bind_group, &[heatmap.offset]);
+        }
+        RenderCommandResult::Success
+    }
+}
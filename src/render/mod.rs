@@ -1,6 +1,26 @@
+mod core_2d;
+mod heatmap;
+#[cfg(feature = "shadows")]
 mod shadow;
-
-pub use shadow::RenderSettings;
+#[cfg(feature = "shadows")]
+mod shadow_debug;
+#[cfg(feature = "shadows")]
+mod shadow_decal;
+#[cfg(feature = "shadows")]
+mod shadow_occlusion;
+#[cfg(feature = "shadows")]
+mod shadow_timing;
+
+pub(crate) use core_2d::register_core_2d;
+#[cfg(feature = "shadows")]
+pub use shadow::{
+    GridShadowBindGroup, GridShadowUniformOffset, GridShadowView, RenderSettings, ShadowFilter,
+    ViewGridShadows,
+};
+#[cfg(feature = "shadows")]
+pub(crate) use shadow_decal::{register_shadow_decal, SHADOW_DECAL_MATERIAL_SHADER_HANDLE};
+#[cfg(feature = "shadows")]
+pub(crate) use shadow_occlusion::register_shadow_occlusion;
 
 use std::borrow::Cow;
 
@@ -26,20 +46,28 @@ use bevy::{
             BlendState, BufferBindingType, BufferSize, ColorTargetState, ColorWrites,
             CompareFunction, DepthBiasState, DepthStencilState, DynamicUniformBuffer,
             FragmentState, MultisampleState, PipelineCache, PolygonMode, PrimitiveState,
-            RenderPipelineDescriptor, SamplerBindingType, ShaderStages, ShaderType,
+            RenderPipelineDescriptor, ShaderDefVal, ShaderStages, ShaderType,
             SpecializedRenderPipeline, SpecializedRenderPipelines, StencilFaceState, StencilState,
-            TextureFormat, TextureSampleType, TextureViewDimension, VertexState,
+            TextureFormat, VertexState,
         },
         renderer::{RenderDevice, RenderQueue},
         texture::BevyDefault,
         view::{ExtractedView, ViewTarget, VisibleEntities},
         Extract, ExtractSchedule, Render, RenderApp, RenderSet,
     },
+    utils::HashMap,
 };
 
-use crate::{GridFrustumIntersect, InfiniteGridSettings};
+use crate::{
+    diagnostics::GridRenderStats, FadeMode, FadeoutDistanceOverride, GridHeatmap,
+    InfiniteGridGizmos, InfiniteGridSettings, InfiniteGridViewOverrides, LineStyle, NoInfiniteGrid,
+    PcfKernel, ReflectionCaptureCamera,
+};
 
-use shadow::{GridShadow, SetGridShadowBindGroup};
+use heatmap::SetGridHeatmapBindGroup;
+
+#[cfg(feature = "shadows")]
+use shadow::SetGridShadowBindGroup;
 
 static PLANE_RENDER: &str = include_str!("plane_render.wgsl");
 
@@ -63,20 +91,80 @@ pub struct GridDisplaySettingsUniform {
     scale: f32,
     // 1 / fadeout_distance
     dist_fadeout_const: f32,
+    // 1 / (fadeout_distance * minor_fadeout_ratio)
+    minor_dist_fadeout_const: f32,
     dot_fadeout_const: f32,
+    plane_offset: f32,
+    aa_width: f32,
+    // 0 without a sub-grid
+    sub_scale: f32,
+    // 1 / (fadeout_distance * sub_grid.fadeout_ratio)
+    sub_dist_fadeout_const: f32,
     x_axis_color: Vec3,
     z_axis_color: Vec3,
     minor_line_color: Vec4,
     major_line_color: Vec4,
+    sub_line_color: Vec4,
+    // grid space point fadeouts are measured from, for FadeMode::RadialFromPoint
+    fade_center: Vec2,
+    // 1 for FadeMode::RadialFromPoint, 0 for FadeMode::Camera
+    radial_fade: u32,
+    x_lines_tint: Vec4,
+    z_lines_tint: Vec4,
+    // in pixels, 0 without a halo
+    glow: f32,
+    // 0 to draw the axes like any other line
+    highlight_axes: u32,
+    // grid space point the lines, axes and cells are laid out from
+    origin_offset: Vec2,
+    // dash and gap length, 0 for solid lines
+    minor_line_dash: Vec2,
+    major_line_dash: Vec2,
+}
+
+impl GridDisplaySettingsUniform {
+    fn from_settings(settings: &InfiniteGridSettings) -> Self {
+        let sub_grid = settings.sub_grid.unwrap_or_default();
+        Self {
+            scale: settings.scale,
+            dist_fadeout_const: 1. / settings.fadeout_distance,
+            minor_dist_fadeout_const: 1.
+                / (settings.fadeout_distance * settings.minor_fadeout_ratio),
+            dot_fadeout_const: 1. / settings.dot_fadeout_strength,
+            plane_offset: settings.plane_offset,
+            // thinner bands alias badly where the plane is seen at grazing angles
+            aa_width: settings.aa_width.max(0.5),
+            sub_scale: match settings.sub_grid {
+                Some(sub_grid) => settings.scale * sub_grid.scale_ratio,
+                None => 0.,
+            },
+            sub_dist_fadeout_const: 1. / (settings.fadeout_distance * sub_grid.fadeout_ratio),
+            x_axis_color: Vec3::from_slice(&settings.x_axis_color.as_linear_rgba_f32()),
+            z_axis_color: Vec3::from_slice(&settings.z_axis_color.as_linear_rgba_f32()),
+            minor_line_color: Vec4::from_slice(&settings.minor_line_color.as_linear_rgba_f32()),
+            major_line_color: Vec4::from_slice(&settings.major_line_color.as_linear_rgba_f32()),
+            sub_line_color: Vec4::from_slice(&sub_grid.color.as_linear_rgba_f32()),
+            fade_center: match settings.fade_mode {
+                FadeMode::Camera => Vec2::ZERO,
+                FadeMode::RadialFromPoint { center } => center,
+            },
+            radial_fade: matches!(settings.fade_mode, FadeMode::RadialFromPoint { .. }) as u32,
+            x_lines_tint: Vec4::from_slice(&settings.x_lines_tint.as_linear_rgba_f32()),
+            z_lines_tint: Vec4::from_slice(&settings.z_lines_tint.as_linear_rgba_f32()),
+            glow: settings.glow.max(0.),
+            highlight_axes: settings.highlight_axes as u32,
+            origin_offset: settings.grid_origin_offset,
+            minor_line_dash: line_dash(settings.minor_line_style),
+            major_line_dash: line_dash(settings.major_line_style),
+        }
+    }
 }
 
-#[derive(Debug, ShaderType)]
-pub struct GridShadowUniform {
-    shadow_color: Vec4,
-    shadow_collapse_matrix: Mat3,
-    shadow_center_pos: Vec3,
-    shadow_texture_width: f32,
-    shadow_texture_height: f32,
+fn line_dash(style: LineStyle) -> Vec2 {
+    match style {
+        LineStyle::Dashed { dash, gap } if dash > 0. && gap > 0. => Vec2::new(dash, gap),
+        _ => Vec2::ZERO,
+    }
 }
 
 #[derive(Resource, Default)]
@@ -89,11 +177,6 @@ struct GridDisplaySettingsUniforms {
     uniforms: DynamicUniformBuffer<GridDisplaySettingsUniform>,
 }
 
-#[derive(Resource, Default)]
-struct GridShadowUniforms {
-    uniforms: DynamicUniformBuffer<GridShadowUniform>,
-}
-
 #[derive(Component)]
 struct InfiniteGridUniformOffsets {
     position_offset: u32,
@@ -101,13 +184,15 @@ struct InfiniteGridUniformOffsets {
 }
 
 #[derive(Component)]
-pub struct GridShadowUniformOffset {
+pub struct PerCameraSettingsUniformOffset {
     offset: u32,
 }
 
+/// Settings uniform offsets of each grid as a view with [`InfiniteGridViewOverrides`] sees it,
+/// keyed by grid entity.
 #[derive(Component)]
-pub struct PerCameraSettingsUniformOffset {
-    offset: u32,
+struct ViewGridSettingsUniformOffsets {
+    offsets: HashMap<Entity, u32>,
 }
 
 #[derive(Resource)]
@@ -118,10 +203,13 @@ struct InfiniteGridBindGroup {
 #[derive(Clone, ShaderType)]
 pub struct GridViewUniform {
     projection: Mat4,
-    inverse_projection: Mat4,
+    // rays are unprojected with it alone, whatever the projection's shape
+    inverse_view_projection: Mat4,
     view: Mat4,
     inverse_view: Mat4,
     world_position: Vec3,
+    // 1 for orthographic projections, whose rays are parallel
+    orthographic: u32,
 }
 
 #[derive(Resource, Default)]
@@ -164,29 +252,36 @@ struct SetInfiniteGridBindGroup<const I: usize>;
 
 impl<const I: usize, P: PhaseItem> RenderCommand<P> for SetInfiniteGridBindGroup<I> {
     type Param = SRes<InfiniteGridBindGroup>;
-    type ViewQuery = Option<Read<PerCameraSettingsUniformOffset>>;
+    type ViewQuery = (
+        Option<Read<PerCameraSettingsUniformOffset>>,
+        Option<Read<ViewGridSettingsUniformOffsets>>,
+    );
     type ItemQuery = Read<InfiniteGridUniformOffsets>;
 
     #[inline]
     fn render<'w>(
-        _item: &P,
-        camera_settings_offset: ROQueryItem<'w, Self::ViewQuery>,
+        item: &P,
+        (camera_settings_offset, view_offsets): ROQueryItem<'w, Self::ViewQuery>,
         base_offsets: ROQueryItem<'w, Option<Self::ItemQuery>>,
         bind_group: SystemParamItem<'w, '_, Self::Param>,
         pass: &mut bevy::render::render_phase::TrackedRenderPass<'w>,
     ) -> RenderCommandResult {
-        if let Some(base_offsets) = base_offsets {
-            pass.set_bind_group(
-                I,
-                &bind_group.into_inner().value,
-                &[
-                    base_offsets.position_offset,
-                    camera_settings_offset
-                        .map(|cs| cs.offset)
-                        .unwrap_or(base_offsets.settings_offset),
-                ],
-            );
-        }
+        // Drawing without the bind group is a validation error, skip the grid instead.
+        let Some(base_offsets) = base_offsets else {
+            return RenderCommandResult::Failure;
+        };
+        pass.set_bind_group(
+            I,
+            &bind_group.into_inner().value,
+            &[
+                base_offsets.position_offset,
+                view_offsets
+                    .and_then(|view_offsets| view_offsets.offsets.get(&item.entity()))
+                    .copied()
+                    .or(camera_settings_offset.map(|cs| cs.offset))
+                    .unwrap_or(base_offsets.settings_offset),
+            ],
+        );
         RenderCommandResult::Success
     }
 }
@@ -228,8 +323,9 @@ fn prepare_grid_view_uniforms(
                 projection,
                 view,
                 inverse_view,
-                inverse_projection: projection.inverse(),
+                inverse_view_projection: view * projection.inverse(),
                 world_position: camera.transform.translation(),
+                orthographic: crate::is_orthographic(&projection) as u32,
             }),
         });
     }
@@ -260,6 +356,7 @@ fn prepare_grid_view_bind_groups(
     }
 }
 
+#[allow(clippy::type_complexity)]
 fn extract_infinite_grids(
     mut commands: Commands,
     grids: Extract<
@@ -268,21 +365,27 @@ fn extract_infinite_grids(
             &InfiniteGridSettings,
             &GlobalTransform,
             &VisibleEntities,
+            Option<&FadeoutDistanceOverride>,
+            &ViewVisibility,
         )>,
     >,
 ) {
+    // Grids no view sees, e.g. hidden ones, get no uniforms, shadow textures or passes.
     let extracted: Vec<_> = grids
         .iter()
-        .map(|(entity, grid, transform, visible_entities)| {
+        .filter(|(.., visibility)| visibility.get())
+        .map(|(entity, grid, transform, visible_entities, fadeout, _)| {
             (
                 entity,
                 (
                     ExtractedInfiniteGrid {
                         transform: *transform,
-                        grid: *grid,
+                        grid: InfiniteGridSettings {
+                            fadeout_distance: FadeoutDistanceOverride::apply(fadeout, grid),
+                            ..*grid
+                        },
                     },
                     visible_entities.clone(),
-                    RenderPhase::<GridShadow>::default(),
                 ),
             )
         })
@@ -290,14 +393,38 @@ fn extract_infinite_grids(
     commands.insert_or_spawn_batch(extracted);
 }
 
-fn extract_grid_shadows(
+/// Marks the render world entity of a grid drawn through [`InfiniteGridGizmos`], which no view
+/// lists among its visible entities.
+#[derive(Component)]
+struct GizmoGrid;
+
+fn extract_gizmo_grids(mut commands: Commands, gizmos: Extract<Res<InfiniteGridGizmos>>) {
+    let extracted: Vec<_> = gizmos
+        .grids
+        .iter()
+        .map(|(transform, grid)| {
+            (
+                ExtractedInfiniteGrid {
+                    transform: *transform,
+                    grid: InfiniteGridSettings {
+                        shadow: None,
+                        ..*grid
+                    },
+                },
+                GizmoGrid,
+            )
+        })
+        .collect();
+    commands.spawn_batch(extracted);
+}
+
+fn extract_view_overrides(
     mut commands: Commands,
-    grids: Extract<Query<(Entity, &InfiniteGridSettings, &GridFrustumIntersect)>>,
+    cameras: Extract<Query<(Entity, &InfiniteGridViewOverrides), With<Camera>>>,
 ) {
-    let extracted: Vec<_> = grids
+    let extracted: Vec<_> = cameras
         .iter()
-        .filter(|(_, grid_settings, _)| grid_settings.shadow_color.is_some())
-        .map(|(entity, _, intersect)| (entity, (*intersect,)))
+        .map(|(entity, overrides)| (entity, *overrides))
         .collect();
     commands.insert_or_spawn_batch(extracted);
 }
@@ -313,22 +440,64 @@ fn extract_per_camera_settings(
     commands.insert_or_spawn_batch(extracted);
 }
 
+#[allow(clippy::type_complexity)]
+fn extract_no_grid_cameras(
+    mut commands: Commands,
+    cameras: Extract<Query<Entity, (With<Camera>, With<NoInfiniteGrid>)>>,
+) {
+    let extracted: Vec<_> = cameras
+        .iter()
+        .map(|entity| (entity, NoInfiniteGrid))
+        .collect();
+    commands.insert_or_spawn_batch(extracted);
+}
+
+#[allow(clippy::type_complexity)]
+fn extract_reflection_cameras(
+    mut commands: Commands,
+    cameras: Extract<Query<Entity, (With<Camera>, With<ReflectionCaptureCamera>)>>,
+) {
+    let extracted: Vec<_> = cameras
+        .iter()
+        .map(|entity| (entity, ReflectionCaptureCamera))
+        .collect();
+    commands.insert_or_spawn_batch(extracted);
+}
+
+/// Whether a view draws a grid, as far as [`ReflectionCaptureCamera`] goes.
+fn reflection_draws_grid(reflection: bool, grid: &ExtractedInfiniteGrid) -> bool {
+    !(reflection && grid.grid.exclude_from_reflections)
+}
+
+#[allow(clippy::too_many_arguments)]
 fn prepare_infinite_grids(
     mut commands: Commands,
     grids: Query<(Entity, &ExtractedInfiniteGrid)>,
     cameras: Query<(Entity, &InfiniteGridSettings), With<ExtractedView>>,
+    override_views: Query<
+        (
+            Entity,
+            &InfiniteGridViewOverrides,
+            Option<&InfiniteGridSettings>,
+        ),
+        With<ExtractedView>,
+    >,
     mut position_uniforms: ResMut<InfiniteGridUniforms>,
     mut settings_uniforms: ResMut<GridDisplaySettingsUniforms>,
     render_device: Res<RenderDevice>,
     render_queue: Res<RenderQueue>,
 ) {
     position_uniforms.uniforms.clear();
+    settings_uniforms.uniforms.clear();
     for (entity, extracted) in grids.iter() {
         let transform = extracted.transform;
         let t = transform.compute_transform();
         let offset = transform.translation();
         let normal = transform.up();
-        let rot_matrix = Mat3::from_quat(t.rotation.inverse());
+        // Grid space is the entity's local space, so X and Z scale stretch the cells. Y scale
+        // has nothing to stretch on a plane.
+        let rot_matrix = Mat3::from_diagonal(Vec3::new(t.scale.x, 1., t.scale.z).recip())
+            * Mat3::from_quat(t.rotation.inverse());
         commands.entity(entity).insert(InfiniteGridUniformOffsets {
             position_offset: position_uniforms.uniforms.push(&InfiniteGridUniform {
                 rot_matrix,
@@ -337,19 +506,7 @@ fn prepare_infinite_grids(
             }),
             settings_offset: settings_uniforms
                 .uniforms
-                .push(&GridDisplaySettingsUniform {
-                    scale: extracted.grid.scale,
-                    dist_fadeout_const: 1. / extracted.grid.fadeout_distance,
-                    dot_fadeout_const: 1. / extracted.grid.dot_fadeout_strength,
-                    x_axis_color: Vec3::from_slice(&extracted.grid.x_axis_color.as_rgba_f32()),
-                    z_axis_color: Vec3::from_slice(&extracted.grid.z_axis_color.as_rgba_f32()),
-                    minor_line_color: Vec4::from_slice(
-                        &extracted.grid.minor_line_color.as_rgba_f32(),
-                    ),
-                    major_line_color: Vec4::from_slice(
-                        &extracted.grid.major_line_color.as_rgba_f32(),
-                    ),
-                }),
+                .push(&GridDisplaySettingsUniform::from_settings(&extracted.grid)),
         });
     }
 
@@ -359,22 +516,27 @@ fn prepare_infinite_grids(
             .insert(PerCameraSettingsUniformOffset {
                 offset: settings_uniforms
                     .uniforms
-                    .push(&GridDisplaySettingsUniform {
-                        scale: settings.scale,
-                        dist_fadeout_const: 1. / settings.fadeout_distance,
-                        dot_fadeout_const: 1. / settings.dot_fadeout_strength,
-                        x_axis_color: Vec3::from_slice(&settings.x_axis_color.as_rgba_f32()),
-                        z_axis_color: Vec3::from_slice(&settings.z_axis_color.as_rgba_f32()),
-                        minor_line_color: Vec4::from_slice(
-                            &settings.minor_line_color.as_rgba_f32(),
-                        ),
-                        major_line_color: Vec4::from_slice(
-                            &settings.major_line_color.as_rgba_f32(),
-                        ),
-                    }),
+                    .push(&GridDisplaySettingsUniform::from_settings(settings)),
             });
     }
 
+    // Every grid is pushed for these views, the ones a view doesn't draw are never looked up.
+    for (view, overrides, camera_settings) in override_views.iter() {
+        let offsets = grids
+            .iter()
+            .map(|(grid, extracted)| {
+                let settings = overrides.apply(camera_settings.unwrap_or(&extracted.grid));
+                let offset = settings_uniforms
+                    .uniforms
+                    .push(&GridDisplaySettingsUniform::from_settings(&settings));
+                (grid, offset)
+            })
+            .collect();
+        commands
+            .entity(view)
+            .insert(ViewGridSettingsUniformOffsets { offsets });
+    }
+
     position_uniforms
         .uniforms
         .write_buffer(&render_device, &render_queue);
@@ -384,46 +546,6 @@ fn prepare_infinite_grids(
         .write_buffer(&render_device, &render_queue);
 }
 
-fn prepare_grid_shadows(
-    mut commands: Commands,
-    grids: Query<(Entity, &ExtractedInfiniteGrid, &GridFrustumIntersect)>,
-    mut uniforms: ResMut<GridShadowUniforms>,
-    render_device: Res<RenderDevice>,
-    render_queue: Res<RenderQueue>,
-) {
-    uniforms.uniforms.clear();
-    for (entity, extracted, intersect) in grids.iter() {
-        let transform = extracted.transform;
-        let normal = transform.up();
-
-        // When called after [`extract_grid_shadows()`] has filtered out
-        // [`InfiniteGrid`]s that have shadow_color: None, this is always
-        // true. However, if this is ever called before then the unwrap()
-        // that was here before will crash the program with a panic! that
-        // makes shadow_color: None unusable.
-        if let Some(grid_shadow_color) = extracted.grid.shadow_color {
-            commands.entity(entity).insert(GridShadowUniformOffset {
-                offset: uniforms.uniforms.push(&GridShadowUniform {
-                    shadow_color: Vec4::from_slice(&grid_shadow_color.as_rgba_f32()),
-                    shadow_collapse_matrix: Mat3::from_cols(
-                        normal.cross(-intersect.up_dir),
-                        normal,
-                        -intersect.up_dir,
-                    )
-                    .inverse(),
-                    shadow_center_pos: intersect.center,
-                    shadow_texture_height: intersect.height,
-                    shadow_texture_width: intersect.width,
-                }),
-            });
-        }
-    }
-
-    uniforms
-        .uniforms
-        .write_buffer(&render_device, &render_queue);
-}
-
 fn prepare_bind_groups_for_infinite_grids(
     mut commands: Commands,
     position_uniforms: Res<InfiniteGridUniforms>,
@@ -447,85 +569,152 @@ fn prepare_bind_groups_for_infinite_grids(
     commands.insert_resource(InfiniteGridBindGroup { value: bind_group });
 }
 
-#[allow(clippy::too_many_arguments)]
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn queue_infinite_grids(
     pipeline_cache: Res<PipelineCache>,
     transparent_draw_functions: Res<DrawFunctions<Transparent3d>>,
     pipeline: Res<InfiniteGridPipeline>,
     mut pipelines: ResMut<SpecializedRenderPipelines<InfiniteGridPipeline>>,
-    infinite_grids: Query<&ExtractedInfiniteGrid>,
-    intersects: Query<&GridFrustumIntersect>,
-    mut views: Query<(
-        &VisibleEntities,
-        &mut RenderPhase<Transparent3d>,
-        &ExtractedView,
-    )>,
+    infinite_grids: Query<(&ExtractedInfiniteGrid, Has<GridHeatmap>)>,
+    gizmo_grids: Query<Entity, With<GizmoGrid>>,
+    mut views: Query<
+        (
+            Entity,
+            &VisibleEntities,
+            &mut RenderPhase<Transparent3d>,
+            &ExtractedView,
+            Has<ReflectionCaptureCamera>,
+        ),
+        Without<NoInfiniteGrid>,
+    >,
+    #[cfg(feature = "shadows")] view_shadows: Query<&ViewGridShadows>,
     msaa: Res<Msaa>,
+    stats: Res<GridRenderStats>,
 ) {
+    let mut queued = 0;
     let draw_function_id = transparent_draw_functions
         .read()
         .get_id::<DrawInfiniteGrid>()
         .unwrap();
 
-    for (entities, mut phase, view) in views.iter_mut() {
+    for (view_entity, entities, mut phase, view, reflection) in views.iter_mut() {
         let mesh_key = MeshPipelineKey::from_hdr(view.hdr);
-        let base_pipeline = pipelines.specialize(
-            &pipeline_cache,
-            &pipeline,
-            GridPipelineKey {
-                mesh_key,
-                has_shadows: false,
-                sample_count: msaa.samples(),
-            },
-        );
-        let shadow_pipeline = pipelines.specialize(
-            &pipeline_cache,
-            &pipeline,
-            GridPipelineKey {
-                mesh_key,
-                has_shadows: true,
-                sample_count: msaa.samples(),
-            },
-        );
-        for &entity in &entities.entities {
-            if infinite_grids
-                .get(entity)
-                .map(|grid| plane_check(&grid.transform, view.transform.translation()))
-                .unwrap_or(false)
-            {
+        let base_key = GridPipelineKey {
+            mesh_key,
+            has_shadows: false,
+            shadow_pcf: None,
+            shadow_height: false,
+            shadow_cascade: false,
+            shadow_lighten: false,
+            heatmap: false,
+            pixel_snap: false,
+            cull_underside: false,
+            glow: false,
+            core_2d: false,
+            sample_count: msaa.samples(),
+        };
+        #[cfg(feature = "shadows")]
+        let view_shadows = view_shadows.get(view_entity).ok();
+        #[cfg(not(feature = "shadows"))]
+        let _ = view_entity;
+        for entity in entities.entities.iter().copied().chain(&gizmo_grids) {
+            if let Some((grid, heatmap)) = infinite_grids.get(entity).ok().filter(|(grid, _)| {
+                reflection_draws_grid(reflection, grid)
+                    && plane_check(&grid.transform, view.transform.translation())
+            }) {
+                let key = GridPipelineKey {
+                    heatmap,
+                    pixel_snap: grid.grid.pixel_snap,
+                    cull_underside: grid.grid.cull_underside,
+                    glow: grid.grid.glow > 0.,
+                    ..base_key
+                };
+                // Shadow variants are only compiled once some view has grid shadows.
+                #[cfg(feature = "shadows")]
+                let pipeline = match (view_shadows, grid.grid.shadow) {
+                    (Some(view_shadows), Some(shadow))
+                        if view_shadows.shadows.contains_key(&entity) =>
+                    {
+                        pipelines.specialize(
+                            &pipeline_cache,
+                            &pipeline,
+                            GridPipelineKey {
+                                has_shadows: true,
+                                shadow_pcf: shadow::shadow_pcf(&shadow),
+                                shadow_height: shadow::contact_hardening(&shadow),
+                                shadow_cascade: shadow.cascades >= 2,
+                                shadow_lighten: shadow.mode == crate::GridShadowMode::Lighten,
+                                ..key
+                            },
+                        )
+                    }
+                    _ => pipelines.specialize(&pipeline_cache, &pipeline, key),
+                };
+                #[cfg(not(feature = "shadows"))]
+                let pipeline = pipelines.specialize(&pipeline_cache, &pipeline, key);
+
                 phase.items.push(Transparent3d {
-                    pipeline: match intersects.contains(entity) {
-                        true => shadow_pipeline,
-                        false => base_pipeline,
-                    },
+                    pipeline,
                     entity,
                     draw_function: draw_function_id,
                     distance: f32::NEG_INFINITY,
                     batch_range: 0..1,
                     dynamic_offset: None,
                 });
+                queued += 1;
             }
         }
     }
+    stats.set_grids(queued);
 }
 
 fn plane_check(plane: &GlobalTransform, point: Vec3) -> bool {
     plane.up().dot(plane.translation() - point).abs() > f32::EPSILON
 }
 
+#[cfg(feature = "shadows")]
 type DrawInfiniteGrid = (
     SetItemPipeline,
     SetGridViewBindGroup<0>,
     SetInfiniteGridBindGroup<1>,
     SetGridShadowBindGroup<2>,
+    SetGridHeatmapBindGroup,
     FinishDrawInfiniteGrid,
 );
 
+#[cfg(not(feature = "shadows"))]
+type DrawInfiniteGrid = (
+    SetItemPipeline,
+    SetGridViewBindGroup<0>,
+    SetInfiniteGridBindGroup<1>,
+    SetGridHeatmapBindGroup,
+    FinishDrawInfiniteGrid,
+);
+
+/// Render world resource holding the grid's bind group layouts.
 #[derive(Resource)]
-struct InfiniteGridPipeline {
+pub struct InfiniteGridPipeline {
     view_layout: BindGroupLayout,
     infinite_grid_layout: BindGroupLayout,
+    #[cfg(feature = "shadows")]
     grid_shadows_layout: BindGroupLayout,
+    heatmap_layout: BindGroupLayout,
+}
+
+impl InfiniteGridPipeline {
+    /// Layout of [`GridShadowBindGroup`], for pipelines that sample grid shadows themselves.
+    ///
+    /// - binding 0: the grid's shadow uniform, with a dynamic offset from
+    ///   [`GridShadowUniformOffset`]. Its layout is `GridShadow` in `plane_render.wgsl`.
+    /// - binding 1: the shadow texture, a filterable `texture_2d<f32>` with coverage in red and,
+    ///   for `Rg16Float` textures, `1 / (1 + height)` of the closest caster in green.
+    /// - binding 2: a filtering sampler.
+    /// - binding 3: the near cascade's shadow texture, laid out like binding 1. An empty texture
+    ///   for grids with a single cascade.
+    #[cfg(feature = "shadows")]
+    pub fn grid_shadows_layout(&self) -> &BindGroupLayout {
+        &self.grid_shadows_layout
+    }
 }
 
 impl FromWorld for InfiniteGridPipeline {
@@ -572,42 +761,16 @@ impl FromWorld for InfiniteGridPipeline {
             ],
         );
 
-        let grid_shadows_layout = render_device.create_bind_group_layout(
-            "grid-shadows-bind-group-layout",
-            &[
-                BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: ShaderStages::FRAGMENT,
-                    ty: BindingType::Buffer {
-                        ty: BufferBindingType::Uniform,
-                        has_dynamic_offset: true,
-                        min_binding_size: BufferSize::new(GridShadowUniform::min_size().into()),
-                    },
-                    count: None,
-                },
-                BindGroupLayoutEntry {
-                    binding: 1,
-                    visibility: ShaderStages::FRAGMENT,
-                    ty: BindingType::Texture {
-                        sample_type: TextureSampleType::Float { filterable: true },
-                        view_dimension: TextureViewDimension::D2,
-                        multisampled: false,
-                    },
-                    count: None,
-                },
-                BindGroupLayoutEntry {
-                    binding: 2,
-                    visibility: ShaderStages::FRAGMENT,
-                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
-                    count: None,
-                },
-            ],
-        );
+        #[cfg(feature = "shadows")]
+        let grid_shadows_layout = shadow::create_grid_shadows_layout(render_device);
+        let heatmap_layout = heatmap::create_heatmap_layout(render_device);
 
         Self {
             view_layout,
             infinite_grid_layout,
+            #[cfg(feature = "shadows")]
             grid_shadows_layout,
+            heatmap_layout,
         }
     }
 }
@@ -616,6 +779,23 @@ impl FromWorld for InfiniteGridPipeline {
 pub struct GridPipelineKey {
     mesh_key: MeshPipelineKey,
     has_shadows: bool,
+    shadow_pcf: Option<(u32, PcfKernel)>,
+    /// Widen the shadow filter with the height of the closest caster.
+    shadow_height: bool,
+    /// Blend in the near cascade's shadow.
+    shadow_cascade: bool,
+    /// Add the shadow color instead of covering with it, with premultiplied alpha blending.
+    shadow_lighten: bool,
+    /// Tint by a [`GridHeatmap`], bound after the shadow.
+    heatmap: bool,
+    /// Hard one pixel lines, see [`InfiniteGridSettings::pixel_snap`].
+    pixel_snap: bool,
+    /// See [`InfiniteGridSettings::cull_underside`].
+    cull_underside: bool,
+    /// Add a halo around the lines, with premultiplied alpha blending.
+    glow: bool,
+    /// Drawn in the 2D pass, which has no depth buffer.
+    core_2d: bool,
     sample_count: u32,
 }
 
@@ -623,6 +803,56 @@ impl SpecializedRenderPipeline for InfiniteGridPipeline {
     type Key = GridPipelineKey;
 
     fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        let mut shader_defs = Vec::new();
+        if key.has_shadows {
+            shader_defs.push("SHADOWS".into());
+        }
+        if let Some((size, kernel)) = key.shadow_pcf {
+            shader_defs.push(ShaderDefVal::Int("SHADOW_PCF_SIZE".into(), size as i32));
+            if kernel == PcfKernel::Poisson {
+                shader_defs.push("SHADOW_PCF_POISSON".into());
+            }
+        }
+        if key.shadow_height {
+            shader_defs.push("SHADOW_HEIGHT".into());
+        }
+        if key.shadow_lighten {
+            shader_defs.push("SHADOW_LIGHTEN".into());
+        }
+        if key.shadow_cascade {
+            shader_defs.push("SHADOW_CASCADE".into());
+        }
+        if key.heatmap {
+            shader_defs.push("HEATMAP".into());
+            let group = if key.has_shadows { 3 } else { 2 };
+            shader_defs.push(ShaderDefVal::UInt("HEATMAP_GROUP".into(), group));
+        }
+        if key.pixel_snap {
+            shader_defs.push("PIXEL_SNAP".into());
+        }
+        if key.cull_underside {
+            shader_defs.push("CULL_UNDERSIDE".into());
+        }
+        if key.glow {
+            shader_defs.push("GRID_GLOW".into());
+        }
+        if key.core_2d {
+            shader_defs.push("CORE_2D".into());
+        }
+        // both add light to what's behind the grid
+        if key.shadow_lighten || key.glow {
+            shader_defs.push("PREMULTIPLIED_ALPHA".into());
+        }
+
+        let mut layout = vec![self.view_layout.clone(), self.infinite_grid_layout.clone()];
+        #[cfg(feature = "shadows")]
+        if key.has_shadows {
+            layout.push(self.grid_shadows_layout.clone());
+        }
+        if key.heatmap {
+            layout.push(self.heatmap_layout.clone());
+        }
+
         let format = match key.mesh_key.contains(MeshPipelineKey::HDR) {
             true => ViewTarget::TEXTURE_FORMAT_HDR,
             false => TextureFormat::bevy_default(),
@@ -634,10 +864,7 @@ impl SpecializedRenderPipeline for InfiniteGridPipeline {
             } else {
                 "grid-render-pipeline-shadowless"
             })),
-            layout: [self.view_layout.clone(), self.infinite_grid_layout.clone()]
-                .into_iter()
-                .chain(key.has_shadows.then(|| self.grid_shadows_layout.clone()))
-                .collect(),
+            layout,
             push_constant_ranges: Vec::new(),
             vertex: VertexState {
                 shader: SHADER_HANDLE,
@@ -654,7 +881,7 @@ impl SpecializedRenderPipeline for InfiniteGridPipeline {
                 polygon_mode: PolygonMode::Fill,
                 conservative: false,
             },
-            depth_stencil: Some(DepthStencilState {
+            depth_stencil: (!key.core_2d).then_some(DepthStencilState {
                 format: TextureFormat::Depth32Float,
                 depth_write_enabled: false,
                 depth_compare: CompareFunction::Greater,
@@ -677,15 +904,15 @@ impl SpecializedRenderPipeline for InfiniteGridPipeline {
             },
             fragment: Some(FragmentState {
                 shader: SHADER_HANDLE,
-                shader_defs: key
-                    .has_shadows
-                    .then(|| "SHADOWS".into())
-                    .into_iter()
-                    .collect(),
+                shader_defs,
                 entry_point: Cow::Borrowed("fragment"),
                 targets: vec![Some(ColorTargetState {
                     format,
-                    blend: Some(BlendState::ALPHA_BLENDING),
+                    blend: Some(if key.shadow_lighten || key.glow {
+                        BlendState::PREMULTIPLIED_ALPHA_BLENDING
+                    } else {
+                        BlendState::ALPHA_BLENDING
+                    }),
                     write_mask: ColorWrites::ALL,
                 })],
             }),
@@ -693,45 +920,96 @@ impl SpecializedRenderPipeline for InfiniteGridPipeline {
     }
 }
 
-pub fn render_app_builder(app: &mut App) {
+pub fn render_app_builder(app: &mut App, shadows: bool) {
     app.world
         .resource_mut::<Assets<Shader>>()
         .get_or_insert_with(SHADER_HANDLE, || Shader::from_wgsl(PLANE_RENDER, file!()));
 
+    let stats = GridRenderStats::default();
+    app.insert_resource(stats.clone());
+
     let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
         return;
     };
     render_app
+        .insert_resource(stats)
         .init_resource::<GridViewUniforms>()
         .init_resource::<InfiniteGridUniforms>()
         .init_resource::<GridDisplaySettingsUniforms>()
-        .init_resource::<GridShadowUniforms>()
+        .init_resource::<heatmap::GridHeatmapUniforms>()
         .init_resource::<InfiniteGridPipeline>()
         .init_resource::<SpecializedRenderPipelines<InfiniteGridPipeline>>()
         .add_render_command::<Transparent3d, DrawInfiniteGrid>()
         .add_systems(
             ExtractSchedule,
-            (extract_grid_shadows, extract_infinite_grids).chain(), // order to minimize move overhead
+            (
+                extract_infinite_grids,
+                extract_gizmo_grids,
+                extract_per_camera_settings,
+                extract_view_overrides,
+                extract_no_grid_cameras,
+                extract_reflection_cameras,
+                heatmap::extract_grid_heatmaps,
+            ),
         )
-        .add_systems(ExtractSchedule, extract_per_camera_settings)
         .add_systems(
             Render,
-            (
-                prepare_infinite_grids,
-                prepare_grid_shadows,
-                prepare_grid_view_uniforms,
-            )
-                .in_set(RenderSet::Prepare),
+            (prepare_infinite_grids, prepare_grid_view_uniforms)
+                .in_set(RenderSet::PrepareResources),
         )
         .add_systems(
             Render,
             (
                 prepare_bind_groups_for_infinite_grids,
                 prepare_grid_view_bind_groups,
+                heatmap::prepare_grid_heatmaps,
             )
                 .in_set(RenderSet::PrepareBindGroups),
         )
         .add_systems(Render, queue_infinite_grids.in_set(RenderSet::Queue));
 
-    shadow::register_shadow(app);
+    #[cfg(feature = "shadows")]
+    if shadows {
+        shadow::register_shadow(app);
+        shadow_debug::register_shadow_debug(app);
+        shadow_timing::register_shadow_timing(app);
+    }
+    #[cfg(not(feature = "shadows"))]
+    let _ = shadows;
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::GridDisplaySettingsUniform;
+    use crate::InfiniteGridSettings;
+
+    // The presets were tuned while the uniforms took sRGB values as they were, so their linear
+    // colors must still reach the shader with those values.
+    #[test]
+    fn preset_uniform_colors_are_unchanged() {
+        let blender = GridDisplaySettingsUniform::from_settings(&InfiniteGridSettings::blender());
+        assert_eq!(
+            blender.x_axis_color,
+            Vec3::from_slice(&Color::rgb(0.9, 0.21, 0.33).as_rgba_f32())
+        );
+        assert_eq!(
+            blender.minor_line_color,
+            Vec4::from(Color::rgb(0.19, 0.19, 0.19).as_rgba_f32())
+        );
+
+        let unity = GridDisplaySettingsUniform::from_settings(&InfiniteGridSettings::unity());
+        assert_eq!(
+            unity.major_line_color,
+            Vec4::from(Color::rgba(0.5, 0.5, 0.5, 0.45).as_rgba_f32())
+        );
+
+        let blueprint =
+            GridDisplaySettingsUniform::from_settings(&InfiniteGridSettings::blueprint());
+        assert_eq!(
+            blueprint.minor_line_color,
+            Vec4::from(Color::rgba(0.45, 0.6, 0.85, 0.6).as_rgba_f32())
+        );
+    }
 }
@@ -9,28 +9,70 @@ struct InfiniteGridSettings {
     scale: f32,
     // 1 / fadeout_distance
     dist_fadeout_const: f32,
+    // 1 / (fadeout_distance * minor_fadeout_ratio)
+    minor_dist_fadeout_const: f32,
     dot_fadeout_const: f32,
+    plane_offset: f32,
+    // in pixels, at least 0.5
+    aa_width: f32,
+    // 0 without a sub-grid
+    sub_scale: f32,
+    // 1 / (fadeout_distance * sub_grid.fadeout_ratio)
+    sub_dist_fadeout_const: f32,
     x_axis_col: vec3<f32>,
     z_axis_col: vec3<f32>,
     minor_line_col: vec4<f32>,
     major_line_col: vec4<f32>,
+    sub_line_col: vec4<f32>,
+    // grid space point fadeouts are measured from, for FadeMode::RadialFromPoint
+    fade_center: vec2<f32>,
+    // 1 for FadeMode::RadialFromPoint, 0 for FadeMode::Camera
+    radial_fade: u32,
+    x_lines_tint: vec4<f32>,
+    z_lines_tint: vec4<f32>,
+    // in pixels, 0 without a halo
+    glow: f32,
+    // 0 to draw the axes like any other line
+    highlight_axes: u32,
+    // grid space point the lines, axes and cells are laid out from
+    origin_offset: vec2<f32>,
+    // dash and gap length, 0 for solid lines
+    minor_line_dash: vec2<f32>,
+    major_line_dash: vec2<f32>,
 
 };
 
 struct GridShadow {
     shadow_col: vec4<f32>,
+    shadow_intensity: f32,
+    shadow_falloff: f32,
+    // world space radius of the filter kernel
+    shadow_softness: f32,
     shadow_collapse_matrix: mat3x3<f32>,
     shadow_center_pos: vec3<f32>,
     shadow_texture_width: f32,
     shadow_texture_height: f32,
+    // shadow offset across the grid per unit of height, zero for a straight down projection
+    shadow_shear: vec3<f32>,
+    // extra filter radius per unit of caster height, and the largest radius it can reach
+    shadow_height_softness: f32,
+    shadow_max_softness: f32,
+    // the near cascade's area, like the fields above for the whole shadow
+    cascade_collapse_matrix: mat3x3<f32>,
+    cascade_center_pos: vec3<f32>,
+    cascade_texture_width: f32,
+    cascade_texture_height: f32,
 };
 
 struct View {
     projection: mat4x4<f32>,
-    inverse_projection: mat4x4<f32>,
+    // rays are unprojected with it alone, whatever the projection's shape
+    inverse_view_projection: mat4x4<f32>,
     view: mat4x4<f32>,
     inverse_view: mat4x4<f32>,
     world_position: vec3<f32>,
+    // 1 for orthographic projections, whose rays are parallel
+    orthographic: u32,
 };
 
 @group(0) @binding(0)
@@ -50,6 +92,180 @@ var grid_shadow_texture: texture_2d<f32>;
 
 @group(2) @binding(2)
 var grid_shadow_sampler: sampler;
+
+@group(2) @binding(3)
+var grid_shadow_cascade_texture: texture_2d<f32>;
+
+// width of the band, in near cascade uv, over which the cascades blend
+const SHADOW_CASCADE_BLEND: f32 = 0.05;
+
+#ifdef SHADOW_PCF_SIZE
+const SHADOW_PCF_TAPS: i32 = #{SHADOW_PCF_SIZE} * #{SHADOW_PCF_SIZE};
+
+// Offset of a filter tap, within the unit disk or square.
+fn shadow_pcf_offset(i: i32) -> vec2<f32> {
+#ifdef SHADOW_PCF_POISSON
+    // golden angle spiral, a cheap stand in for a precomputed poisson disk
+    let r = sqrt((f32(i) + 0.5) / f32(SHADOW_PCF_TAPS));
+    let theta = f32(i) * 2.3999632;
+    return vec2<f32>(cos(theta), sin(theta)) * r;
+#else
+    let half_size = #{SHADOW_PCF_SIZE} / 2;
+    let tap = vec2<i32>(i % #{SHADOW_PCF_SIZE}, i / #{SHADOW_PCF_SIZE}) - vec2<i32>(half_size);
+    return vec2<f32>(tap) / f32(half_size);
+#endif
+}
+#endif
+
+// Where `pos` lands in a shadow texture covering `size` world units around `center`.
+fn grid_shadow_uv(pos: vec3<f32>, collapse_matrix: mat3x3<f32>, center: vec3<f32>, size: vec2<f32>) -> vec2<f32> {
+    let relative_to_center = (collapse_matrix * (pos - center)).xz;
+    return relative_to_center / size + vec2<f32>(0.5);
+}
+
+fn sample_grid_shadow(shadow_texture: texture_2d<f32>, uv: vec2<f32>, size: vec2<f32>) -> f32 {
+#ifdef SHADOW_PCF_SIZE
+    // radii are in world units, so the filtered area doesn't depend on the texture resolution
+    let world_to_uv = 1. / size;
+    var softness = grid_shadow.shadow_softness;
+#ifdef SHADOW_HEIGHT
+    // look for casters as far as the widest filter reaches, and widen the filter by their
+    // average height, so shadows sharpen where casters touch the grid
+    var closeness = 0.;
+    var blockers = 0.;
+    for (var i = 0; i < SHADOW_PCF_TAPS; i += 1) {
+        let offset = shadow_pcf_offset(i) * grid_shadow.shadow_max_softness * world_to_uv;
+        let tap = textureSampleLevel(shadow_texture, grid_shadow_sampler, uv + offset, 0.).g;
+        if tap > 0. {
+            closeness += tap;
+            blockers += 1.;
+        }
+    }
+    if blockers > 0. {
+        let height = blockers / closeness - 1.;
+        softness = min(softness + grid_shadow.shadow_height_softness * height, grid_shadow.shadow_max_softness);
+    }
+#endif
+    let radius = softness * world_to_uv;
+    var total = 0.;
+    for (var i = 0; i < SHADOW_PCF_TAPS; i += 1) {
+        total += textureSampleLevel(shadow_texture, grid_shadow_sampler, uv + shadow_pcf_offset(i) * radius, 0.).r;
+    }
+    return total / f32(SHADOW_PCF_TAPS);
+#else
+    return textureSample(shadow_texture, grid_shadow_sampler, uv).r;
+#endif
+}
+#endif
+
+#ifdef HEATMAP
+struct GridHeatmap {
+    origin: vec2<f32>,
+    extent: vec2<f32>,
+    opacity: f32,
+    // 1 to tint the lines too
+    tint_lines: u32,
+};
+
+@group(#{HEATMAP_GROUP}) @binding(0)
+var<uniform> grid_heatmap: GridHeatmap;
+
+@group(#{HEATMAP_GROUP}) @binding(1)
+var grid_heatmap_texture: texture_2d<f32>;
+
+@group(#{HEATMAP_GROUP}) @binding(2)
+var grid_heatmap_sampler: sampler;
+
+// Heatmap color at a grid space point, transparent off the image.
+fn sample_grid_heatmap(pos: vec2<f32>) -> vec4<f32> {
+    let uv = (pos - grid_heatmap.origin) / grid_heatmap.extent;
+    if any(uv < vec2<f32>(0.)) || any(uv > vec2<f32>(1.)) {
+        return vec4<f32>(0.);
+    }
+    let color = textureSampleLevel(grid_heatmap_texture, grid_heatmap_sampler, uv, 0.);
+    return vec4<f32>(color.rgb, color.a * grid_heatmap.opacity);
+}
+#endif
+
+// Coverage of the grid lines through `coord` by direction, lines sitting on integer coordinates:
+// x for the lines running along Z, y for those running along X.
+fn axis_line_coverage(coord: vec2<f32>) -> vec2<f32> {
+    let derivative = fwidth(coord);
+#ifdef PIXEL_SNAP
+    // A line covers exactly the pixel whose footprint it crosses, so it stays one pixel wide and
+    // steps from pixel to pixel instead of shimmering. Derivatives are per pixel, so this holds
+    // whatever the viewport's offset and size.
+    let crossed = fract(coord + 0.5 * derivative) < derivative;
+    return vec2<f32>(crossed);
+#else
+    // Box filtered over the pixel footprint ("pristine grid"): lines keep their width in pixels
+    // and, once several fall into a pixel, blend into their average coverage instead of
+    // point sampling into moire.
+    let line_width = min(grid_settings.aa_width * derivative, vec2<f32>(1.));
+    // past half a cell the gaps are the thinner lines, drawn inverted
+    let invert = line_width > vec2<f32>(0.5);
+    let target_width = select(line_width, 1. - line_width, invert);
+    let draw_width = clamp(target_width, derivative, vec2<f32>(0.5));
+    let line_aa = derivative * grid_settings.aa_width;
+    // twice the distance to the nearest line, or gap when inverted, in cells
+    let from_line = select(1. - abs(fract(coord) * 2. - 1.), abs(fract(coord) * 2. - 1.), invert);
+    var coverage = 1. - smoothstep(draw_width - line_aa, draw_width + line_aa, from_line);
+    // lines thinner than a pixel are drawn a pixel wide and fainter
+    coverage *= clamp(target_width / max(draw_width, vec2<f32>(1e-6)), vec2<f32>(0.), vec2<f32>(1.));
+    coverage = mix(coverage, target_width, clamp(derivative * 2. - 1., vec2<f32>(0.), vec2<f32>(1.)));
+    return select(coverage, 1. - coverage, invert);
+#endif
+}
+
+fn line_coverage(coord: vec2<f32>) -> f32 {
+    let coverage = axis_line_coverage(coord);
+    return max(coverage.x, coverage.y);
+}
+
+// Dashes along the lines by direction, like `axis_line_coverage`: the lines running along Z dash
+// along grid Y and the others along grid X. `pos` is in grid units, `dash` holds the dash and gap
+// lengths. Once a dash and its gap span only a few pixels the dashes fade into their average
+// coverage, instead of aliasing into solid lines and back.
+fn line_dash_coverage(pos: vec2<f32>, dash: vec2<f32>) -> vec2<f32> {
+    let along = pos.yx;
+    let period = max(dash.x + dash.y, 1e-5);
+    let derivative = fwidth(along);
+    // distance to the nearest dash center, dashes starting at multiples of the period
+    let from_center = abs(fract((along - 0.5 * dash.x) / period + 0.5) - 0.5) * period;
+    let dashes = clamp((0.5 * dash.x - from_center) / derivative + 0.5, vec2<f32>(0.), vec2<f32>(1.));
+    let pixels_per_period = period / derivative;
+    let blur = 1. - smoothstep(vec2<f32>(2.), vec2<f32>(4.), pixels_per_period);
+    let coverage = mix(dashes, vec2<f32>(dash.x / period), blur);
+    return select(vec2<f32>(1.), coverage, dash.x > 0.);
+}
+
+#ifdef CORE_2D
+// Zoomed out, lines closer than a few pixels would blur into a flat tone, so they fade out
+// between 8 and 3 pixels apart.
+fn line_density_fade(coord: vec2<f32>) -> f32 {
+    let derivative = fwidth(coord);
+    let spacing = 1. / max(derivative.x, derivative.y);
+    return smoothstep(3., 8., spacing);
+}
+#endif
+
+#ifdef GRID_GLOW
+// Halo of the lines through `coord` by direction, like `axis_line_coverage`. Where the lines are
+// closer together than the halo is wide it thins out, so distant lines don't blur into a haze.
+fn axis_line_glow(coord: vec2<f32>) -> vec2<f32> {
+    let derivative = fwidth(coord);
+    let distance = abs(fract(coord - 0.5) - 0.5) / derivative;
+    let falloff = max(1. - distance / grid_settings.glow, vec2<f32>(0.));
+    let crowding = min(1. / (2. * grid_settings.glow * derivative), vec2<f32>(1.));
+    return falloff * falloff * crowding;
+}
+
+// The brightest of the two directions' halos rather than their sum, so crossings don't add up.
+fn line_glow_color(color: vec4<f32>, glow: vec2<f32>) -> vec3<f32> {
+    let along_z = color.rgb * grid_settings.z_lines_tint.rgb * glow.x;
+    let along_x = color.rgb * grid_settings.x_lines_tint.rgb * glow.y;
+    return max(along_z, along_x) * color.a;
+}
 #endif
 
 struct Vertex {
@@ -57,14 +273,13 @@ struct Vertex {
 };
 
 fn unproject_point(p: vec3<f32>) -> vec3<f32> {
-    let unprojected = view.view * view.inverse_projection * vec4<f32>(p, 1.0);
+    let unprojected = view.inverse_view_projection * vec4<f32>(p, 1.0);
     return unprojected.xyz / unprojected.w;
 }
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
-    @location(0) near_point: vec3<f32>,
-    @location(1) far_point: vec3<f32>,
+    @location(0) ndc: vec2<f32>,
 };
 
 @vertex
@@ -81,26 +296,47 @@ fn vertex(vertex: Vertex) -> VertexOutput {
     var out: VertexOutput;
 
     out.clip_position = vec4<f32>(p, 1.);
-    out.near_point = unproject_point(p);
-    out.far_point = unproject_point(vec3<f32>(p.xy, 0.001)); // unprojecting on the far plane
+    out.ndc = p.xy;
     return out;
 }
 
 struct FragmentOutput {
     @location(0) color: vec4<f32>,
+#ifndef CORE_2D
     @builtin(frag_depth) depth: f32,
+#endif
 };
 
 @fragment
 fn fragment(in: VertexOutput) -> FragmentOutput {
-    let ray_origin = in.near_point;
-    let ray_direction = normalize(in.far_point - in.near_point);
+    let orthographic = view.orthographic != 0u;
+    // Unprojected per fragment, as the unprojected points don't vary linearly across the screen
+    // once the projection's w depends on x and y. Parallel rays come out parallel, whatever
+    // their angle to the view.
+    let near_point = unproject_point(vec3<f32>(in.ndc, 1.));
+    let far_point = unproject_point(vec3<f32>(in.ndc, 0.001)); // unprojecting on the far plane
+    let ray_origin = near_point;
+    let ray_direction = normalize(far_point - near_point);
     let plane_normal = grid_position.normal;
-    let plane_origin = grid_position.origin;
+    // only the drawn plane moves, grid coordinates stay relative to the entity
+    let plane_origin = grid_position.origin + plane_normal * grid_settings.plane_offset;
 
     let denominator = dot(ray_direction, plane_normal);
+#ifdef CULL_UNDERSIDE
+    // The plane is a fullscreen quad, so there's no triangle to cull. Per ray, this also holds
+    // for orthographic views.
+    if denominator > 0. {
+        discard;
+    }
+#endif
     let point_to_point = plane_origin - ray_origin;
     let t = dot(plane_normal, point_to_point) / denominator;
+    // Rays above the horizon hit the plane behind the camera (or never, along the horizon).
+    // Bevy's reverse-Z infinite projection would still give those a depth, so drop them here.
+    // The comparison is also false for NaN.
+    if !(t > 0. && t < 1e30) {
+        discard;
+    }
     let frag_pos_3d = ray_direction * t + ray_origin;
 
     let planar_offset = frag_pos_3d - plane_origin;
@@ -112,59 +348,175 @@ fn fragment(in: VertexOutput) -> FragmentOutput {
     let clip_space_pos = view.projection * view_space_pos;
     let clip_depth = clip_space_pos.z / clip_space_pos.w;
     let real_depth = -view_space_pos.z;
+    // an orthographic view has no eye point to measure from, so it fades with the distance from
+    // its axis instead
+#ifdef CORE_2D
+    // a 2D view pans and zooms over the grid without moving away from it, only the radial
+    // fadeout applies
+    let camera_distance = 0.;
+#else
+    let camera_distance = select(real_depth, length(view_space_pos.xy), orthographic);
+#endif
+    // what the fadeouts are measured from
+    let fade_distance = select(camera_distance, distance(plane_coords, grid_settings.fade_center), grid_settings.radial_fade != 0u);
 
     var out: FragmentOutput;
 
+#ifndef CORE_2D
     out.depth = clip_depth;
+#endif
 
     #ifdef SHADOWS
-    let grid_pos_relative_to_shadow_center = (grid_shadow.shadow_collapse_matrix * (frag_pos_3d - grid_shadow.shadow_center_pos)).xz;
+    // project like the shadow pass did, which only matters when the drawn plane is offset
+    let shadow_pos = frag_pos_3d + grid_shadow.shadow_shear * dot(frag_pos_3d - grid_position.origin, plane_normal);
     let shadow_size = vec2<f32>(grid_shadow.shadow_texture_width, grid_shadow.shadow_texture_height);
-    let offset_location = grid_pos_relative_to_shadow_center / shadow_size;
-    let uv = offset_location + vec2<f32>(0.5);
+    let uv = grid_shadow_uv(shadow_pos, grid_shadow.shadow_collapse_matrix, grid_shadow.shadow_center_pos, shadow_size);
 
     let checks = step(vec2<f32>(1.), uv) + step(vec2<f32>(0.), -uv);
     let inbounds = 1. - step(1., checks.x + checks.y);
 
-    let shadow = textureSample(grid_shadow_texture, grid_shadow_sampler, uv).r;
-    let shadow2 = 1. - shadow * inbounds;
+    // fade towards the edges of the shadow texture instead of cutting off
+    let edge_distance = min(min(uv.x, uv.y), min(1. - uv.x, 1. - uv.y));
+    let edge_fade = select(1., clamp(edge_distance / grid_shadow.shadow_falloff, 0., 1.), grid_shadow.shadow_falloff > 0.);
+
+    var shadow = sample_grid_shadow(grid_shadow_texture, uv, shadow_size);
+#ifdef SHADOW_CASCADE
+    // the near cascade covers the part of the grid closest to the camera in more detail, fading
+    // into the whole shadow towards its edges
+    let cascade_size = vec2<f32>(grid_shadow.cascade_texture_width, grid_shadow.cascade_texture_height);
+    let cascade_uv = grid_shadow_uv(shadow_pos, grid_shadow.cascade_collapse_matrix, grid_shadow.cascade_center_pos, cascade_size);
+    let cascade_edge = min(min(cascade_uv.x, cascade_uv.y), min(1. - cascade_uv.x, 1. - cascade_uv.y));
+    let cascade_weight = clamp(cascade_edge / SHADOW_CASCADE_BLEND, 0., 1.);
+    shadow = mix(shadow, sample_grid_shadow(grid_shadow_cascade_texture, cascade_uv, cascade_size), cascade_weight);
+#endif
+    let shadow2 = 1. - shadow * inbounds * edge_fade * grid_shadow.shadow_intensity;
     #endif
 
     let scale = grid_settings.scale;
-    let coord = plane_coords * scale; // use the scale variable to set the distance between the lines
+    // lines, axes and cells are laid out from the offset origin, fades and heatmaps stay in grid space
+    let grid_coords = plane_coords - grid_settings.origin_offset;
+    let coord = grid_coords * scale; // use the scale variable to set the distance between the lines
     let derivative = fwidth(coord);
-    let grid = abs(fract(coord - 0.5) - 0.5) / derivative;
-    let lne = min(grid.x, grid.y);
 
     let minimumz = min(derivative.y, 1.) / scale;
     let minimumx = min(derivative.x, 1.) / scale;
 
-    let derivative2 = fwidth(coord * 0.1);
-    let grid2 = abs(fract((coord * 0.1) - 0.5) - 0.5) / derivative2;
-    let mg_line = min(grid2.x, grid2.y);
+    // minor lines fade out on their own (shorter) distance, major line coverage is kept as is
+    // so the remaining majors stay antialiased
+    let minor_fadeout = clamp(1. - grid_settings.minor_dist_fadeout_const * fade_distance, 0., 1.);
+    let major_lines = axis_line_coverage(coord * 0.1);
+    let major_dashes = major_lines * line_dash_coverage(grid_coords, grid_settings.major_line_dash);
+    // minor lines give way where major lines run, so the gaps of dashed majors stay open
+    let minor_dashes = axis_line_coverage(coord) * (1. - major_lines) * line_dash_coverage(grid_coords, grid_settings.minor_line_dash);
+    let major_coverage = max(major_dashes.x, major_dashes.y);
+    let minor_coverage = max(minor_dashes.x, minor_dashes.y);
+#ifdef CORE_2D
+    let major_alpha = major_coverage * line_density_fade(coord * 0.1);
+    let minor_alpha = minor_coverage * minor_fadeout * line_density_fade(coord);
+#else
+    let major_alpha = major_coverage;
+    let minor_alpha = minor_coverage * minor_fadeout;
+#endif
+    let grid_alpha = max(major_alpha, minor_alpha);
+    // blended by coverage: filtered far away, major coverage never drops to 0 between the lines,
+    // so picking one color would turn every distant line major at once
+    let base_grid_color = mix(grid_settings.minor_line_col, grid_settings.major_line_col, major_alpha / max(grid_alpha, 1e-5));
+    // every line is a minor line, so their coverage tells which way the line here runs,
+    // blending the tints where lines cross
+    let directions = axis_line_coverage(coord);
+    let along_x = directions.y / max(directions.x + directions.y, 1e-5);
+    let tint = mix(grid_settings.z_lines_tint, grid_settings.x_lines_tint, along_x);
+    // only the color may go past 1, for bloom
+    var grid_color = vec4<f32>(base_grid_color.rgb * tint.rgb, min(base_grid_color.a * grid_alpha * tint.a, 1.));
+#ifdef HEATMAP
+    if grid_heatmap.tint_lines != 0u {
+        let tint = sample_grid_heatmap(plane_coords);
+        grid_color = vec4<f32>(mix(grid_color.rgb, tint.rgb, tint.a), grid_color.a);
+    }
+#endif
 
-    let grid_alpha = 1.0 - min(lne, 1.0);
-    let base_grid_color = mix(grid_settings.major_line_col, grid_settings.minor_line_col, step(1., mg_line));
-    let grid_color = vec4<f32>(base_grid_color.rgb, base_grid_color.a * grid_alpha);
+    if grid_settings.sub_scale > 0. {
+        let sub_coord = grid_coords * grid_settings.sub_scale;
+        let sub_fadeout = clamp(1. - grid_settings.sub_dist_fadeout_const * fade_distance, 0., 1.);
+#ifdef CORE_2D
+        let sub_alpha = grid_settings.sub_line_col.a * line_coverage(sub_coord) * sub_fadeout * line_density_fade(sub_coord);
+#else
+        let sub_alpha = grid_settings.sub_line_col.a * line_coverage(sub_coord) * sub_fadeout;
+#endif
+        // composite the grid over the sub-grid, so shared lines don't darken where they cross
+        let alpha = grid_color.a + sub_alpha * (1. - grid_color.a);
+        let rgb = grid_color.rgb * grid_color.a + grid_settings.sub_line_col.rgb * sub_alpha * (1. - grid_color.a);
+        grid_color = vec4<f32>(rgb / max(alpha, 1e-5), alpha);
+    }
+
+#ifdef HEATMAP
+    // the cell's color, from its center, under the lines
+    let cell = (floor(coord) + 0.5) / scale + grid_settings.origin_offset;
+    let heat = sample_grid_heatmap(cell);
+    let heat_alpha = grid_color.a + heat.a * (1. - grid_color.a);
+    let heat_rgb = grid_color.rgb * grid_color.a + heat.rgb * heat.a * (1. - grid_color.a);
+    grid_color = vec4<f32>(heat_rgb / max(heat_alpha, 1e-5), heat_alpha);
+#endif
 
     #ifdef SHADOWS
+#ifdef SHADOW_LIGHTEN
+    var color = grid_color;
+#else
     var color = mix(grid_color, grid_shadow.shadow_col, 1. - shadow2);
+#endif
     #else
     var color = grid_color;
     #endif
 
-    let z_axis_cond = plane_coords.x > -1.0 * minimumx && plane_coords.x < 1.0 * minimumx;
-    let x_axis_cond = plane_coords.y > -1.0 * minimumz && plane_coords.y < 1.0 * minimumz;
+    let highlight_axes = grid_settings.highlight_axes != 0u;
+    if highlight_axes {
+        let z_axis_cond = grid_coords.x > -1.0 * minimumx && grid_coords.x < 1.0 * minimumx;
+        let x_axis_cond = grid_coords.y > -1.0 * minimumz && grid_coords.y < 1.0 * minimumz;
+#ifdef CORE_2D
+        // the axes stay once the lines along them have faded out
+        let axis_coverage = 1. - min(abs(coord) / derivative / grid_settings.aa_width, vec2<f32>(1.));
+        color.a = max(color.a, max(axis_coverage.x, axis_coverage.y) * grid_settings.major_line_col.a);
+#endif
 
-    color = mix(color, vec4<f32>(grid_settings.z_axis_col, color.a), f32(z_axis_cond));
-    color = mix(color, vec4<f32>(grid_settings.x_axis_col, color.a), f32(x_axis_cond));
+        color = mix(color, vec4<f32>(grid_settings.z_axis_col, color.a), f32(z_axis_cond));
+        color = mix(color, vec4<f32>(grid_settings.x_axis_col, color.a), f32(x_axis_cond));
+    }
 
-    let dist_fadeout = min(1., 1. - grid_settings.dist_fadeout_const * real_depth);
-    let dot_fadeout = abs(dot(grid_position.normal, normalize(view.world_position - frag_pos_3d)));
-    let alpha_fadeout = mix(dist_fadeout, 1., dot_fadeout) * min(grid_settings.dot_fadeout_const * dot_fadeout, 1.);
+    // past the fadeout distance this would go negative, and a negative alpha darkens what's behind
+    // the grid into a band along the horizon
+    let dist_fadeout = clamp(1. - grid_settings.dist_fadeout_const * fade_distance, 0., 1.);
+    // towards the camera, back along the ray for parallel rays
+    let to_camera = select(normalize(view.world_position - frag_pos_3d), -ray_direction, orthographic);
+    let dot_fadeout = abs(dot(grid_position.normal, to_camera));
+    // looking down on the grid lifts the camera fadeout, the radial one holds at any angle
+    let view_fadeout = select(mix(dist_fadeout, 1., dot_fadeout), dist_fadeout, grid_settings.radial_fade != 0u);
+    let alpha_fadeout = view_fadeout * min(grid_settings.dot_fadeout_const * dot_fadeout, 1.);
 
     color.a = color.a * alpha_fadeout;
+#ifdef PREMULTIPLIED_ALPHA
+    // premultiplied alpha, so the light adds to what's behind the grid instead of covering it
+    var light = vec3<f32>(0.);
+#ifdef SHADOW_LIGHTEN
+    light += grid_shadow.shadow_col.rgb * grid_shadow.shadow_col.a * (1. - shadow2) * alpha_fadeout;
+#endif
+#ifdef GRID_GLOW
+    var glow = max(
+        line_glow_color(grid_settings.major_line_col, axis_line_glow(coord * 0.1)),
+        line_glow_color(grid_settings.minor_line_col, axis_line_glow(coord) * minor_fadeout),
+    );
+    if highlight_axes {
+        // the axes are single lines, their halo never crowds
+        let axis_glow = max(1. - abs(coord) / derivative / grid_settings.glow, vec2<f32>(0.));
+        glow = max(glow, grid_settings.z_axis_col * axis_glow.x * axis_glow.x);
+        glow = max(glow, grid_settings.x_axis_col * axis_glow.y * axis_glow.y);
+    }
+    // around the lines only, their own color already covers them
+    light += glow * (1. - color.a) * alpha_fadeout;
+#endif
+    out.color = vec4<f32>(color.rgb * color.a + light, color.a);
+#else
     out.color = color;
+#endif
 
     return out;
 }
\ No newline at end of file
@@ -5,61 +5,66 @@ use bevy::{
     ecs::{
         query::ROQueryItem,
         system::{
-            lifetimeless::{Read, SRes},
+            lifetimeless::{Read, SQuery, SRes},
             SystemParamItem,
         },
     },
     pbr::{
-        setup_morph_and_skinning_defs, DrawMesh, MaterialPipeline, MaterialPipelineKey,
-        MeshLayouts, MeshPipeline, MeshPipelineKey, RenderMaterialInstances, RenderMaterials,
-        RenderMeshInstances, SetMaterialBindGroup, SetMeshBindGroup, MAX_CASCADES_PER_LIGHT,
-        MAX_DIRECTIONAL_LIGHTS,
+        setup_morph_and_skinning_defs, DrawMesh, MeshLayouts, MeshPipeline, MeshPipelineKey,
+        RenderMaterialInstances, RenderMaterials, RenderMeshInstances, SetMaterialBindGroup,
+        SetMeshBindGroup, MAX_CASCADES_PER_LIGHT, MAX_DIRECTIONAL_LIGHTS,
     },
     prelude::*,
     render::{
         batching::batch_and_prepare_render_phase,
-        camera::CameraProjection,
+        camera::{CameraProjection, ExtractedCamera},
         mesh::MeshVertexBufferLayout,
         render_asset::RenderAssets,
         render_graph::{Node, RenderGraph, RenderLabel},
         render_phase::{
-            AddRenderCommand, CachedRenderPipelinePhaseItem, DrawFunctionId, DrawFunctions,
-            PhaseItem, RenderCommand, RenderCommandResult, RenderPhase, SetItemPipeline,
+            sort_phase_system, AddRenderCommand, CachedRenderPipelinePhaseItem, DrawFunctionId,
+            DrawFunctions, PhaseItem, RenderCommand, RenderCommandResult, RenderPhase,
+            SetItemPipeline,
         },
         render_resource::{
             AddressMode, AsBindGroup, BindGroup, BindGroupEntries, BindGroupLayout,
-            BindGroupLayoutEntry, BindingType, BufferBindingType, BufferSize,
-            CachedRenderPipelineId, ColorTargetState, ColorWrites, Extent3d, FilterMode,
-            FragmentState, FrontFace, LoadOp, MultisampleState, Operations, PipelineCache,
-            PolygonMode, PrimitiveState, RenderPassColorAttachment, RenderPassDescriptor,
-            RenderPipelineDescriptor, Sampler, SamplerDescriptor, ShaderDefVal, ShaderStages,
-            ShaderType, SpecializedMeshPipeline, SpecializedMeshPipelineError,
-            SpecializedMeshPipelines, StoreOp, TextureDescriptor, TextureDimension, TextureFormat,
-            TextureUsages, TextureView, VertexState,
+            BindGroupLayoutEntry, BindingType, BlendComponent, BlendFactor, BlendOperation,
+            BlendState, BufferBindingType, BufferId, BufferSize, CachedPipelineState,
+            CachedRenderPipelineId, ColorTargetState, ColorWrites, DynamicUniformBuffer, Extent3d,
+            FilterMode, FragmentState, FrontFace, LoadOp, MultisampleState, Operations,
+            PipelineCache, PolygonMode, PrimitiveState, RenderPassColorAttachment,
+            RenderPassDescriptor, RenderPipelineDescriptor, Sampler, SamplerBindingType,
+            SamplerDescriptor, SamplerId, ShaderDefVal, ShaderStages, ShaderType,
+            SpecializedMeshPipeline, SpecializedMeshPipelineError, SpecializedMeshPipelines,
+            StoreOp, Texture, TextureDescriptor, TextureDimension, TextureFormat,
+            TextureSampleType, TextureUsages, TextureView, TextureViewDimension, TextureViewId,
+            VertexState,
         },
-        renderer::RenderDevice,
-        texture::TextureCache,
-        view::{
-            prepare_view_uniforms, ExtractedView, ExtractedWindows, ViewUniform, ViewUniformOffset,
-            ViewUniforms, VisibleEntities,
-        },
-        Render, RenderApp, RenderSet,
+        renderer::{RenderAdapter, RenderDevice, RenderQueue},
+        settings::WgpuFeatures,
+        view::{ExtractedView, ViewUniform, ViewUniformOffset, ViewUniforms, VisibleEntities},
+        Extract, ExtractSchedule, Render, RenderApp, RenderSet,
     },
-    utils::{nonmax::NonMaxU32, FloatOrd},
+    utils::{nonmax::NonMaxU32, HashMap, Instant},
 };
+use wgpu::TextureFormatFeatures;
 
-use crate::{GlobalInfiniteGridSettings, GridFrustumIntersect};
-
-use super::{
-    ExtractedInfiniteGrid, GridShadowUniformOffset, GridShadowUniforms, InfiniteGridPipeline,
+use crate::{
+    diagnostics::GridRenderStats, GlobalInfiniteGridSettings, GridFrustumIntersect, GridQuality,
+    GridShadowCamera, GridShadowCaster, GridShadowCasterOnly, GridShadowFormat, GridShadowOpacity,
+    GridShadowSettings, GridShadowUpdate, GridShadowUpdateMode, InfiniteGridSettings,
+    NoInfiniteGrid, PcfKernel, ReflectionCaptureCamera,
 };
 
+use super::{shadow_timing::GridShadowGpuTimer, ExtractedInfiniteGrid, InfiniteGridPipeline};
+
 static SHADOW_RENDER: &str = include_str!("shadow_render.wgsl");
 
 const SHADOW_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(10461510954165139918);
 
 pub struct GridShadow {
     pub entity: Entity,
+    pub asset_id: AssetId<Mesh>,
     pub pipeline: CachedRenderPipelineId,
     pub draw_function: DrawFunctionId,
     pub batch_range: Range<u32>,
@@ -67,7 +72,9 @@ pub struct GridShadow {
 }
 
 impl PhaseItem for GridShadow {
-    type SortKey = FloatOrd;
+    // Draw order doesn't matter with max blending, so sort like opaque meshes: casters sharing a
+    // pipeline and mesh end up next to each other and get batched into one draw.
+    type SortKey = (usize, AssetId<Mesh>);
 
     #[inline]
     fn entity(&self) -> Entity {
@@ -76,7 +83,12 @@ impl PhaseItem for GridShadow {
 
     #[inline]
     fn sort_key(&self) -> Self::SortKey {
-        unimplemented!("grid shadows don't need sorting")
+        (self.pipeline.id(), self.asset_id)
+    }
+
+    #[inline]
+    fn sort(items: &mut [Self]) {
+        items.sort_unstable_by_key(Self::sort_key);
     }
 
     #[inline]
@@ -112,15 +124,19 @@ impl CachedRenderPipelinePhaseItem for GridShadow {
 pub struct GridShadowPipeline {
     pub view_layout: BindGroupLayout,
     pub material_layout: BindGroupLayout,
-    pub material_pipeline: MaterialPipeline<StandardMaterial>,
     pub mesh_layouts: MeshLayouts,
+    pub per_object_buffer_batch_size: Option<u32>,
+    pub conservative_rasterization: bool,
     pub sampler: Sampler,
+    /// Bound in place of shadows without casters. Textures start zeroed, which is no coverage.
+    pub empty_texture_view: TextureView,
 }
 
 impl FromWorld for GridShadowPipeline {
     fn from_world(world: &mut World) -> Self {
         let world = world.cell();
         let render_device = world.get_resource::<RenderDevice>().unwrap();
+        let settings = world.get_resource::<RenderSettings>().unwrap().clone();
 
         let view_layout = render_device.create_bind_group_layout(
             "grid_shadow_view_layout",
@@ -136,36 +152,129 @@ impl FromWorld for GridShadowPipeline {
                     },
                     count: None,
                 },
+                // Grid plane
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::VERTEX | ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        min_binding_size: Some(GridShadowPassUniform::min_size()),
+                    },
+                    count: None,
+                },
+                // Caster opacity
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        min_binding_size: Some(GridShadowCasterUniform::min_size()),
+                    },
+                    count: None,
+                },
             ],
         );
 
         let mesh_pipeline = world.get_resource::<MeshPipeline>().unwrap();
-        let material_pipeline = world
-            .get_resource::<MaterialPipeline<StandardMaterial>>()
-            .unwrap()
-            .clone();
 
         GridShadowPipeline {
             view_layout,
             mesh_layouts: mesh_pipeline.mesh_layouts.clone(),
-            sampler: render_device.create_sampler(&SamplerDescriptor {
-                address_mode_u: AddressMode::ClampToEdge,
-                address_mode_v: AddressMode::ClampToEdge,
-                address_mode_w: AddressMode::ClampToEdge,
-                mag_filter: FilterMode::Linear,
-                min_filter: FilterMode::Linear,
-                mipmap_filter: FilterMode::Nearest,
-                compare: None,
-                ..Default::default()
-            }),
+            per_object_buffer_batch_size: mesh_pipeline.per_object_buffer_batch_size,
+            sampler: create_shadow_sampler(&render_device, settings.shadow_filter),
+            empty_texture_view: render_device
+                .create_texture(&TextureDescriptor {
+                    label: Some("grid_shadow_empty_texture"),
+                    size: Extent3d::default(),
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: TextureDimension::D2,
+                    format: TextureFormat::R8Unorm,
+                    usage: TextureUsages::TEXTURE_BINDING,
+                    view_formats: &[],
+                })
+                .create_view(&Default::default()),
+            conservative_rasterization: use_conservative_rasterization(&render_device, &settings),
             material_layout: StandardMaterial::bind_group_layout(&render_device),
-            material_pipeline,
         }
     }
 }
 
+fn create_shadow_sampler(render_device: &RenderDevice, filter: ShadowFilter) -> Sampler {
+    let (filter_mode, anisotropy_clamp) = match filter {
+        ShadowFilter::Linear => (FilterMode::Linear, 1),
+        ShadowFilter::Nearest => (FilterMode::Nearest, 1),
+        // wgpu only allows anisotropy together with linear filtering.
+        ShadowFilter::Anisotropic(clamp) => (FilterMode::Linear, clamp.clamp(1, 16)),
+    };
+    render_device.create_sampler(&SamplerDescriptor {
+        address_mode_u: AddressMode::ClampToEdge,
+        address_mode_v: AddressMode::ClampToEdge,
+        address_mode_w: AddressMode::ClampToEdge,
+        mag_filter: filter_mode,
+        min_filter: filter_mode,
+        mipmap_filter: FilterMode::Nearest,
+        compare: None,
+        anisotropy_clamp,
+        ..Default::default()
+    })
+}
+
+// Unsupported devices silently keep regular rasterization.
+fn use_conservative_rasterization(render_device: &RenderDevice, settings: &RenderSettings) -> bool {
+    settings.conservative_rasterization
+        && render_device
+            .features()
+            .contains(WgpuFeatures::CONSERVATIVE_RASTERIZATION)
+}
+
+fn update_shadow_pipeline(
+    settings: Res<RenderSettings>,
+    render_device: Res<RenderDevice>,
+    mut pipeline: ResMut<GridShadowPipeline>,
+    mut pipelines: ResMut<SpecializedMeshPipelines<GridShadowPipeline>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    pipeline.sampler = create_shadow_sampler(&render_device, settings.shadow_filter);
+
+    let conservative_rasterization = use_conservative_rasterization(&render_device, &settings);
+    if pipeline.conservative_rasterization != conservative_rasterization {
+        pipeline.conservative_rasterization = conservative_rasterization;
+        // Specialized pipelines are cached by key, which doesn't include this flag.
+        *pipelines = SpecializedMeshPipelines::default();
+    }
+}
+
+fn extract_render_settings(
+    mut commands: Commands,
+    settings: Extract<Res<GlobalInfiniteGridSettings>>,
+) {
+    if settings.is_changed() {
+        commands.insert_resource(settings.render_settings.clone());
+        commands.insert_resource(ExtractedGridQuality(settings.quality));
+    }
+}
+
+/// [`GlobalInfiniteGridSettings::quality`] in the render world.
+#[derive(Resource)]
+struct ExtractedGridQuality(GridQuality);
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GridShadowPipelineKey {
+    mesh_key: MeshPipelineKey,
+    /// Cut the caster out with its `StandardMaterial`'s base color alpha.
+    alpha_mask: bool,
+    format: TextureFormat,
+    /// Samples per texel, see [`RenderSettings::shadow_msaa_samples`].
+    samples: u32,
+}
+
 impl SpecializedMeshPipeline for GridShadowPipeline {
-    type Key = MaterialPipelineKey<StandardMaterial>;
+    type Key = GridShadowPipelineKey;
 
     fn specialize(
         &self,
@@ -174,10 +283,6 @@ impl SpecializedMeshPipeline for GridShadowPipeline {
     ) -> Result<RenderPipelineDescriptor, SpecializedMeshPipelineError> {
         let mut vertex_attributes = vec![Mesh::ATTRIBUTE_POSITION.at_shader_location(0)];
 
-        let mut bind_group_layouts = vec![self.view_layout.clone()];
-
-        bind_group_layouts.insert(1, self.material_layout.clone());
-
         let mut shader_defs = vec![
             ShaderDefVal::UInt(
                 "MAX_DIRECTIONAL_LIGHTS".to_string(),
@@ -189,8 +294,17 @@ impl SpecializedMeshPipeline for GridShadowPipeline {
             ),
         ];
 
-        bind_group_layouts.insert(
-            2,
+        if let Some(batch_size) = self.per_object_buffer_batch_size {
+            shader_defs.push(ShaderDefVal::UInt(
+                "PER_OBJECT_BUFFER_BATCH_SIZE".to_string(),
+                batch_size,
+            ));
+        }
+
+        // Casters only need their mesh, so any material casts a shadow. Same group order as
+        // bevy's mesh pipeline: view, mesh, and for alpha masked casters the material.
+        let mut bind_group_layouts = vec![
+            self.view_layout.clone(),
             setup_morph_and_skinning_defs(
                 &self.mesh_layouts,
                 layout,
@@ -199,11 +313,16 @@ impl SpecializedMeshPipeline for GridShadowPipeline {
                 &mut shader_defs,
                 &mut vertex_attributes,
             ),
-        );
+        ];
+        if key.alpha_mask {
+            shader_defs.push("ALPHA_MASK".into());
+            vertex_attributes.push(Mesh::ATTRIBUTE_UV_0.at_shader_location(1));
+            bind_group_layouts.push(self.material_layout.clone());
+        }
 
         let vertex_buffer_layout = layout.get_layout(&vertex_attributes)?;
 
-        let mut descriptor = RenderPipelineDescriptor {
+        Ok(RenderPipelineDescriptor {
             vertex: VertexState {
                 shader: SHADOW_SHADER_HANDLE,
                 entry_point: "vertex".into(),
@@ -215,9 +334,21 @@ impl SpecializedMeshPipeline for GridShadowPipeline {
                 shader_defs,
                 entry_point: "fragment".into(),
                 targets: vec![Some(ColorTargetState {
-                    format: TextureFormat::R8Unorm,
-                    blend: None,
-                    write_mask: ColorWrites::RED,
+                    format: key.format,
+                    // Overlapping casters keep the darkest coverage and the highest closeness,
+                    // i.e. the lowest caster.
+                    blend: Some(BlendState {
+                        color: BlendComponent {
+                            src_factor: BlendFactor::One,
+                            dst_factor: BlendFactor::One,
+                            operation: BlendOperation::Max,
+                        },
+                        alpha: BlendComponent::REPLACE,
+                    }),
+                    write_mask: match key.format {
+                        TextureFormat::Rg16Float => ColorWrites::RED | ColorWrites::GREEN,
+                        _ => ColorWrites::RED,
+                    },
                 })],
             }),
             layout: bind_group_layouts,
@@ -229,29 +360,37 @@ impl SpecializedMeshPipeline for GridShadowPipeline {
                 cull_mode: None,
                 unclipped_depth: false,
                 polygon_mode: PolygonMode::Fill,
-                conservative: false,
+                conservative: self.conservative_rasterization,
             },
             depth_stencil: None,
-            multisample: MultisampleState::default(),
+            multisample: MultisampleState {
+                count: key.samples,
+                ..Default::default()
+            },
             label: Some("grid_shadow_pipeline".into()),
-        };
-
-        StandardMaterial::specialize(&self.material_pipeline, &mut descriptor, layout, key)?;
-
-        Ok(descriptor)
+        })
     }
 }
 
 #[derive(Resource, Default)]
 struct GridShadowMeta {
     view_bind_group: Option<BindGroup>,
+    /// View, pass and caster uniform buffers bound by `view_bind_group`.
+    view_bind_group_buffers: Option<[Option<BufferId>; 3]>,
 }
 
 type DrawGridShadowMesh = (
     SetItemPipeline,
     SetGridShadowViewBindGroup<0>,
-    SetMaterialBindGroup<StandardMaterial, 1>,
-    SetMeshBindGroup<2>,
+    SetMeshBindGroup<1>,
+    DrawMesh,
+);
+
+type DrawGridShadowMaskedMesh = (
+    SetItemPipeline,
+    SetGridShadowViewBindGroup<0>,
+    SetMeshBindGroup<1>,
+    SetMaterialBindGroup<StandardMaterial, 2>,
     DrawMesh,
 );
 
@@ -259,104 +398,900 @@ struct SetGridShadowViewBindGroup<const I: usize>;
 
 impl<const I: usize, P: PhaseItem> RenderCommand<P> for SetGridShadowViewBindGroup<I> {
     type Param = SRes<GridShadowMeta>;
-    type ViewQuery = Read<ViewUniformOffset>;
-    type ItemQuery = ();
+    type ViewQuery = (Read<ViewUniformOffset>, Read<GridShadowPassUniformOffset>);
+    type ItemQuery = Read<GridShadowCasterUniformOffset>;
 
     #[inline]
     fn render<'w>(
         _item: &P,
-        view_uniform_offset: ROQueryItem<'w, Self::ViewQuery>,
-        _entity: ROQueryItem<'w, Option<Self::ItemQuery>>,
+        (view_uniform_offset, pass_uniform_offset): ROQueryItem<'w, Self::ViewQuery>,
+        caster_uniform_offset: ROQueryItem<'w, Option<Self::ItemQuery>>,
         meta: SystemParamItem<'w, '_, Self::Param>,
         pass: &mut bevy::render::render_phase::TrackedRenderPass<'w>,
     ) -> RenderCommandResult {
+        let Some(view_bind_group) = meta.into_inner().view_bind_group.as_ref() else {
+            return RenderCommandResult::Failure;
+        };
         pass.set_bind_group(
             I,
-            meta.into_inner().view_bind_group.as_ref().unwrap(),
-            &[view_uniform_offset.offset],
+            view_bind_group,
+            &[
+                view_uniform_offset.offset,
+                pass_uniform_offset.offset,
+                // Casters without `GridShadowOpacity` share the first, opaque, uniform.
+                caster_uniform_offset.map_or(0, |offset| offset.offset),
+            ],
         );
 
         RenderCommandResult::Success
     }
 }
 
+#[derive(Debug, ShaderType)]
+pub struct GridShadowUniform {
+    shadow_color: Vec4,
+    shadow_intensity: f32,
+    shadow_falloff: f32,
+    shadow_softness: f32,
+    shadow_collapse_matrix: Mat3,
+    shadow_center_pos: Vec3,
+    shadow_texture_width: f32,
+    shadow_texture_height: f32,
+    shadow_shear: Vec3,
+    shadow_height_softness: f32,
+    shadow_max_softness: f32,
+    cascade_collapse_matrix: Mat3,
+    cascade_center_pos: Vec3,
+    cascade_texture_width: f32,
+    cascade_texture_height: f32,
+}
+
+#[derive(Resource, Default)]
+struct GridShadowUniforms {
+    uniforms: DynamicUniformBuffer<GridShadowUniform>,
+}
+
+/// What the casters in a shadow view need to know about the grid they shadow.
+#[derive(Debug, ShaderType)]
+pub struct GridShadowPassUniform {
+    plane_origin: Vec3,
+    plane_normal: Vec3,
+    height_falloff: f32,
+}
+
+#[derive(Resource, Default)]
+struct GridShadowPassUniforms {
+    uniforms: DynamicUniformBuffer<GridShadowPassUniform>,
+}
+
 #[derive(Component)]
-struct GridShadowView {
-    texture_view: TextureView,
+pub struct GridShadowPassUniformOffset {
+    offset: u32,
 }
 
-fn prepare_grid_shadow_views(
+/// What the shadow pass needs to know about a single caster.
+#[derive(Debug, ShaderType)]
+pub struct GridShadowCasterUniform {
+    opacity: f32,
+}
+
+#[derive(Resource, Default)]
+struct GridShadowCasterUniforms {
+    uniforms: DynamicUniformBuffer<GridShadowCasterUniform>,
+}
+
+#[derive(Component)]
+pub struct GridShadowCasterUniformOffset {
+    offset: u32,
+}
+
+/// Dynamic offset of a shadow view's uniform in [`GridShadowBindGroup`], in the render world.
+#[derive(Component)]
+pub struct GridShadowUniformOffset {
+    offset: u32,
+}
+
+impl GridShadowUniformOffset {
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+}
+
+fn prepare_grid_shadows(
     mut commands: Commands,
-    grids: Query<(Entity, &ExtractedInfiniteGrid, &GridFrustumIntersect)>,
+    shadow_views: Query<(Entity, &GridShadowView, &GridFrustumIntersect)>,
+    grids: Query<(&ExtractedInfiniteGrid, Option<&GridShadowShear>)>,
+    mut uniforms: ResMut<GridShadowUniforms>,
+    mut pass_uniforms: ResMut<GridShadowPassUniforms>,
     render_device: Res<RenderDevice>,
-    mut texture_cache: ResMut<TextureCache>,
-    windows: Res<ExtractedWindows>,
-    settings: Res<RenderSettings>,
+    render_queue: Res<RenderQueue>,
 ) {
-    let primary_window = if let Some(w) = windows.primary.as_ref().and_then(|id| windows.get(id)) {
-        w
-    } else {
-        return;
+    uniforms.uniforms.clear();
+    pass_uniforms.uniforms.clear();
+    for (entity, shadow_view, intersect) in shadow_views.iter() {
+        let Ok((extracted, shear)) = grids.get(shadow_view.grid) else {
+            continue;
+        };
+        let transform = extracted.transform;
+        let normal = transform.up();
+        let collapse_matrix = |intersect: &GridFrustumIntersect| {
+            Mat3::from_cols(normal.cross(-intersect.up_dir), normal, -intersect.up_dir).inverse()
+        };
+        // Without a near cascade the grid never samples it, anything goes.
+        let cascade = shadow_view
+            .near_cascade
+            .and_then(|near_cascade| shadow_views.get(near_cascade).ok())
+            .map_or(*intersect, |(_, _, intersect)| *intersect);
+
+        if let Some(grid_shadow) = extracted.grid.shadow {
+            commands.entity(entity).insert(GridShadowUniformOffset {
+                offset: uniforms.uniforms.push(&GridShadowUniform {
+                    shadow_color: Vec4::from_slice(&grid_shadow.color.as_linear_rgba_f32()),
+                    shadow_intensity: grid_shadow.intensity,
+                    shadow_falloff: grid_shadow.falloff,
+                    shadow_softness: grid_shadow.softness,
+                    shadow_collapse_matrix: collapse_matrix(intersect),
+                    shadow_center_pos: intersect.center,
+                    shadow_texture_height: intersect.height,
+                    shadow_texture_width: intersect.width,
+                    shadow_shear: shear.map_or(Vec3::ZERO, |shear| shear.0),
+                    shadow_height_softness: grid_shadow.height_softness,
+                    shadow_max_softness: grid_shadow.max_softness,
+                    cascade_collapse_matrix: collapse_matrix(&cascade),
+                    cascade_center_pos: cascade.center,
+                    cascade_texture_width: cascade.width,
+                    cascade_texture_height: cascade.height,
+                }),
+            });
+            commands.entity(entity).insert(GridShadowPassUniformOffset {
+                offset: pass_uniforms.uniforms.push(&GridShadowPassUniform {
+                    plane_origin: transform.translation(),
+                    plane_normal: normal,
+                    height_falloff: grid_shadow.height_falloff,
+                }),
+            });
+        }
+    }
+
+    uniforms
+        .uniforms
+        .write_buffer(&render_device, &render_queue);
+    pass_uniforms
+        .uniforms
+        .write_buffer(&render_device, &render_queue);
+}
+
+fn prepare_grid_shadow_casters(
+    mut commands: Commands,
+    casters: Query<(Entity, &GridShadowOpacity)>,
+    mut uniforms: ResMut<GridShadowCasterUniforms>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+) {
+    uniforms.uniforms.clear();
+    uniforms
+        .uniforms
+        .push(&GridShadowCasterUniform { opacity: 1. });
+    for (entity, opacity) in casters.iter() {
+        commands
+            .entity(entity)
+            .insert(GridShadowCasterUniformOffset {
+                offset: uniforms.uniforms.push(&GridShadowCasterUniform {
+                    opacity: opacity.0.clamp(0., 1.),
+                }),
+            });
+    }
+
+    uniforms
+        .uniforms
+        .write_buffer(&render_device, &render_queue);
+}
+
+/// Kernel size and offsets of the shadow filter, `None` when the grid samples its shadow once.
+pub(super) fn shadow_pcf(shadow: &GridShadowSettings) -> Option<(u32, PcfKernel)> {
+    let size = match shadow.pcf_size {
+        0 | 1 => return None,
+        2 | 3 => 3,
+        _ => 5,
     };
-    let width = primary_window.physical_width;
-    let height = primary_window.physical_height;
+    (shadow.softness > 0. || contact_hardening(shadow)).then_some((size, shadow.pcf_kernel))
+}
+
+/// Whether the shadow blurs with caster height, which needs the heights in the shadow texture.
+pub(super) fn contact_hardening(shadow: &GridShadowSettings) -> bool {
+    shadow.height_softness > 0. && shadow.pcf_size > 1
+}
+
+pub(super) fn create_grid_shadows_layout(render_device: &RenderDevice) -> BindGroupLayout {
+    render_device.create_bind_group_layout(
+        "grid-shadows-bind-group-layout",
+        &[
+            BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::FRAGMENT,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: true,
+                    min_binding_size: BufferSize::new(GridShadowUniform::min_size().into()),
+                },
+                count: None,
+            },
+            BindGroupLayoutEntry {
+                binding: 1,
+                visibility: ShaderStages::FRAGMENT,
+                ty: BindingType::Texture {
+                    // Every `GridShadowFormat` is a filterable float format.
+                    sample_type: TextureSampleType::Float { filterable: true },
+                    view_dimension: TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            BindGroupLayoutEntry {
+                binding: 2,
+                visibility: ShaderStages::FRAGMENT,
+                ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                count: None,
+            },
+            // Near cascade
+            BindGroupLayoutEntry {
+                binding: 3,
+                visibility: ShaderStages::FRAGMENT,
+                ty: BindingType::Texture {
+                    sample_type: TextureSampleType::Float { filterable: true },
+                    view_dimension: TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+        ],
+    )
+}
+
+/// A grid's shadow as seen from one [`GridShadowCamera`], in the render world. Found through
+/// [`ViewGridShadows`].
+#[derive(Component)]
+pub struct GridShadowView {
+    texture_view: TextureView,
+    /// Multisampled target resolved into `texture_view`, with MSAA.
+    msaa_view: Option<TextureView>,
+    pub grid: Entity,
+    pub(super) camera: Entity,
+    /// 0 for the whole grid, 1 for the near cascade.
+    cascade: usize,
+    /// The near cascade's shadow view, on the main one.
+    near_cascade: Option<Entity>,
+    format: TextureFormat,
+    samples: u32,
+    /// Whether the shadow is rendered this frame, otherwise the texture keeps the last one.
+    rerender: bool,
+    /// Whether the shadow has no casters. The pass is skipped and the grid samples an empty
+    /// texture instead.
+    pub(super) empty: bool,
+    /// Area casters are drawn in, as x, y, width and height, see
+    /// [`RenderSettings::shadow_border`].
+    scissor: Option<UVec4>,
+}
+
+impl GridShadowView {
+    /// The shadow texture, rendered before the main pass. It has the usages from
+    /// `RenderSettings::shadow_texture_extra_usages` on top of its own.
+    ///
+    /// Its content is stale when [`is_empty`](Self::is_empty).
+    pub fn texture_view(&self) -> &TextureView {
+        &self.texture_view
+    }
+
+    /// Whether no caster lands in the shadow, so nothing was rendered into the texture.
+    pub fn is_empty(&self) -> bool {
+        self.empty
+    }
+}
+
+/// Shadow textures by camera and grid. They outlive the frame, unlike `TextureCache` ones, so a
+/// shadow that didn't change can be sampled again without rendering it.
+#[derive(Resource, Default)]
+struct GridShadowTextures {
+    /// Keyed by camera, grid and cascade, 0 for the whole grid and 1 for the near cascade.
+    textures: HashMap<(Entity, Entity, usize), GridShadowTexture>,
+}
+
+struct GridShadowTexture {
+    // Keeps the texture alive alongside its view.
+    _texture: Texture,
+    view: TextureView,
+    /// Multisampled target the shadow is drawn into, with MSAA.
+    msaa: Option<(Texture, TextureView)>,
+    size: (u32, u32),
+    format: TextureFormat,
+    usage: TextureUsages,
+    samples: u32,
+    intersect: GridFrustumIntersect,
+    update: GridShadowUpdate,
+    used: bool,
+    /// No caster was drawn into the texture, whatever it holds is stale.
+    empty: bool,
+    /// Casters were left out while their pipelines compiled, so the texture needs another render.
+    incomplete: bool,
+}
+
+impl GridShadowTexture {
+    fn matches(
+        &self,
+        size: (u32, u32),
+        format: TextureFormat,
+        usage: TextureUsages,
+        samples: u32,
+    ) -> bool {
+        self.size == size && self.format == format && self.usage == usage && self.samples == samples
+    }
+}
+
+/// Shadow views of the grids seen by a [`GridShadowCamera`], keyed by grid entity.
+///
+/// Lives on the camera's render world entity. Shadow view entities hold the
+/// [`GridShadowBindGroup`] and [`GridShadowUniformOffset`] to sample a grid's shadow with.
+#[derive(Component, Default)]
+pub struct ViewGridShadows {
+    pub shadows: HashMap<Entity, Entity>,
+}
+
+#[allow(clippy::type_complexity)]
+fn extract_grid_shadow_cameras(
+    mut commands: Commands,
+    cameras: Extract<Query<Entity, (With<Camera>, With<GridShadowCamera>)>>,
+) {
+    let extracted: Vec<_> = cameras
+        .iter()
+        .map(|entity| (entity, GridShadowCamera))
+        .collect();
+    commands.insert_or_spawn_batch(extracted);
+}
+
+fn extract_grid_shadow_updates(
+    mut commands: Commands,
+    grids: Extract<Query<(Entity, &GridShadowUpdate)>>,
+) {
+    let extracted: Vec<_> = grids
+        .iter()
+        .map(|(entity, update)| (entity, *update))
+        .collect();
+    commands.insert_or_spawn_batch(extracted);
+}
+
+/// How far a point's shadow lands across the grid per unit of height, resolved in the main world
+/// where the followed light lives.
+#[derive(Component, Clone, Copy)]
+struct GridShadowShear(Vec3);
+
+fn extract_grid_shadow_shears(
+    mut commands: Commands,
+    grids: Extract<Query<(Entity, &InfiniteGridSettings, &GlobalTransform)>>,
+    lights: Extract<Query<&GlobalTransform, With<DirectionalLight>>>,
+) {
+    let extracted: Vec<_> = grids
+        .iter()
+        .filter_map(|(entity, settings, transform)| {
+            let shear = settings.shadow?.projection.shear(transform.up(), |light| {
+                lights.get(light).ok().map(|light| light.forward())
+            });
+            Some((entity, GridShadowShear(shear)))
+        })
+        .collect();
+    commands.insert_or_spawn_batch(extracted);
+}
+
+// `NotShadowCaster` clears `shadow_caster` on the render mesh instance, so grid only casters need
+// their own marker in the render world.
+fn extract_grid_shadow_caster_only(
+    mut commands: Commands,
+    casters: Extract<Query<Entity, With<GridShadowCasterOnly>>>,
+) {
+    let extracted: Vec<_> = casters
+        .iter()
+        .map(|entity| (entity, GridShadowCasterOnly))
+        .collect();
+    commands.insert_or_spawn_batch(extracted);
+}
+
+fn extract_material_free_casters(
+    mut commands: Commands,
+    casters: Extract<Query<Entity, With<GridShadowCaster>>>,
+) {
+    let extracted: Vec<_> = casters
+        .iter()
+        .map(|entity| (entity, GridShadowCaster))
+        .collect();
+    commands.insert_or_spawn_batch(extracted);
+}
+
+fn extract_grid_shadow_opacities(
+    mut commands: Commands,
+    casters: Extract<Query<(Entity, &GridShadowOpacity)>>,
+) {
+    let extracted: Vec<_> = casters
+        .iter()
+        .map(|(entity, opacity)| (entity, *opacity))
+        .collect();
+    commands.insert_or_spawn_batch(extracted);
+}
+
+/// Shadow texels per pixel of the camera's longer viewport side. The shadow covers everything up
+/// to the fadeout distance, so most of its texels land far from the camera and a full HD window
+/// keeps the default 16384.
+const SHADOW_TEXELS_PER_PIXEL: u32 = 8;
+
+fn target_texture_size(viewport_size: UVec2) -> u32 {
+    (viewport_size.max_element() * SHADOW_TEXELS_PER_PIXEL).next_power_of_two()
+}
+
+// Keeps the aspect ratio of the shadowed area, so texels stay square, with the longest side at
+// `max_texture_size`.
+fn shadow_texture_size(width: f32, height: f32, max_texture_size: u32) -> (u32, u32) {
     let comp = width < height;
     let [min, max] = if comp {
         [width, height]
     } else {
         [height, width]
     };
-    let ratio = min as f32 / max as f32;
-    let tmax = settings.max_texture_size;
-    let tmin = (tmax as f32 * ratio) as u32;
-    let [width, height] = if comp { [tmin, tmax] } else { [tmax, tmin] };
-    for (entity, grid, frustum_intersect) in grids.iter() {
-        let texture = texture_cache.get(
-            &render_device,
-            TextureDescriptor {
-                label: Some("grid_shadow_texture"),
-                size: Extent3d {
-                    width,
-                    height,
-                    depth_or_array_layers: 1,
-                },
-                mip_level_count: 1,
-                sample_count: 1,
-                dimension: TextureDimension::D2,
-                format: TextureFormat::R8Unorm,
-                usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
-                view_formats: &[],
-            },
-        );
+    let ratio = min / max;
+    let tmax = max_texture_size;
+    let tmin = ((tmax as f32 * ratio) as u32).clamp(1, tmax);
+    if comp {
+        (tmin, tmax)
+    } else {
+        (tmax, tmin)
+    }
+}
 
-        let projection = OrthographicProjection {
-            area: Rect::new(
-                // left, bottom, right, top
-                frustum_intersect.width / -2.,
-                frustum_intersect.height / -2.,
-                frustum_intersect.width / 2.,
-                frustum_intersect.height / 2.,
-            ),
-            ..Default::default()
+// Covers the shadowed area with a rectangle along the grid's axes, made of whole texels on a grid
+// fixed to the grid, so the texels under a shadow edge don't change as the camera moves. Texel
+// sizes step by quarter octaves and only shrink once the area is well below the current one, the
+// texture size steps by 64 texels.
+fn snap_shadow_area(
+    intersect: &GridFrustumIntersect,
+    grid: &GlobalTransform,
+    max_texture_size: u32,
+    previous_texel: Option<f32>,
+) -> (GridFrustumIntersect, (u32, u32)) {
+    const TEXTURE_STEP: u32 = 64;
+    const SHRINK_BELOW: f32 = 0.75;
+
+    let normal = grid.up();
+    let up_dir = grid.forward();
+    let right = normal.cross(up_dir);
+    let origin = grid.translation();
+    let (min, max) = intersect
+        .points()
+        .iter()
+        .fold((Vec2::MAX, Vec2::MIN), |(min, max), point| {
+            let point = Vec2::new((*point - origin).dot(right), (*point - origin).dot(up_dir));
+            (min.min(point), max.max(point))
+        });
+    let size = max - min;
+
+    // Even texel counts keep the edges on the texel grid when the center is on it.
+    let max_texels = max_texture_size.max(2) & !1;
+    let required = (size.max_element() / max_texels as f32).max(f32::MIN_POSITIVE);
+    let texel = match previous_texel {
+        Some(previous) if required <= previous && required > previous * SHRINK_BELOW => previous,
+        _ => 2f32.powf((required.log2() * 4.).ceil() / 4.),
+    };
+    // One more texel on each side absorbs the snapping.
+    let texels = |extent: f32| {
+        let texels = ((extent / texel).ceil() as u32)
+            .saturating_add(2)
+            .min(max_texels);
+        (texels.div_ceil(TEXTURE_STEP) * TEXTURE_STEP).min(max_texels)
+    };
+    let (width, height) = (texels(size.x), texels(size.y));
+    let center = (((min + max) / 2.) / texel).round() * texel;
+
+    let snapped = GridFrustumIntersect {
+        center: origin + right * center.x + up_dir * center.y,
+        up_dir,
+        width: width as f32 * texel,
+        height: height as f32 * texel,
+        ..*intersect
+    };
+    (snapped, (width, height))
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn prepare_grid_shadow_views(
+    mut commands: Commands,
+    grids: Query<(
+        Entity,
+        &ExtractedInfiniteGrid,
+        Option<&GridShadowUpdate>,
+        Option<&GridShadowShear>,
+    )>,
+    cameras: Query<
+        (Entity, &ExtractedView, &ExtractedCamera, &VisibleEntities),
+        (
+            With<GridShadowCamera>,
+            Without<NoInfiniteGrid>,
+            Without<ReflectionCaptureCamera>,
+        ),
+    >,
+    render_device: Res<RenderDevice>,
+    render_adapter: Res<RenderAdapter>,
+    mut textures: ResMut<GridShadowTextures>,
+    settings: Res<RenderSettings>,
+    quality: Res<ExtractedGridQuality>,
+    stats: Res<GridRenderStats>,
+    mut warned_clamp: Local<bool>,
+    mut warned_usage: Local<bool>,
+    mut warned_samples: Local<bool>,
+) {
+    let device_max = render_device.limits().max_texture_dimension_2d;
+    let mut texture_bytes = 0;
+    for texture in textures.textures.values_mut() {
+        texture.used = false;
+    }
+    // Without shadows no texture is used, so they are all freed below.
+    let cameras = cameras.iter().filter(|_| quality.0.shadows());
+    for (camera_entity, camera_view, camera, visible_entities) in cameras {
+        // Whatever the target is (window, image or texture view), skip cameras that draw nothing.
+        let Some(viewport_size) = camera.physical_viewport_size else {
+            continue;
         };
+        if viewport_size.x == 0 || viewport_size.y == 0 {
+            continue;
+        }
 
-        commands.entity(entity).insert((
-            ExtractedView {
-                projection: projection.get_projection_matrix(),
-                transform: Transform::from_translation(
-                    frustum_intersect.center + grid.transform.up() * 500.,
+        let mut view_shadows = ViewGridShadows::default();
+        let mut drawn: Vec<(&ExtractedInfiniteGrid, Entity)> = Vec::new();
+        for (grid_entity, grid, update, shear) in grids.iter() {
+            let Some(shadow) = grid.grid.shadow else {
+                continue;
+            };
+            // Grids the camera doesn't draw, e.g. on other `RenderLayers`, get no shadow texture.
+            if !visible_entities.entities.contains(&grid_entity) {
+                continue;
+            }
+            // Coplanar grids with the same fadeout and shadow settings would render the exact
+            // same shadow, so they share one view, texture and pass.
+            if let Some(&(_, shadow_view)) = drawn.iter().find(|(other, _)| {
+                other.transform == grid.transform
+                    && other.grid.fadeout_distance == grid.grid.fadeout_distance
+                    && other.grid.shadow == grid.grid.shadow
+            }) {
+                view_shadows.shadows.insert(grid_entity, shadow_view);
+                continue;
+            }
+            // Without an explicit resolution, small targets like a preview image get textures to
+            // match rather than the full size.
+            let requested = shadow.resolution.unwrap_or_else(|| {
+                settings
+                    .max_texture_size
+                    .min(target_texture_size(viewport_size))
+            });
+            if requested > device_max && !*warned_clamp {
+                warn!(
+                    "Grid shadow texture size {requested} exceeds the device limit, using {device_max}"
+                );
+                *warned_clamp = true;
+            }
+            let mut max_texture_size = requested.clamp(1, device_max);
+            if quality.0 == GridQuality::Medium {
+                max_texture_size = max_texture_size.min(GridQuality::MEDIUM_MAX_TEXTURE_SIZE);
+            }
+            let update = update.copied().unwrap_or_default();
+            let format = shadow_texture_format(&shadow);
+            let usage = shadow_texture_usage(
+                &render_device,
+                &render_adapter,
+                format,
+                settings.shadow_texture_extra_usages,
+                &mut warned_usage,
+            );
+            let samples = if quality.0 == GridQuality::Medium {
+                1
+            } else {
+                shadow_sample_count(
+                    &render_device,
+                    &render_adapter,
+                    format,
+                    settings.shadow_msaa_samples,
+                    &mut warned_samples,
                 )
-                .looking_at(frustum_intersect.center, frustum_intersect.up_dir)
-                .into(),
-                view_projection: None,
-                hdr: false,
-                viewport: UVec4::new(0, 0, width, height),
-                color_grading: Default::default(),
-            },
-            GridShadowView {
-                texture_view: texture.default_view.clone(),
-            },
-        ));
+            };
+
+            // The near cascade comes first, so the main view can point at it.
+            let cascades: &[usize] = if shadow.cascades >= 2 { &[1, 0] } else { &[0] };
+            let mut shadow_view = None;
+            for &cascade in cascades {
+                let distance = if cascade == 0 {
+                    grid.grid.fadeout_distance
+                } else {
+                    shadow.cascade_distance.min(grid.grid.fadeout_distance)
+                };
+                let frustum_intersect = GridFrustumIntersect::new(
+                    &camera_view.transform,
+                    camera_view.projection,
+                    &grid.transform,
+                    distance,
+                )
+                .padded(settings.shadow_area_padding.max(1.));
+                // A degenerate area would make a degenerate projection. The near cascade lies
+                // within the main area, so without the main view there is no shadow at all.
+                if frustum_intersect.is_empty() {
+                    if cascade == 0 {
+                        shadow_view = None;
+                    }
+                    continue;
+                }
+                let (frustum_intersect, (width, height)) = if shadow.texel_snap {
+                    let previous_texel = textures
+                        .textures
+                        .get(&(camera_entity, grid_entity, cascade))
+                        .map(|texture| texture.intersect.width / texture.size.0 as f32);
+                    snap_shadow_area(
+                        &frustum_intersect,
+                        &grid.transform,
+                        max_texture_size,
+                        previous_texel,
+                    )
+                } else {
+                    let size = shadow_texture_size(
+                        frustum_intersect.width,
+                        frustum_intersect.height,
+                        max_texture_size,
+                    );
+                    (frustum_intersect, size)
+                };
+                let mut rerender = true;
+                let texture = textures
+                    .textures
+                    .entry((camera_entity, grid_entity, cascade))
+                    .and_modify(|texture| {
+                        if !texture.matches((width, height), format, usage, samples) {
+                            return;
+                        }
+                        let requested = texture.update.requests != update.requests;
+                        rerender = match shadow.update_mode {
+                            GridShadowUpdateMode::EveryFrame => true,
+                            GridShadowUpdateMode::OnChange => {
+                                requested
+                                    || texture.update.changes != update.changes
+                                    || texture.intersect != frustum_intersect
+                            }
+                            GridShadowUpdateMode::Manual => requested,
+                        };
+                    })
+                    .or_insert_with(|| {
+                        create_shadow_texture(&render_device, width, height, format, usage, samples)
+                    });
+                // Resized or reformatted textures start over, the pipeline key follows the format.
+                if !texture.matches((width, height), format, usage, samples) {
+                    *texture = create_shadow_texture(
+                        &render_device,
+                        width,
+                        height,
+                        format,
+                        usage,
+                        samples,
+                    );
+                }
+                texture.used = true;
+                // Render settings like the border change how every shadow is drawn.
+                rerender |= settings.is_changed() || texture.incomplete;
+                if rerender {
+                    texture.intersect = frustum_intersect;
+                    texture.update = update;
+                }
+                // The shadow keeps matching the area it was rendered for until the next render.
+                let frustum_intersect = texture.intersect;
+                // The multisampled target comes on top of the resolved texture.
+                let samples_stored = if samples > 1 { samples + 1 } else { 1 };
+                texture_bytes += u64::from(width)
+                    * u64::from(height)
+                    * u64::from(format.block_copy_size(None).unwrap_or(1))
+                    * u64::from(samples_stored);
+
+                let (projection, view_transform) =
+                    shadow_projection(&frustum_intersect, &grid.transform, &shadow);
+                // Slides casters across the grid to where the light drops their shadow, keeping their
+                // height so the clipping range still applies. Points on the grid stay put, so the
+                // grid samples the texture as it would for a straight down projection.
+                let view_projection = shear.filter(|shear| shear.0 != Vec3::ZERO).map(|shear| {
+                    let normal = grid.transform.up();
+                    let shear_matrix = Mat4::from_cols(
+                        (Vec3::X + shear.0 * normal.x).extend(0.),
+                        (Vec3::Y + shear.0 * normal.y).extend(0.),
+                        (Vec3::Z + shear.0 * normal.z).extend(0.),
+                        (-shear.0 * normal.dot(grid.transform.translation())).extend(1.),
+                    );
+                    projection.get_projection_matrix()
+                        * view_transform.compute_matrix().inverse()
+                        * shear_matrix
+                });
+
+                let view = commands
+                    .spawn((
+                        ExtractedView {
+                            projection: projection.get_projection_matrix(),
+                            transform: view_transform,
+                            view_projection,
+                            hdr: false,
+                            viewport: UVec4::new(0, 0, width, height),
+                            color_grading: Default::default(),
+                        },
+                        GridShadowView {
+                            texture_view: texture.view.clone(),
+                            msaa_view: texture.msaa.as_ref().map(|(_, view)| view.clone()),
+                            grid: grid_entity,
+                            camera: camera_entity,
+                            cascade,
+                            near_cascade: shadow_view,
+                            format,
+                            samples,
+                            rerender,
+                            // Decided once the phase is queued, when rendering again.
+                            empty: texture.empty,
+                            scissor: (settings.shadow_border && width > 2 && height > 2)
+                                .then(|| UVec4::new(1, 1, width - 2, height - 2)),
+                        },
+                        frustum_intersect,
+                        RenderPhase::<GridShadow>::default(),
+                    ))
+                    .id();
+                shadow_view = Some(view);
+            }
+            let Some(shadow_view) = shadow_view else {
+                continue;
+            };
+            view_shadows.shadows.insert(grid_entity, shadow_view);
+            drawn.push((grid, shadow_view));
+        }
+        commands.entity(camera_entity).insert(view_shadows);
+    }
+    textures.textures.retain(|_, texture| texture.used);
+    stats.set_shadow_texture_bytes(texture_bytes);
+}
+
+// The orthographic view a grid's shadow is rendered with, looking down the grid's normal at the
+// area from `projection_height` above it, clipped to `near..far` below that.
+fn shadow_projection(
+    intersect: &GridFrustumIntersect,
+    grid: &GlobalTransform,
+    shadow: &GridShadowSettings,
+) -> (OrthographicProjection, GlobalTransform) {
+    let projection = OrthographicProjection {
+        area: Rect::new(
+            // left, bottom, right, top
+            intersect.width / -2.,
+            intersect.height / -2.,
+            intersect.width / 2.,
+            intersect.height / 2.,
+        ),
+        near: shadow.near,
+        far: shadow.far,
+        ..Default::default()
+    };
+    let transform =
+        Transform::from_translation(intersect.center + grid.up() * shadow.projection_height)
+            .looking_at(intersect.center, intersect.up_dir);
+    (projection, transform.into())
+}
+
+fn shadow_texture_format(shadow: &GridShadowSettings) -> TextureFormat {
+    if contact_hardening(shadow) {
+        return TextureFormat::Rg16Float;
+    }
+    match shadow.format {
+        GridShadowFormat::R8Unorm => TextureFormat::R8Unorm,
+        GridShadowFormat::R16Float => TextureFormat::R16Float,
+        GridShadowFormat::Rg16Float => TextureFormat::Rg16Float,
+    }
+}
+
+// What the device can do with `format`.
+fn shadow_format_features(
+    render_device: &RenderDevice,
+    render_adapter: &RenderAdapter,
+    format: TextureFormat,
+) -> TextureFormatFeatures {
+    let features = render_device.features();
+    // Same rule as wgpu: adapter specific features need the feature enabled on the device.
+    if features.contains(WgpuFeatures::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES) {
+        render_adapter.get_texture_format_features(format)
+    } else {
+        format.guaranteed_format_features(features)
+    }
+}
+
+// The usages the shadow pass and grid need, plus the extra ones the device supports for `format`.
+fn shadow_texture_usage(
+    render_device: &RenderDevice,
+    render_adapter: &RenderAdapter,
+    format: TextureFormat,
+    extra: TextureUsages,
+    warned: &mut bool,
+) -> TextureUsages {
+    let allowed = shadow_format_features(render_device, render_adapter, format).allowed_usages;
+    if !allowed.contains(extra) && !*warned {
+        warn!(
+            "Grid shadow texture format {format:?} doesn't support usages {:?}, ignoring them",
+            extra - allowed
+        );
+        *warned = true;
+    }
+    TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING | (extra & allowed)
+}
+
+// `requested` samples if the device can multisample `format` with that many, otherwise 1.
+fn shadow_sample_count(
+    render_device: &RenderDevice,
+    render_adapter: &RenderAdapter,
+    format: TextureFormat,
+    requested: u32,
+    warned: &mut bool,
+) -> u32 {
+    if requested <= 1 {
+        return 1;
+    }
+    let flags = shadow_format_features(render_device, render_adapter, format).flags;
+    if requested != 4 || !flags.sample_count_supported(requested) {
+        if !*warned {
+            warn!(
+                "Grid shadow texture format {format:?} doesn't support {requested} samples, \
+                 rendering shadows without MSAA"
+            );
+            *warned = true;
+        }
+        return 1;
+    }
+    requested
+}
+
+fn create_shadow_texture(
+    render_device: &RenderDevice,
+    width: u32,
+    height: u32,
+    format: TextureFormat,
+    usage: TextureUsages,
+    samples: u32,
+) -> GridShadowTexture {
+    let descriptor = TextureDescriptor {
+        label: Some("grid_shadow_texture"),
+        size: Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format,
+        usage,
+        view_formats: &[],
+    };
+    let texture = render_device.create_texture(&descriptor);
+    let msaa = (samples > 1).then(|| {
+        let texture = render_device.create_texture(&TextureDescriptor {
+            label: Some("grid_shadow_msaa_texture"),
+            sample_count: samples,
+            usage: TextureUsages::RENDER_ATTACHMENT,
+            ..descriptor
+        });
+        let view = texture.create_view(&Default::default());
+        (texture, view)
+    });
+    GridShadowTexture {
+        view: texture.create_view(&Default::default()),
+        _texture: texture,
+        msaa,
+        size: (width, height),
+        format,
+        usage,
+        samples,
+        intersect: GridFrustumIntersect::default(),
+        update: GridShadowUpdate::default(),
+        used: true,
+        empty: true,
+        incomplete: false,
     }
 }
 
@@ -365,83 +1300,190 @@ fn prepare_grid_shadow_view_bind_group(
     shadow_pipeline: Res<GridShadowPipeline>,
     mut meta: ResMut<GridShadowMeta>,
     view_uniforms: Res<ViewUniforms>,
+    pass_uniforms: Res<GridShadowPassUniforms>,
+    caster_uniforms: Res<GridShadowCasterUniforms>,
 ) {
-    if let Some(view_binding) = view_uniforms.uniforms.binding() {
+    if let (Some(view_binding), Some(pass_binding), Some(caster_binding)) = (
+        view_uniforms.uniforms.binding(),
+        pass_uniforms.uniforms.binding(),
+        caster_uniforms.uniforms.binding(),
+    ) {
+        // Uniform buffers are only reallocated when they grow.
+        let buffers = [
+            view_uniforms.uniforms.buffer(),
+            pass_uniforms.uniforms.buffer(),
+            caster_uniforms.uniforms.buffer(),
+        ]
+        .map(|buffer| buffer.map(|buffer| buffer.id()));
+        if meta.view_bind_group.is_some() && meta.view_bind_group_buffers == Some(buffers) {
+            return;
+        }
+        meta.view_bind_group_buffers = Some(buffers);
         meta.view_bind_group = Some(render_device.create_bind_group(
             "grid_shadow_view_bind_group",
             &shadow_pipeline.view_layout,
-            &BindGroupEntries::single(view_binding),
+            &BindGroupEntries::sequential((view_binding, pass_binding, caster_binding)),
         ));
     }
 }
 
+/// Shadow uniform, texture and sampler of a shadow view, in the render world. Laid out as
+/// [`InfiniteGridPipeline::grid_shadows_layout`].
 #[derive(Component)]
 pub struct GridShadowBindGroup {
     bind_group: BindGroup,
 }
 
+impl GridShadowBindGroup {
+    pub fn bind_group(&self) -> &BindGroup {
+        &self.bind_group
+    }
+}
+
+/// Grid shadow bind groups by what they bind: uniform buffer, texture, sampler and cascade
+/// texture. Shadow views are spawned anew every frame, but what they bind rarely changes.
+#[derive(Resource, Default)]
+struct GridShadowBindGroupCache {
+    bind_groups: HashMap<(BufferId, TextureViewId, SamplerId, TextureViewId), BindGroup>,
+}
+
+#[allow(clippy::too_many_arguments)]
 fn prepare_grid_shadow_bind_groups(
     mut commands: Commands,
-    grids: Query<(Entity, &GridShadowView)>,
+    shadow_views: Query<(Entity, &GridShadowView)>,
     uniforms: Res<GridShadowUniforms>,
     infinite_grid_pipeline: Res<InfiniteGridPipeline>,
     grid_shadow_pipeline: Res<GridShadowPipeline>,
     render_device: Res<RenderDevice>,
+    mut cache: ResMut<GridShadowBindGroupCache>,
+    stats: Res<GridRenderStats>,
 ) {
-    if let Some(uniform_binding) = uniforms.uniforms.binding() {
-        for (entity, shadow_view) in grids.iter() {
-            let bind_group = render_device.create_bind_group(
-                "grid-shadow-bind-group",
-                &infinite_grid_pipeline.grid_shadows_layout,
-                &BindGroupEntries::sequential((
-                    uniform_binding.clone(),
-                    &shadow_view.texture_view,
-                    &grid_shadow_pipeline.sampler,
-                )),
+    // Bind groups not reused this frame are dropped.
+    let mut previous = std::mem::take(&mut cache.bind_groups);
+    if let (Some(uniform_binding), Some(uniform_buffer)) =
+        (uniforms.uniforms.binding(), uniforms.uniforms.buffer())
+    {
+        for (entity, shadow_view) in shadow_views.iter() {
+            let texture_view = |shadow_view: &GridShadowView| {
+                if shadow_view.empty {
+                    grid_shadow_pipeline.empty_texture_view.clone()
+                } else {
+                    shadow_view.texture_view.clone()
+                }
+            };
+            let cascade_texture_view = shadow_view
+                .near_cascade
+                .and_then(|near_cascade| shadow_views.get(near_cascade).ok())
+                .map_or_else(
+                    || grid_shadow_pipeline.empty_texture_view.clone(),
+                    |(_, near_cascade)| texture_view(near_cascade),
+                );
+            let texture_view = texture_view(shadow_view);
+            let key = (
+                uniform_buffer.id(),
+                texture_view.id(),
+                grid_shadow_pipeline.sampler.id(),
+                cascade_texture_view.id(),
             );
+            let bind_group = match previous.remove(&key) {
+                Some(bind_group) => bind_group,
+                None => cache.bind_groups.get(&key).cloned().unwrap_or_else(|| {
+                    render_device.create_bind_group(
+                        "grid-shadow-bind-group",
+                        &infinite_grid_pipeline.grid_shadows_layout,
+                        &BindGroupEntries::sequential((
+                            uniform_binding.clone(),
+                            &texture_view,
+                            &grid_shadow_pipeline.sampler,
+                            &cascade_texture_view,
+                        )),
+                    )
+                }),
+            };
+            cache.bind_groups.insert(key, bind_group.clone());
             commands
                 .entity(entity)
                 .insert(GridShadowBindGroup { bind_group });
         }
     }
+    stats.set_shadow_bind_groups(cache.bind_groups.len() as u64);
 }
 
 #[allow(clippy::too_many_arguments)]
 fn queue_grid_shadows(
-    mut grids: Query<(&mut RenderPhase<GridShadow>, &VisibleEntities)>,
+    mut shadow_views: Query<(&mut RenderPhase<GridShadow>, &mut GridShadowView)>,
+    mut textures: ResMut<GridShadowTextures>,
+    grids: Query<&VisibleEntities, With<ExtractedInfiniteGrid>>,
     render_meshes: Res<RenderAssets<Mesh>>,
     render_mesh_instances: Res<RenderMeshInstances>,
     render_materials: Res<RenderMaterials<StandardMaterial>>,
     render_material_instances: Res<RenderMaterialInstances<StandardMaterial>>,
+    grid_only_casters: Query<(), With<GridShadowCasterOnly>>,
+    material_free_casters: Query<(), With<GridShadowCaster>>,
     mut pipelines: ResMut<SpecializedMeshPipelines<GridShadowPipeline>>,
     pipeline_cache: Res<PipelineCache>,
     shadow_pipeline: Res<GridShadowPipeline>,
     shadow_draw_functions: Res<DrawFunctions<GridShadow>>,
+    stats: Res<GridRenderStats>,
+    mut warned_draw_functions: Local<bool>,
 ) {
-    let draw_shadow_mesh = shadow_draw_functions
-        .read()
-        .get_id::<DrawGridShadowMesh>()
-        .unwrap();
-    for (mut phase, entities) in grids.iter_mut() {
-        for &entity in &entities.entities {
-            if let (Some(mesh_instance), Some(material_asset_id)) = (
-                render_mesh_instances.get(&entity),
-                render_material_instances.get(&entity),
-            ) {
-                if !mesh_instance.shadow_caster {
+    let start = Instant::now();
+    let mut casters = 0;
+    let mut casters_per_grid = 0;
+    let draw_functions = shadow_draw_functions.read();
+    let (Some(draw_shadow_mesh), Some(draw_shadow_masked_mesh)) = (
+        draw_functions.get_id::<DrawGridShadowMesh>(),
+        draw_functions.get_id::<DrawGridShadowMaskedMesh>(),
+    ) else {
+        if !*warned_draw_functions {
+            warn!("Grid shadow draw functions aren't registered, skipping grid shadows");
+            *warned_draw_functions = true;
+        }
+        return;
+    };
+    let mut passes = 0;
+    for (mut phase, mut shadow_view) in shadow_views.iter_mut() {
+        if !shadow_view.rerender {
+            continue;
+        }
+        let entities = grids
+            .get(shadow_view.grid)
+            .map_or(&[][..], |visible| &visible.entities[..]);
+        let mut incomplete = false;
+        for &entity in entities {
+            if let Some(mesh_instance) = render_mesh_instances.get(&entity) {
+                // `NotGridShadowCaster` meshes never reach the grid's visible entities.
+                if !mesh_instance.shadow_caster && !grid_only_casters.contains(entity) {
                     continue;
                 }
 
-                if let (Some(mesh), Some(material)) = (
-                    render_meshes.get(mesh_instance.mesh_asset_id),
-                    render_materials.get(material_asset_id),
-                ) {
-                    let key = MaterialPipelineKey {
-                        mesh_key: MeshPipelineKey::from_primitive_topology(mesh.primitive_topology),
-                        bind_group_data: material.key.clone(),
-                    };
-                    let pipeline_id =
-                        pipelines.specialize(&pipeline_cache, &shadow_pipeline, key, &mesh.layout);
+                if let Some(mesh) = render_meshes.get(mesh_instance.mesh_asset_id) {
+                    let mut mesh_key =
+                        MeshPipelineKey::from_primitive_topology(mesh.primitive_topology);
+                    // Must match the mesh bind group `SetMeshBindGroup` picks for the entity.
+                    if mesh.morph_targets.is_some() {
+                        mesh_key |= MeshPipelineKey::MORPH_TARGETS;
+                    }
+                    // Only masked casters pay for binding and sampling their material.
+                    let alpha_mask = !material_free_casters.contains(entity)
+                        && mesh.layout.contains(Mesh::ATTRIBUTE_UV_0)
+                        && render_material_instances
+                            .get(&entity)
+                            .and_then(|material| render_materials.get(material))
+                            .is_some_and(|material| {
+                                matches!(material.properties.alpha_mode, AlphaMode::Mask(_))
+                            });
+                    let pipeline_id = pipelines.specialize(
+                        &pipeline_cache,
+                        &shadow_pipeline,
+                        GridShadowPipelineKey {
+                            mesh_key,
+                            alpha_mask,
+                            format: shadow_view.format,
+                            samples: shadow_view.samples,
+                        },
+                        &mesh.layout,
+                    );
 
                     let pipeline_id = match pipeline_id {
                         Ok(id) => id,
@@ -450,36 +1492,105 @@ fn queue_grid_shadows(
                             continue;
                         }
                     };
+                    // Pipelines compile in the background, for the first frames and after the
+                    // cache is reset. Their casters wait for the next render of the texture.
+                    // Pipelines queued this frame aren't in the cache until it processes them.
+                    if !pipeline_cache
+                        .pipelines()
+                        .nth(pipeline_id.id())
+                        .is_some_and(|pipeline| {
+                            matches!(pipeline.state, CachedPipelineState::Ok(_))
+                        })
+                    {
+                        incomplete = true;
+                        continue;
+                    }
 
                     phase.add(GridShadow {
-                        draw_function: draw_shadow_mesh,
+                        draw_function: if alpha_mask {
+                            draw_shadow_masked_mesh
+                        } else {
+                            draw_shadow_mesh
+                        },
                         pipeline: pipeline_id,
                         entity,
+                        asset_id: mesh_instance.mesh_asset_id,
                         batch_range: 0..1,
                         dynamic_offset: None,
                     });
+                    casters += 1;
                 }
             }
         }
+        casters_per_grid = casters_per_grid.max(phase.items.len() as u64);
+        // Nothing to draw, so skip the pass and its clear, and let the grid sample no shadow.
+        shadow_view.empty = phase.items.is_empty();
+        if let Some(texture) =
+            textures
+                .textures
+                .get_mut(&(shadow_view.camera, shadow_view.grid, shadow_view.cascade))
+        {
+            texture.empty = shadow_view.empty;
+            texture.incomplete = incomplete;
+        }
+        if !shadow_view.empty {
+            passes += 1;
+        }
     }
+    stats.set_shadow_casters(casters);
+    stats.set_shadow_passes(passes);
+    stats.set_shadow_casters_per_grid(casters_per_grid);
+    stats.set_shadow_queue_cpu_time(start.elapsed());
+}
+
+/// When batching the shadow phases started, for the diagnostics.
+#[derive(Resource, Default)]
+struct GridShadowBatchStart(Option<Instant>);
+
+fn start_grid_shadow_batch(mut batch_start: ResMut<GridShadowBatchStart>) {
+    batch_start.0 = Some(Instant::now());
+}
+
+fn end_grid_shadow_batch(
+    mut batch_start: ResMut<GridShadowBatchStart>,
+    shadow_phases: Query<&RenderPhase<GridShadow>>,
+    stats: Res<GridRenderStats>,
+) {
+    if let Some(start) = batch_start.0.take() {
+        stats.set_shadow_batch_cpu_time(start.elapsed());
+    }
+    // Walks the phases like `RenderPhase::render` does, a batch is drawn once from its first item.
+    let mut draws = 0;
+    for phase in shadow_phases.iter() {
+        let mut index = 0;
+        while let Some(item) = phase.items.get(index) {
+            let batch = item.batch_range().len();
+            draws += (batch > 0) as u64;
+            index += batch.max(1);
+        }
+    }
+    stats.set_shadow_draws(draws);
 }
 
 pub struct SetGridShadowBindGroup<const I: usize>;
 
 impl<const I: usize, P: PhaseItem> RenderCommand<P> for SetGridShadowBindGroup<I> {
-    type Param = ();
-    type ViewQuery = ();
-    type ItemQuery = Option<(Read<GridShadowBindGroup>, Read<GridShadowUniformOffset>)>;
+    type Param = SQuery<(Read<GridShadowBindGroup>, Read<GridShadowUniformOffset>)>;
+    type ViewQuery = Option<Read<ViewGridShadows>>;
+    type ItemQuery = ();
 
     #[inline]
     fn render<'w>(
-        _item: &P,
-        _view: ROQueryItem<'w, Self::ViewQuery>,
-        bg_offset: ROQueryItem<'w, Option<Self::ItemQuery>>,
-        _query: SystemParamItem<'w, '_, Self::Param>,
+        item: &P,
+        view_shadows: ROQueryItem<'w, Self::ViewQuery>,
+        _entity: ROQueryItem<'w, Option<Self::ItemQuery>>,
+        shadow_views: SystemParamItem<'w, '_, Self::Param>,
         pass: &mut bevy::render::render_phase::TrackedRenderPass<'w>,
     ) -> RenderCommandResult {
-        if let Some(Some((bg, offset))) = bg_offset {
+        if let Some((bg, offset)) = view_shadows
+            .and_then(|view_shadows| view_shadows.shadows.get(&item.entity()))
+            .and_then(|&shadow_view| shadow_views.get_inner(shadow_view).ok())
+        {
             pass.set_bind_group(I, &bg.bind_group, &[offset.offset]);
         }
         RenderCommandResult::Success
@@ -488,9 +1599,9 @@ impl<const I: usize, P: PhaseItem> RenderCommand<P> for SetGridShadowBindGroup<I
 
 #[allow(clippy::type_complexity)]
 struct GridShadowPassNode {
-    grids: Vec<Entity>,
-    grid_entity_query: QueryState<Entity, With<GridShadowView>>,
-    grid_element_query: QueryState<(
+    shadow_views: Vec<(Entity, Entity)>,
+    shadow_view_query: QueryState<(Entity, Read<GridShadowView>)>,
+    shadow_element_query: QueryState<(
         Read<GridShadowView>,
         Read<RenderPhase<GridShadow>>,
         Read<ViewUniformOffset>,
@@ -498,50 +1609,79 @@ struct GridShadowPassNode {
 }
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
-struct GridShadowPassLabel;
+pub(super) struct GridShadowPassLabel;
 
 impl GridShadowPassNode {
     fn new(world: &mut World) -> Self {
         Self {
-            grids: Vec::new(),
-            grid_entity_query: world.query_filtered(),
-            grid_element_query: world.query(),
+            shadow_views: Vec::new(),
+            shadow_view_query: world.query(),
+            shadow_element_query: world.query(),
         }
     }
 }
 
 impl Node for GridShadowPassNode {
     fn update(&mut self, world: &mut World) {
-        self.grids.clear();
-        self.grids.extend(self.grid_entity_query.iter(world));
-        self.grid_element_query.update_archetypes(world);
+        self.shadow_views.clear();
+        self.shadow_views.extend(
+            self.shadow_view_query
+                .iter(world)
+                .filter(|(_, shadow_view)| shadow_view.rerender && !shadow_view.empty)
+                .map(|(entity, shadow_view)| (entity, shadow_view.camera)),
+        );
+        self.shadow_element_query.update_archetypes(world);
     }
 
     fn run(
         &self,
-        _graph: &mut bevy::render::render_graph::RenderGraphContext,
+        graph: &mut bevy::render::render_graph::RenderGraphContext,
         render_context: &mut bevy::render::renderer::RenderContext,
         world: &World,
     ) -> Result<(), bevy::render::render_graph::NodeRunError> {
-        for &entity in &self.grids {
-            let (shadow_view, render_phase, _) =
-                self.grid_element_query.get_manual(world, entity).unwrap();
+        // The node runs once per camera, so only draw the shadows of the current one.
+        let view_entity = graph.view_entity();
+        for &(entity, camera) in &self.shadow_views {
+            if camera != view_entity {
+                continue;
+            }
+            // A grid despawned mid-frame can leave its shadow view without a phase or uniform.
+            let Ok((shadow_view, render_phase, _)) =
+                self.shadow_element_query.get_manual(world, entity)
+            else {
+                continue;
+            };
+            // With MSAA, casters are drawn into the multisampled target and only the resolved
+            // texture is kept.
+            let (view, resolve_target, store) = match &shadow_view.msaa_view {
+                Some(msaa_view) => (
+                    &**msaa_view,
+                    Some(&*shadow_view.texture_view),
+                    StoreOp::Discard,
+                ),
+                None => (&*shadow_view.texture_view, None, StoreOp::Store),
+            };
             let pass_descriptor = RenderPassDescriptor {
                 label: Some("grid_shadow_pass"),
                 color_attachments: &[Some(RenderPassColorAttachment {
-                    view: &shadow_view.texture_view,
-                    resolve_target: None,
+                    view,
+                    resolve_target,
                     ops: Operations {
                         load: LoadOp::Clear(Color::BLACK.into()),
-                        store: StoreOp::Store,
+                        store,
                     },
                 })],
                 depth_stencil_attachment: None,
-                timestamp_writes: None,
+                timestamp_writes: world
+                    .get_resource::<GridShadowGpuTimer>()
+                    .and_then(GridShadowGpuTimer::timestamp_writes),
                 occlusion_query_set: None,
             };
 
             let mut tracked_render_pass = render_context.begin_tracked_render_pass(pass_descriptor);
+            if let Some(scissor) = shadow_view.scissor {
+                tracked_render_pass.set_scissor_rect(scissor.x, scissor.y, scissor.z, scissor.w);
+            }
             render_phase.render(&mut tracked_render_pass, world, entity);
         }
 
@@ -549,19 +1689,75 @@ impl Node for GridShadowPassNode {
     }
 }
 
-#[derive(Resource, Clone)]
+#[derive(Resource, Clone, Reflect)]
+#[reflect(Default)]
 pub struct RenderSettings {
+    /// Longest side of the shadow textures. Larger values than the device supports are clamped.
+    /// Cameras with small viewports, e.g. rendering to a small `Image`, get smaller textures, in
+    /// proportion to their viewport's longer side.
     pub max_texture_size: u32,
+    pub shadow_filter: ShadowFilter,
+    /// Rasterize shadow casters conservatively, so thin meshes like wires and poles can't fall
+    /// between texels of the shadow texture. Ignored when the device doesn't support it.
+    pub conservative_rasterization: bool,
+    /// Usages added to the shadow textures, e.g. `STORAGE_BINDING` to process them in a compute
+    /// pass. Usages the device doesn't support for the texture format are dropped with a warning.
+    #[reflect(ignore)]
+    pub shadow_texture_extra_usages: TextureUsages,
+    /// Keeps a one texel border of the shadow textures clear. Filter taps past the edge of a
+    /// shadow texture read its border, so without it casters at the edge of the shadowed area
+    /// smear their shadow beyond it.
+    pub shadow_border: bool,
+    /// Samples per texel of the shadow pass, 1 or 4. With 4, shadow edges are antialiased at the
+    /// cost of a multisampled copy of each shadow texture. Falls back to 1 when the device can't
+    /// multisample the shadow texture format.
+    pub shadow_msaa_samples: u32,
+    /// Scales the shadowed area around its center, e.g. 1.1 for a tenth more on each axis, so
+    /// shadows of casters at the edge of the view don't pop in and out as the camera moves. The
+    /// texture covers more ground at the same resolution. Values below 1 are treated as 1.
+    pub shadow_area_padding: f32,
+}
+
+impl RenderSettings {
+    /// The default settings with `max_texture_size` lowered to what the device supports.
+    pub fn from_device(render_device: &RenderDevice) -> Self {
+        let default = Self::default();
+        Self {
+            max_texture_size: default
+                .max_texture_size
+                .min(render_device.limits().max_texture_dimension_2d),
+            ..default
+        }
+    }
 }
 
 impl Default for RenderSettings {
     fn default() -> Self {
         Self {
             max_texture_size: 16384,
+            shadow_filter: ShadowFilter::default(),
+            conservative_rasterization: false,
+            shadow_texture_extra_usages: TextureUsages::empty(),
+            shadow_border: true,
+            shadow_msaa_samples: 1,
+            shadow_area_padding: 1.,
         }
     }
 }
 
+/// How the grid samples its shadow texture.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Default)]
+pub enum ShadowFilter {
+    /// Smooth shadow edges.
+    #[default]
+    Linear,
+    /// Blocky, pixel crisp shadow edges.
+    Nearest,
+    /// Linear filtering that stays sharp at grazing angles. The clamp is limited to 1..=16.
+    Anisotropic(u16),
+}
+
 pub fn register_shadow(app: &mut App) {
     app.world
         .resource_mut::<Assets<Shader>>()
@@ -569,26 +1765,50 @@ pub fn register_shadow(app: &mut App) {
             Shader::from_wgsl(SHADOW_RENDER, file!())
         });
 
-    let render_settings = app
-        .world
-        .resource::<GlobalInfiniteGridSettings>()
-        .render_settings
-        .clone();
+    let global_settings = app.world.resource::<GlobalInfiniteGridSettings>();
+    let render_settings = global_settings.render_settings.clone();
+    let quality = ExtractedGridQuality(global_settings.quality);
 
     let render_app = app.get_sub_app_mut(RenderApp).unwrap();
     render_app
+        .insert_resource(render_settings)
+        .insert_resource(quality)
+        .init_resource::<GridShadowUniforms>()
+        .init_resource::<GridShadowPassUniforms>()
+        .init_resource::<GridShadowCasterUniforms>()
         .init_resource::<GridShadowMeta>()
+        .init_resource::<GridShadowTextures>()
+        .init_resource::<GridShadowBatchStart>()
+        .init_resource::<GridShadowBindGroupCache>()
         .init_resource::<GridShadowPipeline>()
         .init_resource::<DrawFunctions<GridShadow>>()
         .init_resource::<SpecializedMeshPipelines<GridShadowPipeline>>()
-        .insert_resource(render_settings)
         .add_render_command::<GridShadow, DrawGridShadowMesh>()
+        .add_render_command::<GridShadow, DrawGridShadowMaskedMesh>()
+        .add_systems(
+            ExtractSchedule,
+            (
+                extract_grid_shadow_cameras,
+                extract_grid_shadow_caster_only,
+                extract_material_free_casters,
+                extract_grid_shadow_opacities,
+                extract_grid_shadow_updates,
+                extract_grid_shadow_shears,
+                extract_render_settings,
+            ),
+        )
+        .add_systems(
+            Render,
+            (prepare_grid_shadows, prepare_grid_shadow_casters).in_set(RenderSet::PrepareResources),
+        )
         .add_systems(
             Render,
-            (prepare_grid_shadow_views, apply_deferred)
-                .chain()
-                .before(prepare_view_uniforms)
-                .in_set(RenderSet::Prepare),
+            update_shadow_pipeline.in_set(RenderSet::PrepareResources),
+        )
+        .add_systems(
+            Render,
+            // Shadow views are spawned before queueing so their phases can be filled.
+            prepare_grid_shadow_views.in_set(RenderSet::ManageViews),
         )
         .add_systems(
             Render,
@@ -601,16 +1821,63 @@ pub fn register_shadow(app: &mut App) {
         .add_systems(
             Render,
             (
-                queue_grid_shadows,
-                batch_and_prepare_render_phase::<GridShadow, MeshPipeline>,
-            )
-                .chain()
-                .in_set(RenderSet::Queue),
+                queue_grid_shadows.in_set(RenderSet::Queue),
+                sort_phase_system::<GridShadow>.in_set(RenderSet::PhaseSort),
+                // Batching compares the material bind groups set while queueing the materials,
+                // so it waits for the queue like Bevy's own phases do.
+                (
+                    start_grid_shadow_batch,
+                    batch_and_prepare_render_phase::<GridShadow, MeshPipeline>,
+                    end_grid_shadow_batch,
+                )
+                    .chain()
+                    .in_set(RenderSet::PrepareResources),
+            ),
         );
 
     let grid_shadow_pass_node = GridShadowPassNode::new(&mut render_app.world);
     let mut graph = render_app.world.resource_mut::<RenderGraph>();
     let draw_3d_graph = graph.get_sub_graph_mut(Core3d).unwrap();
     draw_3d_graph.add_node(GridShadowPassLabel, grid_shadow_pass_node);
-    draw_3d_graph.add_node_edge(GridShadowPassLabel, Node3d::EndMainPass);
+    draw_3d_graph.add_node_edge(GridShadowPassLabel, Node3d::StartMainPass);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tall_caster_fits_the_projection() {
+        let grid = GlobalTransform::IDENTITY;
+        let intersect = GridFrustumIntersect {
+            center: Vec3::ZERO,
+            up_dir: grid.forward(),
+            width: 20.,
+            height: 20.,
+            ..default()
+        };
+        // The corners of a 1000 unit tall tower standing on the grid.
+        let tower: Vec<_> = [0., 1000.]
+            .into_iter()
+            .flat_map(|y| {
+                [(-1., -1.), (-1., 1.), (1., -1.), (1., 1.)].map(|(x, z)| Vec3::new(x, y, z))
+            })
+            .collect();
+        let inside = |shadow: &GridShadowSettings| {
+            let (projection, view) = shadow_projection(&intersect, &grid, shadow);
+            let clip = projection.get_projection_matrix() * view.compute_matrix().inverse();
+            tower.iter().all(|&corner| {
+                let ndc = clip.project_point3(corner);
+                ndc.x.abs() <= 1. && ndc.y.abs() <= 1. && (0. ..=1.).contains(&ndc.z)
+            })
+        };
+
+        // The default projection starts 500 units above the grid, so the tower's top is clipped.
+        assert!(!inside(&GridShadowSettings::default()));
+        assert!(inside(&GridShadowSettings {
+            projection_height: 1100.,
+            far: 1200.,
+            ..default()
+        }));
+    }
 }
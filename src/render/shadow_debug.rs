@@ -0,0 +1,253 @@
+use bevy::{
+    core_pipeline::{
+        core_3d::graph::{Core3d, Node3d},
+        fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    },
+    prelude::*,
+    render::{
+        render_asset::RenderAssets,
+        render_graph::{Node, RenderGraph, RenderGraphContext, RenderLabel},
+        render_resource::{
+            BindGroup, BindGroupEntries, BindGroupLayout, BindGroupLayoutEntry, BindingType,
+            CachedRenderPipelineId, ColorTargetState, ColorWrites, FilterMode, FragmentState,
+            LoadOp, MultisampleState, Operations, PipelineCache, PrimitiveState,
+            RenderPassColorAttachment, RenderPassDescriptor, RenderPipelineDescriptor, Sampler,
+            SamplerBindingType, SamplerDescriptor, ShaderStages, SpecializedRenderPipeline,
+            SpecializedRenderPipelines, StoreOp, TextureFormat, TextureSampleType, TextureUsages,
+            TextureView, TextureViewDimension,
+        },
+        renderer::{RenderContext, RenderDevice},
+        Extract, ExtractSchedule, Render, RenderApp, RenderSet,
+    },
+};
+
+use crate::DebugGridShadowTexture;
+
+use super::shadow::{GridShadowPassLabel, GridShadowPipeline, GridShadowView, ViewGridShadows};
+
+static SHADOW_DEBUG: &str = include_str!("shadow_debug.wgsl");
+
+const SHADOW_DEBUG_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(3318470725316093841);
+
+#[derive(Resource)]
+struct GridShadowDebugPipeline {
+    layout: BindGroupLayout,
+    sampler: Sampler,
+}
+
+impl FromWorld for GridShadowDebugPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let layout = render_device.create_bind_group_layout(
+            "grid_shadow_debug_layout",
+            &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        );
+        let sampler = render_device.create_sampler(&SamplerDescriptor {
+            label: Some("grid_shadow_debug_sampler"),
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Self { layout, sampler }
+    }
+}
+
+impl SpecializedRenderPipeline for GridShadowDebugPipeline {
+    // Format of the debug image.
+    type Key = TextureFormat;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        RenderPipelineDescriptor {
+            label: Some("grid_shadow_debug_pipeline".into()),
+            layout: vec![self.layout.clone()],
+            push_constant_ranges: Vec::new(),
+            vertex: fullscreen_shader_vertex_state(),
+            fragment: Some(FragmentState {
+                shader: SHADOW_DEBUG_SHADER_HANDLE,
+                shader_defs: vec![],
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format: key,
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+        }
+    }
+}
+
+fn extract_debug_shadow_textures(
+    mut commands: Commands,
+    grids: Extract<Query<(Entity, &DebugGridShadowTexture)>>,
+) {
+    let extracted: Vec<_> = grids
+        .iter()
+        .map(|(entity, debug)| (entity, debug.clone()))
+        .collect();
+    commands.insert_or_spawn_batch(extracted);
+}
+
+struct GridShadowDebugBlit {
+    camera: Entity,
+    target: TextureView,
+    bind_group: BindGroup,
+    pipeline: CachedRenderPipelineId,
+}
+
+#[derive(Resource, Default)]
+struct GridShadowDebugBlits {
+    blits: Vec<GridShadowDebugBlit>,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn prepare_debug_shadow_blits(
+    grids: Query<(Entity, &DebugGridShadowTexture)>,
+    cameras: Query<(Entity, &ViewGridShadows)>,
+    shadow_views: Query<&GridShadowView>,
+    images: Res<RenderAssets<Image>>,
+    render_device: Res<RenderDevice>,
+    pipeline: Res<GridShadowDebugPipeline>,
+    shadow_pipeline: Res<GridShadowPipeline>,
+    pipeline_cache: Res<PipelineCache>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<GridShadowDebugPipeline>>,
+    mut blits: ResMut<GridShadowDebugBlits>,
+    mut warned_usage: Local<bool>,
+) {
+    blits.blits.clear();
+    for (grid, debug) in grids.iter() {
+        // The image isn't uploaded yet in the first frames.
+        let Some(image) = images.get(&debug.image) else {
+            continue;
+        };
+        if !image
+            .texture
+            .usage()
+            .contains(TextureUsages::RENDER_ATTACHMENT)
+        {
+            if !*warned_usage {
+                warn!("DebugGridShadowTexture image needs TextureUsages::RENDER_ATTACHMENT");
+                *warned_usage = true;
+            }
+            continue;
+        }
+        let Some((camera, shadow_view)) = cameras
+            .iter()
+            .filter(|(camera, _)| debug.camera.is_none() || debug.camera == Some(*camera))
+            .find_map(|(camera, view_shadows)| {
+                let shadow_view = shadow_views.get(*view_shadows.shadows.get(&grid)?).ok()?;
+                Some((camera, shadow_view))
+            })
+        else {
+            continue;
+        };
+
+        // Like the grid, show no shadow rather than a stale one.
+        let texture_view = if shadow_view.is_empty() {
+            &shadow_pipeline.empty_texture_view
+        } else {
+            shadow_view.texture_view()
+        };
+        blits.blits.push(GridShadowDebugBlit {
+            camera,
+            target: image.texture_view.clone(),
+            bind_group: render_device.create_bind_group(
+                "grid_shadow_debug_bind_group",
+                &pipeline.layout,
+                &BindGroupEntries::sequential((texture_view, &pipeline.sampler)),
+            ),
+            pipeline: pipelines.specialize(&pipeline_cache, &pipeline, image.texture_format),
+        });
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+struct GridShadowDebugLabel;
+
+struct GridShadowDebugNode;
+
+impl Node for GridShadowDebugNode {
+    fn run(
+        &self,
+        graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), bevy::render::render_graph::NodeRunError> {
+        let pipeline_cache = world.resource::<PipelineCache>();
+        // Like the shadow pass, the node runs once per camera.
+        let view_entity = graph.view_entity();
+        for blit in &world.resource::<GridShadowDebugBlits>().blits {
+            if blit.camera != view_entity {
+                continue;
+            }
+            let Some(pipeline) = pipeline_cache.get_render_pipeline(blit.pipeline) else {
+                continue;
+            };
+            let mut pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+                label: Some("grid_shadow_debug_pass"),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: &blit.target,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: LoadOp::Load,
+                        store: StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            pass.set_render_pipeline(pipeline);
+            pass.set_bind_group(0, &blit.bind_group, &[]);
+            pass.draw(0..3, 0..1);
+        }
+
+        Ok(())
+    }
+}
+
+pub fn register_shadow_debug(app: &mut App) {
+    app.world
+        .resource_mut::<Assets<Shader>>()
+        .get_or_insert_with(SHADOW_DEBUG_SHADER_HANDLE, || {
+            Shader::from_wgsl(SHADOW_DEBUG, file!())
+        });
+
+    let render_app = app.get_sub_app_mut(RenderApp).unwrap();
+    render_app
+        .init_resource::<GridShadowDebugPipeline>()
+        .init_resource::<GridShadowDebugBlits>()
+        .init_resource::<SpecializedRenderPipelines<GridShadowDebugPipeline>>()
+        .add_systems(ExtractSchedule, extract_debug_shadow_textures)
+        .add_systems(
+            Render,
+            prepare_debug_shadow_blits.in_set(RenderSet::PrepareBindGroups),
+        );
+
+    let mut graph = render_app.world.resource_mut::<RenderGraph>();
+    let draw_3d_graph = graph.get_sub_graph_mut(Core3d).unwrap();
+    draw_3d_graph.add_node(GridShadowDebugLabel, GridShadowDebugNode);
+    // Shows the shadow once it's rendered, before the main pass samples it.
+    draw_3d_graph.add_node_edge(GridShadowPassLabel, GridShadowDebugLabel);
+    draw_3d_graph.add_node_edge(GridShadowDebugLabel, Node3d::StartMainPass);
+}
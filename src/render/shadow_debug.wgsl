@@ -0,0 +1,10 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+@group(0) @binding(0) var shadow_texture: texture_2d<f32>;
+@group(0) @binding(1) var shadow_sampler: sampler;
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let coverage = textureSample(shadow_texture, shadow_sampler, in.uv).r;
+    return vec4<f32>(vec3<f32>(coverage), 1.);
+}
@@ -0,0 +1,340 @@
+use bevy::{
+    core_pipeline::{
+        core_3d::graph::{Core3d, Node3d},
+        fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    },
+    prelude::*,
+    render::{
+        render_asset::RenderAssets,
+        render_graph::{Node, RenderGraph, RenderGraphContext, RenderLabel},
+        render_resource::{
+            BindGroup, BindGroupEntries, BindGroupLayout, BindGroupLayoutEntry, BindingType,
+            BufferBindingType, BufferSize, CachedRenderPipelineId, ColorTargetState, ColorWrites,
+            DynamicUniformBuffer, FilterMode, FragmentState, LoadOp, MultisampleState, Operations,
+            PipelineCache, PrimitiveState, RenderPassColorAttachment, RenderPassDescriptor,
+            RenderPipelineDescriptor, Sampler, SamplerBindingType, SamplerDescriptor, ShaderStages,
+            ShaderType, SpecializedRenderPipeline, SpecializedRenderPipelines, StoreOp,
+            TextureFormat, TextureSampleType, TextureUsages, TextureView, TextureViewDimension,
+        },
+        renderer::{RenderContext, RenderDevice, RenderQueue},
+        Extract, ExtractSchedule, Render, RenderApp, RenderSet,
+    },
+};
+
+use crate::{GridFrustumIntersect, GridShadowDecal};
+
+use super::{
+    shadow::{GridShadowPassLabel, GridShadowPipeline, GridShadowView, ViewGridShadows},
+    ExtractedInfiniteGrid,
+};
+
+static SHADOW_DECAL: &str = include_str!("shadow_decal.wgsl");
+static SHADOW_DECAL_MATERIAL: &str = include_str!("shadow_decal_material.wgsl");
+
+const SHADOW_DECAL_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(7604519832270161453);
+pub(crate) const SHADOW_DECAL_MATERIAL_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(11820470391352884267);
+
+/// Maps the decal's UV to the shadow texture's.
+#[derive(ShaderType)]
+struct GridShadowDecalUniform {
+    uv_x: Vec2,
+    uv_y: Vec2,
+    uv_offset: Vec2,
+}
+
+#[derive(Resource, Default)]
+struct GridShadowDecalUniforms {
+    uniforms: DynamicUniformBuffer<GridShadowDecalUniform>,
+}
+
+#[derive(Resource)]
+struct GridShadowDecalPipeline {
+    layout: BindGroupLayout,
+    sampler: Sampler,
+}
+
+impl FromWorld for GridShadowDecalPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let layout = render_device.create_bind_group_layout(
+            "grid_shadow_decal_layout",
+            &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        min_binding_size: BufferSize::new(
+                            GridShadowDecalUniform::min_size().into(),
+                        ),
+                    },
+                    count: None,
+                },
+            ],
+        );
+        let sampler = render_device.create_sampler(&SamplerDescriptor {
+            label: Some("grid_shadow_decal_sampler"),
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Self { layout, sampler }
+    }
+}
+
+impl SpecializedRenderPipeline for GridShadowDecalPipeline {
+    // Format of the decal image.
+    type Key = TextureFormat;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        RenderPipelineDescriptor {
+            label: Some("grid_shadow_decal_pipeline".into()),
+            layout: vec![self.layout.clone()],
+            push_constant_ranges: Vec::new(),
+            vertex: fullscreen_shader_vertex_state(),
+            fragment: Some(FragmentState {
+                shader: SHADOW_DECAL_SHADER_HANDLE,
+                shader_defs: vec![],
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format: key,
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+        }
+    }
+}
+
+fn extract_shadow_decals(
+    mut commands: Commands,
+    grids: Extract<Query<(Entity, &GridShadowDecal, &ViewVisibility)>>,
+) {
+    let extracted: Vec<_> = grids
+        .iter()
+        .filter(|(.., visibility)| visibility.get())
+        .map(|(entity, decal, _)| (entity, decal.clone()))
+        .collect();
+    commands.insert_or_spawn_batch(extracted);
+}
+
+/// The affine map from the decal's UV to the shadow texture's, both lying on the grid plane.
+fn decal_to_shadow_uv(
+    decal: &GridShadowDecal,
+    grid: &ExtractedInfiniteGrid,
+    intersect: &GridFrustumIntersect,
+) -> GridShadowDecalUniform {
+    let normal = grid.transform.up();
+    // As in the grid shader.
+    let collapse_matrix =
+        Mat3::from_cols(normal.cross(-intersect.up_dir), normal, -intersect.up_dir).inverse();
+    let size = Vec2::new(intersect.width, intersect.height);
+    let shadow_uv = |uv: Vec2| {
+        let grid_pos = decal.origin + uv * decal.extent;
+        let pos = grid
+            .transform
+            .transform_point(Vec3::new(grid_pos.x, 0., grid_pos.y));
+        (collapse_matrix * (pos - intersect.center)).xz() / size + 0.5
+    };
+    let uv_offset = shadow_uv(Vec2::ZERO);
+    GridShadowDecalUniform {
+        uv_x: shadow_uv(Vec2::X) - uv_offset,
+        uv_y: shadow_uv(Vec2::Y) - uv_offset,
+        uv_offset,
+    }
+}
+
+struct GridShadowDecalBlit {
+    camera: Entity,
+    target: TextureView,
+    bind_group: BindGroup,
+    offset: u32,
+    pipeline: CachedRenderPipelineId,
+}
+
+#[derive(Resource, Default)]
+struct GridShadowDecalBlits {
+    blits: Vec<GridShadowDecalBlit>,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn prepare_shadow_decal_blits(
+    grids: Query<(Entity, &GridShadowDecal, &ExtractedInfiniteGrid)>,
+    cameras: Query<(Entity, &ViewGridShadows)>,
+    shadow_views: Query<(&GridShadowView, &GridFrustumIntersect)>,
+    images: Res<RenderAssets<Image>>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    pipeline: Res<GridShadowDecalPipeline>,
+    shadow_pipeline: Res<GridShadowPipeline>,
+    pipeline_cache: Res<PipelineCache>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<GridShadowDecalPipeline>>,
+    mut uniforms: ResMut<GridShadowDecalUniforms>,
+    mut blits: ResMut<GridShadowDecalBlits>,
+    mut warned_usage: Local<bool>,
+) {
+    blits.blits.clear();
+    uniforms.uniforms.clear();
+    let mut pending = Vec::new();
+    for (grid, decal, extracted) in grids.iter() {
+        // The image isn't uploaded yet in the first frames.
+        let Some(image) = images.get(&decal.image) else {
+            continue;
+        };
+        if !image
+            .texture
+            .usage()
+            .contains(TextureUsages::RENDER_ATTACHMENT)
+        {
+            if !*warned_usage {
+                warn!("GridShadowDecal image needs TextureUsages::RENDER_ATTACHMENT");
+                *warned_usage = true;
+            }
+            continue;
+        }
+        let Some((camera, shadow_view, intersect)) = cameras
+            .iter()
+            .filter(|(camera, _)| decal.camera.is_none() || decal.camera == Some(*camera))
+            .find_map(|(camera, view_shadows)| {
+                let (shadow_view, intersect) =
+                    shadow_views.get(*view_shadows.shadows.get(&grid)?).ok()?;
+                Some((camera, shadow_view, intersect))
+            })
+        else {
+            continue;
+        };
+
+        // Like the grid, show no shadow rather than a stale one.
+        let texture_view = if shadow_view.is_empty() {
+            &shadow_pipeline.empty_texture_view
+        } else {
+            shadow_view.texture_view()
+        };
+        let offset = uniforms
+            .uniforms
+            .push(&decal_to_shadow_uv(decal, extracted, intersect));
+        pending.push((camera, image, texture_view, offset));
+    }
+    uniforms
+        .uniforms
+        .write_buffer(&render_device, &render_queue);
+
+    let Some(binding) = uniforms.uniforms.binding() else {
+        return;
+    };
+    for (camera, image, texture_view, offset) in pending {
+        blits.blits.push(GridShadowDecalBlit {
+            camera,
+            target: image.texture_view.clone(),
+            bind_group: render_device.create_bind_group(
+                "grid_shadow_decal_bind_group",
+                &pipeline.layout,
+                &BindGroupEntries::sequential((texture_view, &pipeline.sampler, binding.clone())),
+            ),
+            offset,
+            pipeline: pipelines.specialize(&pipeline_cache, &pipeline, image.texture_format),
+        });
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+struct GridShadowDecalLabel;
+
+struct GridShadowDecalNode;
+
+impl Node for GridShadowDecalNode {
+    fn run(
+        &self,
+        graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), bevy::render::render_graph::NodeRunError> {
+        let pipeline_cache = world.resource::<PipelineCache>();
+        // Like the shadow pass, the node runs once per camera.
+        let view_entity = graph.view_entity();
+        for blit in &world.resource::<GridShadowDecalBlits>().blits {
+            if blit.camera != view_entity {
+                continue;
+            }
+            let Some(pipeline) = pipeline_cache.get_render_pipeline(blit.pipeline) else {
+                continue;
+            };
+            let mut pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+                label: Some("grid_shadow_decal_pass"),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: &blit.target,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: LoadOp::Load,
+                        store: StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            pass.set_render_pipeline(pipeline);
+            pass.set_bind_group(0, &blit.bind_group, &[blit.offset]);
+            pass.draw(0..3, 0..1);
+        }
+
+        Ok(())
+    }
+}
+
+pub fn register_shadow_decal(app: &mut App) {
+    let mut shaders = app.world.resource_mut::<Assets<Shader>>();
+    shaders.get_or_insert_with(SHADOW_DECAL_SHADER_HANDLE, || {
+        Shader::from_wgsl(SHADOW_DECAL, file!())
+    });
+    shaders.get_or_insert_with(SHADOW_DECAL_MATERIAL_SHADER_HANDLE, || {
+        Shader::from_wgsl(SHADOW_DECAL_MATERIAL, file!())
+    });
+
+    let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+        return;
+    };
+    if !render_app.world.contains_resource::<GridShadowPipeline>() {
+        warn!("GridShadowDecalPlugin needs InfiniteGridPlugin, added before it");
+        return;
+    }
+    render_app
+        .init_resource::<GridShadowDecalPipeline>()
+        .init_resource::<GridShadowDecalUniforms>()
+        .init_resource::<GridShadowDecalBlits>()
+        .init_resource::<SpecializedRenderPipelines<GridShadowDecalPipeline>>()
+        .add_systems(ExtractSchedule, extract_shadow_decals)
+        .add_systems(
+            Render,
+            prepare_shadow_decal_blits.in_set(RenderSet::PrepareBindGroups),
+        );
+
+    let mut graph = render_app.world.resource_mut::<RenderGraph>();
+    let draw_3d_graph = graph.get_sub_graph_mut(Core3d).unwrap();
+    draw_3d_graph.add_node(GridShadowDecalLabel, GridShadowDecalNode);
+    // Copies the shadow once it's rendered, before the main pass draws the decal materials.
+    draw_3d_graph.add_node_edge(GridShadowPassLabel, GridShadowDecalLabel);
+    draw_3d_graph.add_node_edge(GridShadowDecalLabel, Node3d::StartMainPass);
+}
@@ -0,0 +1,21 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+struct GridShadowDecal {
+    // the decal's UV to the shadow texture's, an affine map as both lie on the grid plane
+    uv_x: vec2<f32>,
+    uv_y: vec2<f32>,
+    uv_offset: vec2<f32>,
+};
+
+@group(0) @binding(0) var shadow_texture: texture_2d<f32>;
+@group(0) @binding(1) var shadow_sampler: sampler;
+@group(0) @binding(2) var<uniform> decal: GridShadowDecal;
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let uv = decal.uv_offset + decal.uv_x * in.uv.x + decal.uv_y * in.uv.y;
+    let coverage = textureSample(shadow_texture, shadow_sampler, uv).r;
+    // no shadow past the shadow texture, rather than its clamped edge
+    let inside = all(uv >= vec2<f32>(0.)) && all(uv <= vec2<f32>(1.));
+    return vec4<f32>(vec3<f32>(select(0., coverage, inside)), 1.);
+}
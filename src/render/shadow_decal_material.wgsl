@@ -0,0 +1,51 @@
+#import bevy_pbr::{
+    pbr_fragment::pbr_input_from_standard_material,
+    pbr_functions::alpha_discard,
+}
+
+#ifdef PREPASS_PIPELINE
+#import bevy_pbr::{
+    prepass_io::{VertexOutput, FragmentOutput},
+    pbr_deferred_functions::deferred_output,
+}
+#else
+#import bevy_pbr::{
+    forward_io::{VertexOutput, FragmentOutput},
+    pbr_functions::{apply_pbr_lighting, main_pass_post_lighting_processing},
+}
+#endif
+
+struct GridShadowDecal {
+    // world position to the decal image's UV, in x and y
+    world_to_uv: mat4x4<f32>,
+    intensity: f32,
+};
+
+@group(2) @binding(100) var<uniform> decal: GridShadowDecal;
+@group(2) @binding(101) var decal_texture: texture_2d<f32>;
+@group(2) @binding(102) var decal_sampler: sampler;
+
+@fragment
+fn fragment(
+    in: VertexOutput,
+    @builtin(front_facing) is_front: bool,
+) -> FragmentOutput {
+    var pbr_input = pbr_input_from_standard_material(in, is_front);
+    pbr_input.material.base_color = alpha_discard(pbr_input.material, pbr_input.material.base_color);
+
+    // projected straight onto the grid plane, whatever the mesh's own UVs
+    let uv = (decal.world_to_uv * vec4<f32>(in.world_position.xyz, 1.)).xy;
+    let inside = all(uv >= vec2<f32>(0.)) && all(uv <= vec2<f32>(1.));
+    let coverage = select(0., textureSampleLevel(decal_texture, decal_sampler, uv, 0.).r, inside);
+    let base_color = pbr_input.material.base_color;
+    pbr_input.material.base_color = vec4<f32>(base_color.rgb * (1. - coverage * decal.intensity), base_color.a);
+
+#ifdef PREPASS_PIPELINE
+    let out = deferred_output(in, pbr_input);
+#else
+    var out: FragmentOutput;
+    out.color = apply_pbr_lighting(pbr_input);
+    out.color = main_pass_post_lighting_processing(pbr_input, out.color);
+#endif
+    return out;
+}
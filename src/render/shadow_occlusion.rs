@@ -0,0 +1,324 @@
+use std::{
+    borrow::Cow,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        Arc,
+    },
+};
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_resource::{
+            BindGroupEntries, BindGroupLayout, BindGroupLayoutEntry, BindingType, Buffer,
+            BufferBinding, BufferBindingType, BufferDescriptor, BufferSize, BufferUsages,
+            CachedComputePipelineId, CommandEncoderDescriptor, ComputePassDescriptor,
+            ComputePipelineDescriptor, Maintain, MapMode, PipelineCache, ShaderStages,
+            TextureSampleType, TextureViewDimension,
+        },
+        renderer::{RenderAdapter, RenderDevice, RenderQueue},
+        view::ExtractedView,
+        Render, RenderApp, RenderSet,
+    },
+    utils::HashMap,
+};
+use wgpu::DownlevelFlags;
+
+use crate::GridShadowOcclusion;
+
+use super::{GridShadowView, ViewGridShadows};
+
+static SHADOW_OCCLUSION: &str = include_str!("shadow_occlusion.wgsl");
+
+const SHADOW_OCCLUSION_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(13093842071664529017);
+
+/// Texels counted per workgroup along each axis, as in the shader.
+const WORKGROUP_SIZE: u32 = 16;
+
+const READBACK_PENDING: u8 = 0;
+const READBACK_MAPPED: u8 = 1;
+const READBACK_FAILED: u8 = 2;
+
+#[derive(Resource)]
+struct GridShadowOcclusionPipeline {
+    layout: BindGroupLayout,
+    pipeline: CachedComputePipelineId,
+}
+
+impl FromWorld for GridShadowOcclusionPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let layout = world.resource::<RenderDevice>().create_bind_group_layout(
+            "grid-shadow-occlusion-bind-group-layout",
+            &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: false },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: false },
+                        has_dynamic_offset: false,
+                        min_binding_size: BufferSize::new(4),
+                    },
+                    count: None,
+                },
+            ],
+        );
+        let pipeline =
+            world
+                .resource::<PipelineCache>()
+                .queue_compute_pipeline(ComputePipelineDescriptor {
+                    label: Some(Cow::Borrowed("grid-shadow-occlusion-pipeline")),
+                    layout: vec![layout.clone()],
+                    push_constant_ranges: Vec::new(),
+                    shader: SHADOW_OCCLUSION_SHADER_HANDLE,
+                    shader_defs: Vec::new(),
+                    entry_point: Cow::Borrowed("count_occluded"),
+                });
+        Self { layout, pipeline }
+    }
+}
+
+/// Grids measured in a frame, with their shadow texels and the counter slots of their
+/// textures.
+type MeasuredGrids = Vec<(Entity, u64, Vec<u32>)>;
+
+/// Counts the shadowed texels of the grid shadow textures for [`GridShadowOcclusion`].
+///
+/// Each texture counts into its own slot of `counts`, which is copied to `readback` and read
+/// while the next frames render. Those frames go unmeasured until it arrives.
+#[derive(Resource)]
+struct GridShadowOcclusionCounter {
+    occlusion: GridShadowOcclusion,
+    counts: Option<Buffer>,
+    readback_buffer: Option<Buffer>,
+    /// Slots the buffers hold.
+    capacity: u32,
+    /// Bytes between slots, as storage bindings need aligned offsets.
+    stride: u64,
+    readback: Option<(MeasuredGrids, Arc<AtomicU8>)>,
+}
+
+fn read_grid_shadow_occlusion(
+    mut counter: ResMut<GridShadowOcclusionCounter>,
+    render_device: Res<RenderDevice>,
+) {
+    let Some((grids, state)) = &counter.readback else {
+        return;
+    };
+    match state.load(Ordering::Acquire) {
+        READBACK_PENDING => {
+            render_device.poll(Maintain::Poll);
+        }
+        READBACK_MAPPED => {
+            let readback_buffer = counter.readback_buffer.as_ref().unwrap();
+            let fractions = {
+                let view = readback_buffer.slice(..).get_mapped_range();
+                grids
+                    .iter()
+                    .map(|(grid, texels, slots)| {
+                        let occluded: u64 = slots
+                            .iter()
+                            .map(|&slot| {
+                                let start = (u64::from(slot) * counter.stride) as usize;
+                                u64::from(u32::from_le_bytes(
+                                    view[start..start + 4].try_into().unwrap(),
+                                ))
+                            })
+                            .sum();
+                        (*grid, occluded as f32 / (*texels).max(1) as f32)
+                    })
+                    .collect()
+            };
+            readback_buffer.unmap();
+            counter.occlusion.set(fractions);
+            counter.readback = None;
+        }
+        _ => counter.readback = None,
+    }
+}
+
+// Runs after the render graph has been submitted, so this frame's shadows are all rendered, and
+// before the shadow views are cleared.
+#[allow(clippy::too_many_arguments)]
+fn count_grid_shadow_occlusion(
+    mut counter: ResMut<GridShadowOcclusionCounter>,
+    cameras: Query<&ViewGridShadows>,
+    shadow_views: Query<(&GridShadowView, &ExtractedView)>,
+    occlusion_pipeline: Res<GridShadowOcclusionPipeline>,
+    pipeline_cache: Res<PipelineCache>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+) {
+    if counter.readback.is_some() {
+        return;
+    }
+    let Some(pipeline) = pipeline_cache.get_compute_pipeline(occlusion_pipeline.pipeline) else {
+        return;
+    };
+
+    // Grids that share a shadow view, or are seen by several cameras, count its texture once.
+    let mut slots: HashMap<Entity, u32> = HashMap::new();
+    let mut textures = Vec::new();
+    let mut grids: HashMap<Entity, (u64, Vec<u32>)> = HashMap::new();
+    for view_shadows in cameras.iter() {
+        for (&grid, &view_entity) in view_shadows.shadows.iter() {
+            let Ok((shadow_view, view)) = shadow_views.get(view_entity) else {
+                continue;
+            };
+            let (texels, grid_slots) = grids.entry(grid).or_default();
+            *texels += u64::from(view.viewport.z) * u64::from(view.viewport.w);
+            // Nothing was rendered into an empty shadow, so none of it is shadowed.
+            if shadow_view.is_empty() {
+                continue;
+            }
+            let slot = *slots.entry(view_entity).or_insert_with(|| {
+                textures.push((shadow_view.texture_view(), view.viewport.zw()));
+                textures.len() as u32 - 1
+            });
+            grid_slots.push(slot);
+        }
+    }
+    if textures.is_empty() {
+        // No slot to read back, every measured grid is unshadowed.
+        counter
+            .occlusion
+            .set(grids.into_keys().map(|grid| (grid, 0.)).collect());
+        return;
+    }
+
+    let count = textures.len() as u32;
+    if counter.capacity < count || counter.counts.is_none() {
+        let capacity = count.next_power_of_two();
+        let size = u64::from(capacity) * counter.stride;
+        counter.counts = Some(render_device.create_buffer(&BufferDescriptor {
+            label: Some("grid_shadow_occlusion_counts"),
+            size,
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        }));
+        counter.readback_buffer = Some(render_device.create_buffer(&BufferDescriptor {
+            label: Some("grid_shadow_occlusion_readback_buffer"),
+            size,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        }));
+        counter.capacity = capacity;
+    }
+    let counts = counter.counts.as_ref().unwrap();
+    let readback_buffer = counter.readback_buffer.as_ref().unwrap();
+    let bytes = u64::from(count) * counter.stride;
+
+    let bind_groups: Vec<_> = textures
+        .iter()
+        .enumerate()
+        .map(|(slot, (texture_view, _))| {
+            render_device.create_bind_group(
+                "grid-shadow-occlusion-bind-group",
+                &occlusion_pipeline.layout,
+                &BindGroupEntries::sequential((
+                    *texture_view,
+                    BufferBinding {
+                        buffer: counts,
+                        offset: slot as u64 * counter.stride,
+                        size: BufferSize::new(4),
+                    },
+                )),
+            )
+        })
+        .collect();
+
+    let mut encoder = render_device.create_command_encoder(&CommandEncoderDescriptor {
+        label: Some("grid_shadow_occlusion_encoder"),
+    });
+    encoder.clear_buffer(counts, 0, Some(bytes));
+    {
+        let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor {
+            label: Some("grid_shadow_occlusion_pass"),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(pipeline);
+        for (bind_group, (_, size)) in bind_groups.iter().zip(&textures) {
+            pass.set_bind_group(0, bind_group, &[]);
+            pass.dispatch_workgroups(
+                size.x.div_ceil(WORKGROUP_SIZE),
+                size.y.div_ceil(WORKGROUP_SIZE),
+                1,
+            );
+        }
+    }
+    encoder.copy_buffer_to_buffer(counts, 0, readback_buffer, 0, bytes);
+    render_queue.submit([encoder.finish()]);
+
+    let state = Arc::new(AtomicU8::new(READBACK_PENDING));
+    let callback_state = state.clone();
+    readback_buffer
+        .slice(..)
+        .map_async(MapMode::Read, move |result| {
+            let mapped = if result.is_ok() {
+                READBACK_MAPPED
+            } else {
+                READBACK_FAILED
+            };
+            callback_state.store(mapped, Ordering::Release);
+        });
+    let grids = grids
+        .into_iter()
+        .map(|(grid, (texels, slots))| (grid, texels, slots))
+        .collect();
+    counter.readback = Some((grids, state));
+}
+
+pub fn register_shadow_occlusion(app: &mut App, occlusion: GridShadowOcclusion) {
+    app.world
+        .resource_mut::<Assets<Shader>>()
+        .get_or_insert_with(SHADOW_OCCLUSION_SHADER_HANDLE, || {
+            Shader::from_wgsl(SHADOW_OCCLUSION, file!())
+        });
+
+    let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+        return;
+    };
+    if !render_app
+        .world
+        .resource::<RenderAdapter>()
+        .get_downlevel_capabilities()
+        .flags
+        .contains(DownlevelFlags::COMPUTE_SHADERS)
+    {
+        warn!("Grid shadow occlusion needs compute shaders, which this device lacks");
+        return;
+    }
+    let stride = render_app
+        .world
+        .resource::<RenderDevice>()
+        .limits()
+        .min_storage_buffer_offset_alignment;
+    render_app
+        .insert_resource(GridShadowOcclusionCounter {
+            occlusion,
+            counts: None,
+            readback_buffer: None,
+            capacity: 0,
+            stride: u64::from(stride.max(4)),
+            readback: None,
+        })
+        .init_resource::<GridShadowOcclusionPipeline>()
+        .add_systems(
+            Render,
+            (
+                read_grid_shadow_occlusion.in_set(RenderSet::Prepare),
+                count_grid_shadow_occlusion
+                    .in_set(RenderSet::Cleanup)
+                    .before(World::clear_entities),
+            ),
+        );
+}
@@ -0,0 +1,20 @@
+@group(0) @binding(0) var shadow_texture: texture_2d<f32>;
+@group(0) @binding(1) var<storage, read_write> occluded: atomic<u32>;
+
+var<workgroup> workgroup_occluded: atomic<u32>;
+
+// Counts the texels with any shadow in them, one atomic per workgroup on the shared counter.
+@compute @workgroup_size(16, 16, 1)
+fn count_occluded(
+    @builtin(global_invocation_id) id: vec3<u32>,
+    @builtin(local_invocation_index) index: u32,
+) {
+    let size = textureDimensions(shadow_texture);
+    if all(id.xy < size) && textureLoad(shadow_texture, id.xy, 0).r > 0. {
+        atomicAdd(&workgroup_occluded, 1u);
+    }
+    workgroupBarrier();
+    if index == 0u {
+        atomicAdd(&occluded, atomicLoad(&workgroup_occluded));
+    }
+}
@@ -1,36 +1,81 @@
-#import bevy_pbr::mesh_functions::{mesh_position_local_to_clip, get_model_matrix}
-#import bevy_pbr::mesh_types::Mesh
+#import bevy_pbr::mesh_functions::{mesh_position_local_to_world, get_model_matrix}
 #import bevy_render::view::View
 
+struct GridShadowPass {
+    plane_origin: vec3<f32>,
+    plane_normal: vec3<f32>,
+    height_falloff: f32,
+};
+
+struct GridShadowCaster {
+    opacity: f32,
+};
+
 @group(0) @binding(0)
 var<uniform> view: View;
-
-@group(1) @binding(0)
-var<uniform> mesh: Mesh;
+@group(0) @binding(1)
+var<uniform> grid: GridShadowPass;
+@group(0) @binding(2)
+var<uniform> caster: GridShadowCaster;
 
 #ifdef SKINNED
-@group(1) @binding(1)
-var<uniform> joint_matrices: SkinnedMesh;
 #import bevy_pbr::skinning
 #endif
 
-#import bevy_pbr::pbr_bindings
+#ifdef MORPH_TARGETS
+#import bevy_pbr::morph
+#endif
+
+#ifdef ALPHA_MASK
+#import bevy_pbr::{pbr_bindings, pbr_types}
+#endif
 
 struct Vertex {
     @location(0) position: vec3<f32>,
+#ifdef ALPHA_MASK
+    @location(1) uv: vec2<f32>,
+#endif
 #ifdef SKINNED
     @location(4) joint_indices: vec4<u32>,
     @location(5) joint_weights: vec4<f32>,
+#endif
+#ifdef MORPH_TARGETS
+    @builtin(vertex_index) index: u32,
 #endif
     @builtin(instance_index) instance_index: u32,
 };
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
+    @location(0) world_position: vec3<f32>,
+#ifdef ALPHA_MASK
+    @location(1) uv: vec2<f32>,
+#endif
 };
 
+#ifdef MORPH_TARGETS
+fn morph_position(vertex: Vertex) -> vec3<f32> {
+    var position = vertex.position;
+    let weight_count = bevy_pbr::morph::layer_count();
+    for (var i: u32 = 0u; i < weight_count; i ++) {
+        let weight = bevy_pbr::morph::weight_at(i);
+        if weight == 0.0 {
+            continue;
+        }
+        position += weight * bevy_pbr::morph::morph(vertex.index, bevy_pbr::morph::position_offset, i);
+    }
+    return position;
+}
+#endif
+
 @vertex
 fn vertex(vertex: Vertex) -> VertexOutput {
+#ifdef MORPH_TARGETS
+    let position = morph_position(vertex);
+#else
+    let position = vertex.position;
+#endif
+
 #ifdef SKINNED
     let model = bevy_pbr::skinning::skin_model(vertex.joint_indices, vertex.joint_weights);
 #else
@@ -38,11 +83,28 @@ fn vertex(vertex: Vertex) -> VertexOutput {
 #endif
 
     var out: VertexOutput;
-    out.clip_position = mesh_position_local_to_clip(model, vec4(vertex.position, 1.0));
+    let world_position = mesh_position_local_to_world(model, vec4(position, 1.0));
+    out.clip_position = view.view_proj * world_position;
+    out.world_position = world_position.xyz;
+#ifdef ALPHA_MASK
+    out.uv = vertex.uv;
+#endif
     return out;
 }
 
+// Red holds coverage, scaled by the caster's opacity. Green, kept by two channel formats, holds 1 / (1 + height) so the max blend
+// keeps the closest caster in both.
 @fragment
-fn fragment() -> @location(0) f32 {
-    return 1.0;
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+#ifdef ALPHA_MASK
+    var alpha = pbr_bindings::material.base_color.a;
+    if (pbr_bindings::material.flags & pbr_types::STANDARD_MATERIAL_FLAGS_BASE_COLOR_TEXTURE_BIT) != 0u {
+        alpha *= textureSample(pbr_bindings::base_color_texture, pbr_bindings::base_color_sampler, in.uv).a;
+    }
+    if alpha < pbr_bindings::material.alpha_cutoff {
+        discard;
+    }
+#endif
+    let height = max(dot(in.world_position - grid.plane_origin, grid.plane_normal), 0.);
+    return vec4<f32>(caster.opacity * exp(-grid.height_falloff * height), 1. / (1. + height), 0., 1.);
 }
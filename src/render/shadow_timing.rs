@@ -0,0 +1,193 @@
+use std::{
+    sync::{
+        atomic::{AtomicU32, AtomicU8, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_resource::{
+            Buffer, BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Maintain, MapMode,
+        },
+        renderer::{RenderDevice, RenderQueue},
+        settings::WgpuFeatures,
+        Render, RenderApp, RenderSet,
+    },
+};
+use wgpu::{QuerySet, QuerySetDescriptor, QueryType, RenderPassTimestampWrites, QUERY_SIZE};
+
+use crate::diagnostics::GridRenderStats;
+
+/// Shadow passes timed per frame, passes past it go untimed.
+const MAX_TIMED_PASSES: u32 = 64;
+
+const READBACK_PENDING: u8 = 0;
+const READBACK_MAPPED: u8 = 1;
+const READBACK_FAILED: u8 = 2;
+
+/// Times the grid shadow passes with GPU timestamps, for
+/// `InfiniteGridDiagnosticsPlugin::SHADOW_PASS_GPU_MS`.
+///
+/// The timestamps of a frame are read back while the next frames render, and those frames go
+/// untimed until they arrive.
+#[derive(Resource)]
+pub(super) struct GridShadowGpuTimer {
+    query_set: QuerySet,
+    resolve_buffer: Buffer,
+    readback_buffer: Buffer,
+    /// Nanoseconds per timestamp tick.
+    period: f32,
+    /// Whether the passes of this frame are timed.
+    recording: bool,
+    /// Timestamps allocated this frame, two per pass.
+    written: AtomicU32,
+    /// Timestamps in the readback buffer, and the state of its mapping.
+    readback: Option<(u32, Arc<AtomicU8>)>,
+}
+
+impl GridShadowGpuTimer {
+    fn new(render_device: &RenderDevice, render_queue: &RenderQueue) -> Self {
+        let count = MAX_TIMED_PASSES * 2;
+        let size = (count * QUERY_SIZE) as u64;
+        Self {
+            query_set: render_device
+                .wgpu_device()
+                .create_query_set(&QuerySetDescriptor {
+                    label: Some("grid_shadow_timestamps"),
+                    ty: QueryType::Timestamp,
+                    count,
+                }),
+            resolve_buffer: render_device.create_buffer(&BufferDescriptor {
+                label: Some("grid_shadow_timestamp_resolve_buffer"),
+                size,
+                usage: BufferUsages::QUERY_RESOLVE | BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            }),
+            readback_buffer: render_device.create_buffer(&BufferDescriptor {
+                label: Some("grid_shadow_timestamp_readback_buffer"),
+                size,
+                usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }),
+            period: render_queue.get_timestamp_period(),
+            recording: false,
+            written: AtomicU32::new(0),
+            readback: None,
+        }
+    }
+
+    /// Timestamp writes for a shadow pass, if it's timed.
+    pub(super) fn timestamp_writes(&self) -> Option<RenderPassTimestampWrites<'_>> {
+        if !self.recording {
+            return None;
+        }
+        let index = self.written.fetch_add(2, Ordering::Relaxed);
+        (index < MAX_TIMED_PASSES * 2).then_some(RenderPassTimestampWrites {
+            query_set: &self.query_set,
+            beginning_of_pass_write_index: Some(index),
+            end_of_pass_write_index: Some(index + 1),
+        })
+    }
+}
+
+fn start_grid_shadow_timer(
+    mut timer: ResMut<GridShadowGpuTimer>,
+    render_device: Res<RenderDevice>,
+    stats: Res<GridRenderStats>,
+) {
+    if let Some((count, state)) = &timer.readback {
+        let count = *count;
+        match state.load(Ordering::Acquire) {
+            READBACK_PENDING => {
+                render_device.poll(Maintain::Poll);
+            }
+            READBACK_MAPPED => {
+                let bytes = (count * QUERY_SIZE) as u64;
+                let ticks: u64 = {
+                    let view = timer.readback_buffer.slice(..bytes).get_mapped_range();
+                    view.chunks_exact(2 * QUERY_SIZE as usize)
+                        .map(|pair| {
+                            let start = u64::from_le_bytes(pair[..8].try_into().unwrap());
+                            let end = u64::from_le_bytes(pair[8..].try_into().unwrap());
+                            end.saturating_sub(start)
+                        })
+                        .sum()
+                };
+                timer.readback_buffer.unmap();
+                stats.set_shadow_pass_gpu_time(Duration::from_nanos(
+                    (ticks as f64 * timer.period as f64) as u64,
+                ));
+                timer.readback = None;
+            }
+            _ => timer.readback = None,
+        }
+    }
+
+    timer.recording = timer.readback.is_none() && stats.gpu_timing_wanted();
+    *timer.written.get_mut() = 0;
+}
+
+// Runs after the render graph has been submitted, so the frame's timestamps are all written.
+fn resolve_grid_shadow_timer(
+    mut timer: ResMut<GridShadowGpuTimer>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    stats: Res<GridRenderStats>,
+) {
+    if !timer.recording {
+        return;
+    }
+    let count = (*timer.written.get_mut()).min(MAX_TIMED_PASSES * 2);
+    if count == 0 {
+        // No shadow was rendered, e.g. because all of them are cached.
+        stats.set_shadow_pass_gpu_time(Duration::ZERO);
+        return;
+    }
+
+    let bytes = (count * QUERY_SIZE) as u64;
+    let mut encoder = render_device.create_command_encoder(&CommandEncoderDescriptor {
+        label: Some("grid_shadow_timestamp_encoder"),
+    });
+    encoder.resolve_query_set(&timer.query_set, 0..count, &timer.resolve_buffer, 0);
+    encoder.copy_buffer_to_buffer(&timer.resolve_buffer, 0, &timer.readback_buffer, 0, bytes);
+    render_queue.submit([encoder.finish()]);
+
+    let state = Arc::new(AtomicU8::new(READBACK_PENDING));
+    let callback_state = state.clone();
+    timer
+        .readback_buffer
+        .slice(..bytes)
+        .map_async(MapMode::Read, move |result| {
+            let mapped = if result.is_ok() {
+                READBACK_MAPPED
+            } else {
+                READBACK_FAILED
+            };
+            callback_state.store(mapped, Ordering::Release);
+        });
+    timer.readback = Some((count, state));
+}
+
+pub fn register_shadow_timing(app: &mut App) {
+    let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+        return;
+    };
+    let render_device = render_app.world.resource::<RenderDevice>();
+    if !render_device
+        .features()
+        .contains(WgpuFeatures::TIMESTAMP_QUERY)
+    {
+        return;
+    }
+    let timer = GridShadowGpuTimer::new(render_device, render_app.world.resource::<RenderQueue>());
+    render_app.insert_resource(timer).add_systems(
+        Render,
+        (
+            start_grid_shadow_timer.in_set(RenderSet::Prepare),
+            resolve_grid_shadow_timer.in_set(RenderSet::Cleanup),
+        ),
+    );
+}
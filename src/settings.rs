@@ -0,0 +1,572 @@
+use std::fmt;
+
+use bevy::prelude::*;
+
+use crate::{FadeMode, GridShadowSettings, InfiniteGridSettings, LineStyle, SubGrid};
+
+impl InfiniteGridSettings {
+    pub fn builder() -> InfiniteGridSettingsBuilder {
+        Self::default().into_builder()
+    }
+
+    /// Starts a builder from these settings, e.g. to tweak a preset.
+    pub fn into_builder(self) -> InfiniteGridSettingsBuilder {
+        InfiniteGridSettingsBuilder { settings: self }
+    }
+
+    /// Blender's viewport floor: red X and green Y axes on dark gray lines.
+    pub fn blender() -> Self {
+        Self {
+            x_axis_color: Color::rgb_linear(0.9, 0.21, 0.33),
+            z_axis_color: Color::rgb_linear(0.54, 0.86, 0.0),
+            minor_line_color: Color::rgb_linear(0.19, 0.19, 0.19),
+            major_line_color: Color::rgb_linear(0.26, 0.26, 0.26),
+            ..default()
+        }
+    }
+
+    /// Unity's scene view grid: faint translucent gray lines.
+    pub fn unity() -> Self {
+        Self {
+            x_axis_color: Color::rgb_linear(0.86, 0.24, 0.2),
+            z_axis_color: Color::rgb_linear(0.2, 0.47, 0.86),
+            minor_line_color: Color::rgba_linear(0.5, 0.5, 0.5, 0.2),
+            major_line_color: Color::rgba_linear(0.5, 0.5, 0.5, 0.45),
+            fadeout_distance: 150.,
+            ..default()
+        }
+    }
+
+    /// Unreal's level viewport: near black minor lines with brighter majors.
+    pub fn unreal() -> Self {
+        Self {
+            x_axis_color: Color::rgb_linear(0.59, 0.07, 0.07),
+            z_axis_color: Color::rgb_linear(0.07, 0.38, 0.07),
+            minor_line_color: Color::rgb_linear(0.05, 0.05, 0.05),
+            major_line_color: Color::rgb_linear(0.16, 0.16, 0.16),
+            fadeout_distance: 200.,
+            ..default()
+        }
+    }
+
+    /// Light blue lines on white axes, meant for a dark blue clear color.
+    pub fn blueprint() -> Self {
+        Self {
+            x_axis_color: Color::WHITE,
+            z_axis_color: Color::WHITE,
+            shadow: None,
+            minor_line_color: Color::rgba_linear(0.45, 0.6, 0.85, 0.6),
+            major_line_color: Color::rgb_linear(0.8, 0.9, 1.0),
+            fadeout_distance: 150.,
+            ..default()
+        }
+    }
+
+    /// Former name of [`blueprint`](Self::blueprint).
+    #[deprecated(note = "renamed to `blueprint`")]
+    pub fn cad_blueprint() -> Self {
+        Self::blueprint()
+    }
+
+    /// Neutral grays with muted axes that stay out of the way of the scene.
+    pub fn dark_editor() -> Self {
+        Self {
+            x_axis_color: Color::rgb_linear(0.6, 0.3, 0.3),
+            z_axis_color: Color::rgb_linear(0.3, 0.4, 0.6),
+            minor_line_color: Color::rgba_linear(0.3, 0.3, 0.3, 0.5),
+            major_line_color: Color::rgba_linear(0.4, 0.4, 0.4, 0.8),
+            ..default()
+        }
+    }
+
+    /// Dark blue lines in tenths of a unit, meant for a light clear color.
+    pub fn graph_paper() -> Self {
+        Self {
+            x_axis_color: Color::rgb_linear(0.1, 0.1, 0.3),
+            z_axis_color: Color::rgb_linear(0.1, 0.1, 0.3),
+            minor_line_color: Color::rgba_linear(0.35, 0.5, 0.75, 0.35),
+            major_line_color: Color::rgba_linear(0.25, 0.4, 0.7, 0.8),
+            fadeout_distance: 40.,
+            scale: 10.,
+            ..default()
+        }
+    }
+
+    /// Cyan and magenta lines above 1 in linear space, which glow on an HDR camera with bloom.
+    pub fn neon() -> Self {
+        Self {
+            x_axis_color: Color::rgb_linear(4., 0.2, 2.),
+            z_axis_color: Color::rgb_linear(0.2, 2., 4.),
+            shadow: None,
+            minor_line_color: Color::rgb_linear(0., 0.1, 0.15),
+            major_line_color: Color::rgb_linear(0., 0.8, 1.2),
+            fadeout_distance: 200.,
+            ..default()
+        }
+    }
+}
+
+impl InfiniteGridSettings {
+    /// Blends from `self` at `t = 0` to `other` at `t = 1`. Colors blend in linear space, other
+    /// numbers linearly, and the rest (shadow or sub-grid on or off, pixel snapping, kernel,
+    /// projection) switches at 0.5.
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            x_axis_color: lerp_color(self.x_axis_color, other.x_axis_color, t),
+            z_axis_color: lerp_color(self.z_axis_color, other.z_axis_color, t),
+            shadow: match (self.shadow, other.shadow) {
+                (Some(a), Some(b)) => Some(a.lerp(&b, t)),
+                (a, b) => pick(a, b, t),
+            },
+            minor_line_color: lerp_color(self.minor_line_color, other.minor_line_color, t),
+            major_line_color: lerp_color(self.major_line_color, other.major_line_color, t),
+            fadeout_distance: self.fadeout_distance.lerp(other.fadeout_distance, t),
+            minor_fadeout_ratio: self.minor_fadeout_ratio.lerp(other.minor_fadeout_ratio, t),
+            dot_fadeout_strength: self
+                .dot_fadeout_strength
+                .lerp(other.dot_fadeout_strength, t),
+            scale: self.scale.lerp(other.scale, t),
+            aa_width: self.aa_width.lerp(other.aa_width, t),
+            plane_offset: self.plane_offset.lerp(other.plane_offset, t),
+            sub_grid: match (self.sub_grid, other.sub_grid) {
+                (Some(a), Some(b)) => Some(a.lerp(&b, t)),
+                (a, b) => pick(a, b, t),
+            },
+            pixel_snap: pick(self.pixel_snap, other.pixel_snap, t),
+            cull_underside: pick(self.cull_underside, other.cull_underside, t),
+            fade_mode: match (self.fade_mode, other.fade_mode) {
+                (
+                    FadeMode::RadialFromPoint { center: a },
+                    FadeMode::RadialFromPoint { center: b },
+                ) => FadeMode::RadialFromPoint {
+                    center: a.lerp(b, t),
+                },
+                (a, b) => pick(a, b, t),
+            },
+            x_lines_tint: lerp_color(self.x_lines_tint, other.x_lines_tint, t),
+            z_lines_tint: lerp_color(self.z_lines_tint, other.z_lines_tint, t),
+            glow: self.glow.lerp(other.glow, t),
+            highlight_axes: pick(self.highlight_axes, other.highlight_axes, t),
+            exclude_from_reflections: pick(
+                self.exclude_from_reflections,
+                other.exclude_from_reflections,
+                t,
+            ),
+            grid_origin_offset: self.grid_origin_offset.lerp(other.grid_origin_offset, t),
+            minor_line_style: self.minor_line_style.lerp(&other.minor_line_style, t),
+            major_line_style: self.major_line_style.lerp(&other.major_line_style, t),
+        }
+    }
+}
+
+impl LineStyle {
+    /// Blends like [`InfiniteGridSettings::lerp`], dashes into dashes.
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        match (*self, *other) {
+            (Self::Dashed { dash: a, gap: c }, Self::Dashed { dash: b, gap: d }) => Self::Dashed {
+                dash: a.lerp(b, t),
+                gap: c.lerp(d, t),
+            },
+            (a, b) => pick(a, b, t),
+        }
+    }
+}
+
+impl GridShadowSettings {
+    /// Blends like [`InfiniteGridSettings::lerp`].
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            color: lerp_color(self.color, other.color, t),
+            intensity: self.intensity.lerp(other.intensity, t),
+            falloff: self.falloff.lerp(other.falloff, t),
+            height_falloff: self.height_falloff.lerp(other.height_falloff, t),
+            softness: self.softness.lerp(other.softness, t),
+            height_softness: self.height_softness.lerp(other.height_softness, t),
+            max_softness: self.max_softness.lerp(other.max_softness, t),
+            pcf_size: pick(self.pcf_size, other.pcf_size, t),
+            pcf_kernel: pick(self.pcf_kernel, other.pcf_kernel, t),
+            resolution: pick(self.resolution, other.resolution, t),
+            cull_margin: self.cull_margin.lerp(other.cull_margin, t),
+            update_mode: pick(self.update_mode, other.update_mode, t),
+            projection_height: self.projection_height.lerp(other.projection_height, t),
+            near: self.near.lerp(other.near, t),
+            far: self.far.lerp(other.far, t),
+            projection: pick(self.projection, other.projection, t),
+            format: pick(self.format, other.format, t),
+            texel_snap: pick(self.texel_snap, other.texel_snap, t),
+            cascades: pick(self.cascades, other.cascades, t),
+            cascade_distance: self.cascade_distance.lerp(other.cascade_distance, t),
+            caster_filter: pick(self.caster_filter, other.caster_filter, t),
+            mode: pick(self.mode, other.mode, t),
+        }
+    }
+}
+
+impl SubGrid {
+    /// Blends like [`InfiniteGridSettings::lerp`].
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            color: lerp_color(self.color, other.color, t),
+            scale_ratio: self.scale_ratio.lerp(other.scale_ratio, t),
+            fadeout_ratio: self.fadeout_ratio.lerp(other.fadeout_ratio, t),
+        }
+    }
+}
+
+fn pick<T>(a: T, b: T, t: f32) -> T {
+    if t < 0.5 {
+        a
+    } else {
+        b
+    }
+}
+
+fn lerp_color(a: Color, b: Color, t: f32) -> Color {
+    let a = Vec4::from(a.as_linear_rgba_f32());
+    let b = Vec4::from(b.as_linear_rgba_f32());
+    Color::rgba_linear_from_array(a.lerp(b, t))
+}
+
+/// Blends the grid's [`InfiniteGridSettings`] from `from` to `to` over the timer, then removes
+/// itself, leaving `to`. Runs on virtual time, so pausing it pauses the blend.
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component)]
+pub struct GridSettingsTransition {
+    pub from: InfiniteGridSettings,
+    pub to: InfiniteGridSettings,
+    pub timer: Timer,
+}
+
+impl GridSettingsTransition {
+    pub fn new(from: InfiniteGridSettings, to: InfiniteGridSettings, seconds: f32) -> Self {
+        Self {
+            from,
+            to,
+            timer: Timer::from_seconds(seconds, TimerMode::Once),
+        }
+    }
+}
+
+pub(crate) fn grid_settings_transition_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut grids: Query<(
+        Entity,
+        &mut InfiniteGridSettings,
+        &mut GridSettingsTransition,
+    )>,
+) {
+    for (entity, mut settings, mut transition) in grids.iter_mut() {
+        transition.timer.tick(time.delta());
+        *settings = transition
+            .from
+            .lerp(&transition.to, transition.timer.fraction());
+        if transition.timer.finished() {
+            commands.entity(entity).remove::<GridSettingsTransition>();
+        }
+    }
+}
+
+/// Chainable construction of [`InfiniteGridSettings`], checked by [`build`](Self::build).
+#[derive(Clone, Copy)]
+pub struct InfiniteGridSettingsBuilder {
+    settings: InfiniteGridSettings,
+}
+
+impl InfiniteGridSettingsBuilder {
+    pub fn x_axis_color(mut self, color: Color) -> Self {
+        self.settings.x_axis_color = color;
+        self
+    }
+
+    pub fn z_axis_color(mut self, color: Color) -> Self {
+        self.settings.z_axis_color = color;
+        self
+    }
+
+    pub fn shadow(mut self, shadow: Option<GridShadowSettings>) -> Self {
+        self.settings.shadow = shadow;
+        self
+    }
+
+    pub fn minor_line_color(mut self, color: Color) -> Self {
+        self.settings.minor_line_color = color;
+        self
+    }
+
+    pub fn major_line_color(mut self, color: Color) -> Self {
+        self.settings.major_line_color = color;
+        self
+    }
+
+    pub fn fadeout_distance(mut self, distance: f32) -> Self {
+        self.settings.fadeout_distance = distance;
+        self
+    }
+
+    pub fn minor_fadeout_ratio(mut self, ratio: f32) -> Self {
+        self.settings.minor_fadeout_ratio = ratio;
+        self
+    }
+
+    pub fn dot_fadeout_strength(mut self, strength: f32) -> Self {
+        self.settings.dot_fadeout_strength = strength;
+        self
+    }
+
+    /// Lines per world unit.
+    pub fn scale(mut self, scale: f32) -> Self {
+        self.settings.scale = scale;
+        self
+    }
+
+    /// World units between minor lines, the inverse of [`scale`](Self::scale).
+    pub fn spacing(mut self, spacing: f32) -> Self {
+        self.settings.scale = 1. / spacing;
+        self
+    }
+
+    /// Antialiasing band width in pixels.
+    pub fn aa_width(mut self, width: f32) -> Self {
+        self.settings.aa_width = width;
+        self
+    }
+
+    pub fn plane_offset(mut self, offset: f32) -> Self {
+        self.settings.plane_offset = offset;
+        self
+    }
+
+    pub fn sub_grid(mut self, sub_grid: Option<SubGrid>) -> Self {
+        self.settings.sub_grid = sub_grid;
+        self
+    }
+
+    pub fn pixel_snap(mut self, pixel_snap: bool) -> Self {
+        self.settings.pixel_snap = pixel_snap;
+        self
+    }
+
+    pub fn cull_underside(mut self, cull_underside: bool) -> Self {
+        self.settings.cull_underside = cull_underside;
+        self
+    }
+
+    pub fn fade_mode(mut self, fade_mode: FadeMode) -> Self {
+        self.settings.fade_mode = fade_mode;
+        self
+    }
+
+    /// Tints of the lines running along the grid's X and Z axes.
+    pub fn line_tints(mut self, x_lines: Color, z_lines: Color) -> Self {
+        self.settings.x_lines_tint = x_lines;
+        self.settings.z_lines_tint = z_lines;
+        self
+    }
+
+    /// Halo width in pixels, 0 for none.
+    pub fn glow(mut self, width: f32) -> Self {
+        self.settings.glow = width;
+        self
+    }
+
+    pub fn highlight_axes(mut self, highlight_axes: bool) -> Self {
+        self.settings.highlight_axes = highlight_axes;
+        self
+    }
+
+    pub fn exclude_from_reflections(mut self, exclude: bool) -> Self {
+        self.settings.exclude_from_reflections = exclude;
+        self
+    }
+
+    /// Grid space point the axes cross at.
+    pub fn grid_origin_offset(mut self, offset: Vec2) -> Self {
+        self.settings.grid_origin_offset = offset;
+        self
+    }
+
+    /// Styles of the minor and major lines.
+    pub fn line_styles(mut self, minor: LineStyle, major: LineStyle) -> Self {
+        self.settings.minor_line_style = minor;
+        self.settings.major_line_style = major;
+        self
+    }
+
+    pub fn build(self) -> Result<InfiniteGridSettings, InfiniteGridSettingsError> {
+        let settings = self.settings;
+        let positive = |value: f32| value.is_finite() && value > 0.;
+
+        if !positive(settings.scale) {
+            return Err(InfiniteGridSettingsError::Scale(settings.scale));
+        }
+        if !positive(settings.fadeout_distance) {
+            return Err(InfiniteGridSettingsError::FadeoutDistance(
+                settings.fadeout_distance,
+            ));
+        }
+        if !positive(settings.minor_fadeout_ratio) {
+            return Err(InfiniteGridSettingsError::MinorFadeoutRatio(
+                settings.minor_fadeout_ratio,
+            ));
+        }
+        if !positive(settings.dot_fadeout_strength) {
+            return Err(InfiniteGridSettingsError::DotFadeoutStrength(
+                settings.dot_fadeout_strength,
+            ));
+        }
+        if !positive(settings.aa_width) {
+            return Err(InfiniteGridSettingsError::AaWidth(settings.aa_width));
+        }
+        if let Some(sub_grid) = settings.sub_grid {
+            if !positive(sub_grid.scale_ratio) {
+                return Err(InfiniteGridSettingsError::SubGridScaleRatio(
+                    sub_grid.scale_ratio,
+                ));
+            }
+            if !positive(sub_grid.fadeout_ratio) {
+                return Err(InfiniteGridSettingsError::SubGridFadeoutRatio(
+                    sub_grid.fadeout_ratio,
+                ));
+            }
+        }
+        Ok(settings)
+    }
+}
+
+/// A setting that the shader divides by was zero, negative or not finite.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InfiniteGridSettingsError {
+    Scale(f32),
+    FadeoutDistance(f32),
+    MinorFadeoutRatio(f32),
+    DotFadeoutStrength(f32),
+    AaWidth(f32),
+    SubGridScaleRatio(f32),
+    SubGridFadeoutRatio(f32),
+}
+
+impl fmt::Display for InfiniteGridSettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name, value) = match self {
+            Self::Scale(value) => ("scale", value),
+            Self::FadeoutDistance(value) => ("fadeout_distance", value),
+            Self::MinorFadeoutRatio(value) => ("minor_fadeout_ratio", value),
+            Self::DotFadeoutStrength(value) => ("dot_fadeout_strength", value),
+            Self::AaWidth(value) => ("aa_width", value),
+            Self::SubGridScaleRatio(value) => ("sub_grid.scale_ratio", value),
+            Self::SubGridFadeoutRatio(value) => ("sub_grid.fadeout_ratio", value),
+        };
+        write!(f, "{name} must be a positive number, got {value}")
+    }
+}
+
+impl std::error::Error for InfiniteGridSettingsError {}
+
+#[cfg(test)]
+mod tests {
+    use std::mem::discriminant;
+
+    use super::*;
+
+    fn presets() -> [(&'static str, InfiniteGridSettings); 8] {
+        [
+            ("default", InfiniteGridSettings::default()),
+            ("blender", InfiniteGridSettings::blender()),
+            ("unity", InfiniteGridSettings::unity()),
+            ("unreal", InfiniteGridSettings::unreal()),
+            ("blueprint", InfiniteGridSettings::blueprint()),
+            ("dark_editor", InfiniteGridSettings::dark_editor()),
+            ("graph_paper", InfiniteGridSettings::graph_paper()),
+            ("neon", InfiniteGridSettings::neon()),
+        ]
+    }
+
+    #[test]
+    fn presets_build() {
+        for (name, preset) in presets() {
+            assert!(preset.into_builder().build().is_ok(), "{name}");
+        }
+    }
+
+    fn colors(settings: &InfiniteGridSettings) -> [Color; 4] {
+        [
+            settings.x_axis_color,
+            settings.z_axis_color,
+            settings.minor_line_color,
+            settings.major_line_color,
+        ]
+    }
+
+    #[test]
+    fn preset_colors_differ() {
+        let presets = presets();
+        for (i, (name, preset)) in presets.iter().enumerate() {
+            for (other_name, other) in &presets[i + 1..] {
+                assert_ne!(colors(preset), colors(other), "{name} and {other_name}");
+            }
+        }
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn cad_blueprint_is_blueprint() {
+        assert_eq!(
+            colors(&InfiniteGridSettings::cad_blueprint()),
+            colors(&InfiniteGridSettings::blueprint())
+        );
+    }
+
+    #[test]
+    fn nonsense_values_are_rejected() {
+        use InfiniteGridSettingsError as Error;
+
+        for value in [0., -1., f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            let builder = InfiniteGridSettings::builder;
+            // NaN never equals itself, so only the variants are compared.
+            let check = |result: Result<InfiniteGridSettings, Error>,
+                         expected: fn(f32) -> Error| {
+                let Err(error) = result else {
+                    panic!("{value} was accepted");
+                };
+                assert_eq!(
+                    discriminant(&error),
+                    discriminant(&expected(value)),
+                    "{value}"
+                );
+            };
+            check(builder().scale(value).build(), Error::Scale);
+            check(
+                builder().fadeout_distance(value).build(),
+                Error::FadeoutDistance,
+            );
+            check(
+                builder().minor_fadeout_ratio(value).build(),
+                Error::MinorFadeoutRatio,
+            );
+            check(
+                builder().dot_fadeout_strength(value).build(),
+                Error::DotFadeoutStrength,
+            );
+            check(builder().aa_width(value).build(), Error::AaWidth);
+            check(
+                builder()
+                    .sub_grid(Some(SubGrid {
+                        scale_ratio: value,
+                        ..default()
+                    }))
+                    .build(),
+                Error::SubGridScaleRatio,
+            );
+            check(
+                builder()
+                    .sub_grid(Some(SubGrid {
+                        fadeout_ratio: value,
+                        ..default()
+                    }))
+                    .build(),
+                Error::SubGridFadeoutRatio,
+            );
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use bevy::{
+    pbr::{ExtendedMaterial, MaterialExtension},
+    prelude::*,
+    render::render_resource::{AsBindGroup, ShaderRef},
+};
+
+use crate::render::SHADOW_DECAL_MATERIAL_SHADER_HANDLE;
+
+/// Lets meshes of your own, e.g. a floor, receive grid shadows through
+/// [`GridShadowDecalMaterial`]s.
+///
+/// Needs [`InfiniteGridPlugin`] with shadows.
+///
+/// [`InfiniteGridPlugin`]: crate::InfiniteGridPlugin
+#[derive(Default)]
+pub struct GridShadowDecalPlugin;
+
+impl Plugin for GridShadowDecalPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<GridShadowDecal>()
+            .add_plugins(MaterialPlugin::<GridShadowDecalMaterial>::default());
+    }
+
+    fn finish(&self, app: &mut App) {
+        crate::render::register_shadow_decal(app);
+    }
+}
+
+/// Copies the grid's shadow into `image` every frame, as a decal over a fixed part of the grid
+/// that other materials can sample, see [`GridShadowDecalExtension`].
+///
+/// The image covers the `extent` grid space units from `origin`, like a [`GridHeatmap`]: its
+/// first row along `origin` and its first column along the grid's X axis. It holds the shadow
+/// coverage in red, 0 where the shadow texture doesn't reach. It keeps its last content while
+/// the grid has no shadow, e.g. while hidden.
+///
+/// The image needs `TextureUsages::RENDER_ATTACHMENT` and `TEXTURE_BINDING`. `camera` picks
+/// whose shadow to copy, any shadow camera's when `None`. Needs [`GridShadowDecalPlugin`].
+///
+/// [`GridHeatmap`]: crate::GridHeatmap
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component, Default)]
+pub struct GridShadowDecal {
+    pub image: Handle<Image>,
+    pub origin: Vec2,
+    pub extent: Vec2,
+    pub camera: Option<Entity>,
+}
+
+impl Default for GridShadowDecal {
+    fn default() -> Self {
+        Self {
+            image: Handle::default(),
+            origin: Vec2::splat(-10.),
+            extent: Vec2::splat(20.),
+            camera: None,
+        }
+    }
+}
+
+/// A `StandardMaterial` darkened by a grid's shadow.
+pub type GridShadowDecalMaterial = ExtendedMaterial<StandardMaterial, GridShadowDecalExtension>;
+
+/// Samples a [`GridShadowDecal`] image, darkening the base color by the shadow coverage times
+/// `intensity`.
+///
+/// Fragments are projected straight onto the grid plane, by `world_to_uv` from their world
+/// position to the image's UV in X and Y, so the mesh's own UVs don't matter. Outside the image
+/// nothing changes. Build it with [`new`](Self::new), and again if the grid or the decal moves.
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone)]
+pub struct GridShadowDecalExtension {
+    #[uniform(100)]
+    pub world_to_uv: Mat4,
+    #[uniform(100)]
+    pub intensity: f32,
+    #[texture(101)]
+    #[sampler(102)]
+    pub image: Handle<Image>,
+}
+
+impl GridShadowDecalExtension {
+    /// Projects `decal` as laid over the grid at `grid_transform`.
+    pub fn new(decal: &GridShadowDecal, grid_transform: &GlobalTransform, intensity: f32) -> Self {
+        let extent = decal.extent.recip();
+        // Grid space X and Z to UV.
+        let grid_to_uv = Mat4::from_cols(
+            Vec4::new(extent.x, 0., 0., 0.),
+            Vec4::ZERO,
+            Vec4::new(0., extent.y, 0., 0.),
+            (-decal.origin * extent).extend(0.).extend(1.),
+        );
+        Self {
+            world_to_uv: grid_to_uv * grid_transform.compute_matrix().inverse(),
+            intensity,
+            image: decal.image.clone(),
+        }
+    }
+}
+
+impl MaterialExtension for GridShadowDecalExtension {
+    fn fragment_shader() -> ShaderRef {
+        SHADOW_DECAL_MATERIAL_SHADER_HANDLE.into()
+    }
+
+    fn deferred_fragment_shader() -> ShaderRef {
+        SHADOW_DECAL_MATERIAL_SHADER_HANDLE.into()
+    }
+}
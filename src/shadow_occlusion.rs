@@ -0,0 +1,51 @@
+use std::sync::{Arc, Mutex};
+
+use bevy::{prelude::*, utils::HashMap};
+
+/// Measures how much of each grid's shadow is shadowed, into [`GridShadowOcclusion`].
+///
+/// Every frame a compute pass counts the shadowed texels of the grids' shadow textures, and
+/// the counts are read back without waiting on the GPU. Needs [`InfiniteGridPlugin`] with
+/// shadows, and a device with compute shaders, so not WebGL2.
+///
+/// [`InfiniteGridPlugin`]: crate::InfiniteGridPlugin
+#[derive(Default)]
+pub struct GridShadowOcclusionPlugin;
+
+impl Plugin for GridShadowOcclusionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GridShadowOcclusion>();
+    }
+
+    fn finish(&self, app: &mut App) {
+        let occlusion = app.world.resource::<GridShadowOcclusion>().clone();
+        crate::render::register_shadow_occlusion(app, occlusion);
+    }
+}
+
+/// The fraction of each grid's shadow texture with shadow in it, from 0 to 1, e.g. for
+/// "how much of the floor is in the shade" mechanics. Filled by [`GridShadowOcclusionPlugin`].
+///
+/// The shadow textures cover the part of the grid the [`GridShadowCamera`]s see, so that is
+/// the area measured. With several shadow cameras their textures are added up. Values trail
+/// the rendered frame by a frame or more.
+///
+/// [`GridShadowCamera`]: crate::GridShadowCamera
+#[derive(Resource, Clone, Default)]
+pub struct GridShadowOcclusion(Arc<Mutex<HashMap<Entity, f32>>>);
+
+impl GridShadowOcclusion {
+    /// The grid's occluded fraction, `None` if it has no shadow or wasn't measured yet.
+    pub fn fraction(&self, grid: Entity) -> Option<f32> {
+        self.0.lock().unwrap().get(&grid).copied()
+    }
+
+    /// The occluded fraction of every measured grid.
+    pub fn fractions(&self) -> HashMap<Entity, f32> {
+        self.0.lock().unwrap().clone()
+    }
+
+    pub(crate) fn set(&self, fractions: HashMap<Entity, f32>) {
+        *self.0.lock().unwrap() = fractions;
+    }
+}
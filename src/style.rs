@@ -0,0 +1,156 @@
+use std::fmt;
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::prelude::*;
+use bevy::utils::BoxedFuture;
+use serde::{Deserialize, Serialize};
+
+use crate::InfiniteGridSettings;
+
+pub(crate) fn build(app: &mut App) {
+    app.init_asset::<GridStyleAsset>()
+        .init_asset_loader::<GridStyleAssetLoader>()
+        .add_systems(PostUpdate, apply_grid_styles);
+}
+
+/// Grid settings loaded from a `.gridstyle.ron` file.
+///
+/// Add a `Handle<GridStyleAsset>` or a [`GridSettingsHandle`] to a grid entity and its
+/// [`InfiniteGridSettings`] follow the file, including hot reloads. Fields missing from the file
+/// keep their default values.
+#[derive(Asset, TypePath, Clone, Copy, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct GridStyleAsset(pub InfiniteGridSettings);
+
+/// Another name for [`GridStyleAsset`].
+pub type GridSettingsAsset = GridStyleAsset;
+
+/// Makes a grid's [`InfiniteGridSettings`] follow a [`GridStyleAsset`], like a bare
+/// `Handle<GridStyleAsset>` does. The bare handle wins when an entity has both.
+#[derive(Component, Clone, Default, Deref, DerefMut)]
+pub struct GridSettingsHandle(pub Handle<GridStyleAsset>);
+
+#[derive(Default)]
+pub struct GridStyleAssetLoader;
+
+#[derive(Debug)]
+pub enum GridStyleAssetLoaderError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+}
+
+impl fmt::Display for GridStyleAssetLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "could not read grid style: {err}"),
+            Self::Ron(err) => write!(f, "invalid grid style: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for GridStyleAssetLoaderError {}
+
+impl From<std::io::Error> for GridStyleAssetLoaderError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for GridStyleAssetLoaderError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        Self::Ron(err)
+    }
+}
+
+impl AssetLoader for GridStyleAssetLoader {
+    type Asset = GridStyleAsset;
+    type Settings = ();
+    type Error = GridStyleAssetLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            Ok(ron::de::from_bytes(&bytes)?)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["gridstyle.ron"]
+    }
+}
+
+// A file that fails to (re)load never replaces the asset, so grids simply keep their
+// previous settings and the asset server logs the loader error.
+fn apply_grid_styles(
+    mut events: EventReader<AssetEvent<GridStyleAsset>>,
+    styles: Res<Assets<GridStyleAsset>>,
+    mut grids: Query<(Ref<Handle<GridStyleAsset>>, &mut InfiniteGridSettings)>,
+    mut wrapped: Query<
+        (Ref<GridSettingsHandle>, &mut InfiniteGridSettings),
+        Without<Handle<GridStyleAsset>>,
+    >,
+) {
+    let changed: Vec<_> = events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::Added { id }
+            | AssetEvent::Modified { id }
+            | AssetEvent::LoadedWithDependencies { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+
+    let handles = grids
+        .iter_mut()
+        .map(|(handle, settings)| (handle.is_changed(), handle.id(), settings))
+        .chain(
+            wrapped
+                .iter_mut()
+                .map(|(handle, settings)| (handle.is_changed(), handle.id(), settings)),
+        );
+    for (handle_changed, id, mut settings) in handles {
+        if !handle_changed && !changed.contains(&id) {
+            continue;
+        }
+        if let Some(style) = styles.get(id) {
+            *settings = style.0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_handle_applies_the_style() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()));
+        build(&mut app);
+
+        let style = GridStyleAsset(InfiniteGridSettings {
+            scale: 4.,
+            ..default()
+        });
+        let handle = app
+            .world
+            .resource_mut::<Assets<GridStyleAsset>>()
+            .add(style);
+        let grid = app
+            .world
+            .spawn((InfiniteGridSettings::default(), GridSettingsHandle(handle)))
+            .id();
+        app.update();
+        assert_eq!(
+            app.world.get::<InfiniteGridSettings>(grid).unwrap().scale,
+            4.
+        );
+    }
+}
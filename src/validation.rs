@@ -0,0 +1,81 @@
+use bevy::math::Vec4Swizzles;
+use bevy::prelude::*;
+use bevy::render::view::RenderLayers;
+
+use crate::{camera_sees_grid, InfiniteGrid, NoInfiniteGrid};
+
+/// Set to turn the checks on in builds without the `debug` feature.
+const ENV_FLAG: &str = "BEVY_INFINITE_GRID_DEBUG";
+
+/// Frames to wait after a grid appears, so cameras spawned a little later don't trip the checks.
+const GRACE_FRAMES: u32 = 60;
+
+pub(crate) fn build(app: &mut App) {
+    if cfg!(feature = "debug") || std::env::var_os(ENV_FLAG).is_some() {
+        app.add_systems(Last, warn_unseen_grids);
+    }
+}
+
+// Warns once, it's meant to catch setup mistakes rather than to follow the scene.
+#[allow(clippy::type_complexity)]
+fn warn_unseen_grids(
+    mut frames: Local<u32>,
+    mut warned: Local<bool>,
+    grids: Query<(&GlobalTransform, Option<&RenderLayers>), With<InfiniteGrid>>,
+    cameras: Query<
+        (
+            &Camera,
+            &GlobalTransform,
+            Option<&RenderLayers>,
+            Has<NoInfiniteGrid>,
+        ),
+        Or<(With<Camera3d>, With<Camera2d>)>,
+    >,
+) {
+    if *warned || grids.is_empty() {
+        return;
+    }
+    *frames += 1;
+    if *frames < GRACE_FRAMES {
+        return;
+    }
+    *warned = true;
+
+    let mut cameras = cameras
+        .iter()
+        .filter(|(camera, ..)| camera.is_active)
+        .peekable();
+    if cameras.peek().is_none() {
+        warn!("an InfiniteGrid exists, but there is no active Camera3d or Camera2d to draw it");
+        return;
+    }
+    let seen = cameras.any(|(camera, camera_transform, camera_layers, no_grid)| {
+        grids.iter().any(|(grid, grid_layers)| {
+            camera_sees_grid(camera_layers, no_grid, grid_layers)
+                && faces_plane(camera_transform, camera.projection_matrix(), grid)
+        })
+    });
+    if !seen {
+        warn!(
+            "no active camera can see an InfiniteGrid, check that one looks towards the grid's \
+            plane, shares a RenderLayers layer with it and has no NoInfiniteGrid"
+        );
+    }
+}
+
+// The view frustum is the cone spanned by its corner rays, so it reaches the plane when one of
+// them does.
+fn faces_plane(camera: &GlobalTransform, projection: Mat4, grid: &GlobalTransform) -> bool {
+    let unproject = camera.compute_matrix() * projection.inverse();
+    let normal = grid.up();
+    [(1., 1.), (1., -1.), (-1., -1.), (-1., 1.)]
+        .into_iter()
+        .any(|(x, y)| {
+            let near = unproject * Vec4::new(x, y, 1., 1.);
+            let near = near.xyz() / near.w;
+            let far = unproject * Vec4::new(x, y, 0.001, 1.);
+            let far = far.xyz() / far.w;
+            let height = (near - grid.translation()).dot(normal);
+            (far - near).dot(normal) * height < 0.
+        })
+}
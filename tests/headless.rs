@@ -0,0 +1,327 @@
+//! Renders frames without a window. They need a GPU adapter, a software one like lavapipe or
+//! llvmpipe does, and pass with a note on stderr where there is none. `WGPU_BACKEND` picks the
+//! backend, e.g. `gl` for llvmpipe:
+//!
+//! ```sh
+//! WGPU_BACKEND=gl cargo test --test headless
+//! ```
+
+use std::sync::Arc;
+
+use bevy::diagnostic::DiagnosticsStore;
+use bevy::{
+    log::LogPlugin,
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+        renderer::{initialize_renderer, RenderInstance},
+        settings::{RenderCreation, WgpuSettings},
+        RenderPlugin,
+    },
+    tasks::block_on,
+    window::ExitCondition,
+    winit::WinitPlugin,
+};
+use bevy_infinite_grid::{
+    GridFrustumIntersect, GridShadowCamera, InfiniteGridBundle, InfiniteGridDiagnosticsPlugin,
+    InfiniteGridPlugin,
+};
+#[cfg(feature = "shadows")]
+use bevy_infinite_grid::{GridShadowCaster, InfiniteGridSettings};
+
+/// An app with the grid plugin and no window, or `None` without an adapter to render with.
+fn headless_app() -> Option<App> {
+    // The renderer is created here rather than by `RenderPlugin`, which panics without an
+    // adapter. The instance is handed over too, dropping it would take OpenGL's display along.
+    let settings = WgpuSettings::default();
+    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+        backends: settings.backends?,
+        flags: settings.instance_flags,
+        dx12_shader_compiler: settings.dx12_shader_compiler.clone(),
+        gles_minor_version: settings.gles3_minor_version,
+    });
+    let options = wgpu::RequestAdapterOptions {
+        power_preference: settings.power_preference,
+        ..default()
+    };
+    if block_on(instance.request_adapter(&options)).is_none() {
+        eprintln!("skipped, no GPU adapter");
+        return None;
+    }
+    let (device, queue, adapter_info, adapter) =
+        block_on(initialize_renderer(&instance, &settings, &options));
+
+    let mut app = App::new();
+    app.add_plugins((
+        DefaultPlugins
+            .build()
+            .disable::<WinitPlugin>()
+            // Tests share the process, and with it the global logger.
+            .disable::<LogPlugin>()
+            .set(WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                close_when_requested: false,
+            })
+            .set(RenderPlugin {
+                render_creation: RenderCreation::manual(
+                    device,
+                    queue,
+                    adapter_info,
+                    adapter,
+                    RenderInstance(Arc::new(instance)),
+                ),
+                ..default()
+            }),
+        InfiniteGridPlugin::default(),
+        InfiniteGridDiagnosticsPlugin,
+    ));
+    app.finish();
+    app.cleanup();
+    Some(app)
+}
+
+/// A camera rendering to an image, as there is no window. The main world keeps the image too,
+/// the camera would lose its target after the first frame otherwise.
+fn spawn_camera(app: &mut App, transform: Transform) -> Entity {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: 256,
+            height: 256,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0; 4],
+        TextureFormat::Bgra8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.texture_descriptor.usage |= TextureUsages::RENDER_ATTACHMENT;
+    let target = app.world.resource_mut::<Assets<Image>>().add(image);
+    app.world
+        .spawn((
+            Camera3dBundle {
+                camera: Camera {
+                    target: RenderTarget::Image(target),
+                    ..default()
+                },
+                transform,
+                ..default()
+            },
+            GridShadowCamera,
+        ))
+        .id()
+}
+
+#[test]
+fn first_frames_render_without_panicking() {
+    let Some(mut app) = headless_app() else {
+        return;
+    };
+    let mesh = app
+        .world
+        .resource_mut::<Assets<Mesh>>()
+        .add(Cuboid::new(1., 1., 1.));
+    let material = app
+        .world
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial::default());
+
+    app.world.spawn(InfiniteGridBundle::default());
+    spawn_camera(
+        &mut app,
+        Transform::from_xyz(0., 4., 10.).looking_at(Vec3::ZERO, Vec3::Y),
+    );
+    app.world.spawn(DirectionalLightBundle::default());
+    app.world.spawn(PbrBundle {
+        mesh,
+        material,
+        transform: Transform::from_xyz(0., 2., 0.),
+        ..default()
+    });
+
+    // The first frames queue shadows while their pipelines still compile.
+    for _ in 0..10 {
+        app.update();
+    }
+}
+
+/// The latest value of one of the grid's diagnostics.
+fn diagnostic(app: &App, path: &bevy::diagnostic::DiagnosticPath) -> Option<f64> {
+    app.world
+        .resource::<DiagnosticsStore>()
+        .get(path)
+        .and_then(|diagnostic| diagnostic.value())
+}
+
+#[test]
+fn default_bundle_renders() {
+    let Some(mut app) = headless_app() else {
+        return;
+    };
+    let grid = app.world.spawn(InfiniteGridBundle::default()).id();
+    spawn_camera(
+        &mut app,
+        Transform::from_xyz(0., 4., 10.).looking_at(Vec3::ZERO, Vec3::Y),
+    );
+
+    // The render world's counts reach the diagnostics a frame late.
+    for _ in 0..2 {
+        app.update();
+    }
+    assert_eq!(
+        diagnostic(&app, &InfiniteGridDiagnosticsPlugin::GRIDS),
+        Some(1.)
+    );
+    assert!(!app
+        .world
+        .get::<GridFrustumIntersect>(grid)
+        .unwrap()
+        .is_empty());
+}
+
+// Every frame one grid is replaced by a new one, and now and then another loses or regains its
+// settings. Once all grids are gone, nothing of theirs may be left in the render world.
+#[test]
+#[cfg(feature = "shadows")]
+fn grid_churn_leaves_nothing_behind() {
+    let Some(mut app) = headless_app() else {
+        return;
+    };
+    let mesh = app
+        .world
+        .resource_mut::<Assets<Mesh>>()
+        .add(Cuboid::new(1., 1., 1.));
+    spawn_camera(
+        &mut app,
+        Transform::from_xyz(0., 4., 10.).looking_at(Vec3::ZERO, Vec3::Y),
+    );
+    app.world.spawn(DirectionalLightBundle::default());
+    app.world.spawn(PbrBundle {
+        mesh,
+        transform: Transform::from_xyz(0., 2., 0.),
+        ..default()
+    });
+    let toggled = app.world.spawn(InfiniteGridBundle::default()).id();
+
+    let mut churned = None;
+    let mut most_bind_groups = 0.;
+    for frame in 0..300 {
+        if let Some(grid) = churned {
+            app.world.despawn(grid);
+        }
+        churned = Some(
+            app.world
+                .spawn(InfiniteGridBundle {
+                    transform: Transform::from_xyz(0., -1. - (frame % 10) as f32 * 0.1, 0.),
+                    ..default()
+                })
+                .id(),
+        );
+        if frame % 30 == 29 {
+            let mut toggled = app.world.entity_mut(toggled);
+            if toggled.contains::<InfiniteGridSettings>() {
+                toggled.remove::<InfiniteGridSettings>();
+            } else {
+                toggled.insert(InfiniteGridSettings::default());
+            }
+        }
+        app.update();
+        if let Some(bind_groups) =
+            diagnostic(&app, &InfiniteGridDiagnosticsPlugin::SHADOW_BIND_GROUPS)
+        {
+            most_bind_groups = f64::max(most_bind_groups, bind_groups);
+        }
+    }
+    // One shadow for each of the two grids, not one for every grid ever spawned.
+    assert!(
+        (1. ..=2.).contains(&most_bind_groups),
+        "{most_bind_groups} bind groups"
+    );
+
+    app.world.despawn(churned.unwrap());
+    app.world.despawn(toggled);
+    for _ in 0..2 {
+        app.update();
+    }
+    for path in [
+        InfiniteGridDiagnosticsPlugin::GRIDS,
+        InfiniteGridDiagnosticsPlugin::SHADOW_TEXTURE_BYTES,
+        InfiniteGridDiagnosticsPlugin::SHADOW_BIND_GROUPS,
+    ] {
+        assert_eq!(diagnostic(&app, &path), Some(0.), "{path}");
+    }
+}
+
+#[test]
+#[cfg(feature = "shadows")]
+fn identical_casters_are_batched() {
+    let Some(mut app) = headless_app() else {
+        return;
+    };
+    let mesh = app
+        .world
+        .resource_mut::<Assets<Mesh>>()
+        .add(Cuboid::new(1., 1., 1.));
+    let material = app
+        .world
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial::default());
+
+    app.world.spawn(InfiniteGridBundle::default());
+    spawn_camera(
+        &mut app,
+        Transform::from_xyz(0., 4., 10.).looking_at(Vec3::ZERO, Vec3::Y),
+    );
+    app.world.spawn(DirectionalLightBundle::default());
+    for x in -4..4 {
+        app.world.spawn(PbrBundle {
+            mesh: mesh.clone(),
+            material: material.clone(),
+            transform: Transform::from_xyz(x as f32 * 1.5, 2., 0.),
+            ..default()
+        });
+    }
+
+    // The casters are queued once their mesh and material reach the render world.
+    let queued = (0..30).any(|_| {
+        app.update();
+        diagnostic(&app, &InfiniteGridDiagnosticsPlugin::SHADOW_CASTERS) == Some(8.)
+    });
+    assert!(queued, "the casters were never queued");
+    // Casters sharing a mesh and material go into the same instanced draw.
+    let draws = diagnostic(&app, &InfiniteGridDiagnosticsPlugin::SHADOW_DRAWS).unwrap();
+    assert!(draws < 8., "{draws} draws for 8 casters");
+}
+
+// Meshes drawn by a pipeline of their own have no material, and cast grid shadows all the same.
+#[test]
+#[cfg(feature = "shadows")]
+fn material_free_casters_cast() {
+    let Some(mut app) = headless_app() else {
+        return;
+    };
+    let mesh = app
+        .world
+        .resource_mut::<Assets<Mesh>>()
+        .add(Cuboid::new(1., 1., 1.));
+
+    app.world.spawn(InfiniteGridBundle::default());
+    spawn_camera(
+        &mut app,
+        Transform::from_xyz(0., 4., 10.).looking_at(Vec3::ZERO, Vec3::Y),
+    );
+    app.world.spawn(DirectionalLightBundle::default());
+    app.world.spawn((
+        mesh,
+        SpatialBundle::from_transform(Transform::from_xyz(0., 2., 0.)),
+        GridShadowCaster,
+    ));
+
+    let queued = (0..30).any(|_| {
+        app.update();
+        diagnostic(&app, &InfiniteGridDiagnosticsPlugin::SHADOW_CASTERS) == Some(1.)
+    });
+    assert!(queued, "the caster was never queued");
+}